@@ -0,0 +1,265 @@
+// 扫描取消与超时 - 每次流式扫描登记一个可取消的句柄
+//
+// 句柄传入扫描器与图标提取，在目录遍历和逐项处理的循环中检查，取消或超时后尽快返回；
+// 扫描中启动的子进程在句柄触发时结束。超时由派生句柄的截止时间表示，
+// 扫描器与每个图标源各自派生一个。无法检查句柄的阻塞调用（单个图标的提取）
+// 在独立线程中执行，超时后不再等待，不会拖住 rayon 线程池与后续的扫描。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, Instant};
+
+// 等待时检查取消标记的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static NEXT_SCAN_ID: AtomicU64 = AtomicU64::new(1);
+static SCANS: OnceLock<Mutex<HashMap<u64, Weak<ScanState>>>> = OnceLock::new();
+
+fn scans() -> &'static Mutex<HashMap<u64, Weak<ScanState>>> {
+    SCANS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 一次扫描的取消标记，最后一个句柄释放时注销
+struct ScanState {
+    id: u64,
+    cancelled: AtomicBool,
+}
+
+impl Drop for ScanState {
+    fn drop(&mut self) {
+        if self.id == 0 {
+            return;
+        }
+        if let Ok(mut scans) = scans().lock() {
+            scans.remove(&self.id);
+        }
+    }
+}
+
+/// 扫描的取消句柄，可复制后传入各线程；派生的句柄共享取消标记并带有截止时间
+#[derive(Clone)]
+pub struct ScanToken {
+    state: Arc<ScanState>,
+    deadline: Option<Instant>,
+}
+
+impl ScanToken {
+    /// 登记一次新的扫描
+    pub fn begin() -> Self {
+        let id = NEXT_SCAN_ID.fetch_add(1, Ordering::Relaxed);
+        let state = Arc::new(ScanState {
+            id,
+            cancelled: AtomicBool::new(false),
+        });
+        if let Ok(mut scans) = scans().lock() {
+            scans.insert(id, Arc::downgrade(&state));
+        }
+        ScanToken {
+            state,
+            deadline: None,
+        }
+    }
+
+    /// 不可取消的句柄，用于一次性返回结果的命令
+    pub fn uncancellable() -> Self {
+        ScanToken {
+            state: Arc::new(ScanState {
+                id: 0,
+                cancelled: AtomicBool::new(false),
+            }),
+            deadline: None,
+        }
+    }
+
+    /// 派生一个从现在起 timeout 后到期的句柄，不晚于自身的截止时间
+    pub fn with_timeout(&self, timeout: Option<Duration>) -> Self {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        ScanToken {
+            state: self.state.clone(),
+            deadline: match (self.deadline, deadline) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.state.id
+    }
+
+    /// 扫描被取消
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// 已过截止时间
+    pub fn is_expired(&self) -> bool {
+        self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+
+    /// 被取消或超时，工作应尽快结束
+    pub fn should_stop(&self) -> bool {
+        self.is_cancelled() || self.is_expired()
+    }
+
+    /// 距截止时间的剩余时间，没有截止时间时为 None
+    fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|d| d.saturating_duration_since(Instant::now()))
+    }
+}
+
+/// 取消指定的扫描，扫描不存在（已结束）时返回 false
+pub fn cancel_scan(id: u64) -> bool {
+    let Some(state) = scans()
+        .lock()
+        .ok()
+        .and_then(|s| s.get(&id).and_then(Weak::upgrade))
+    else {
+        return false;
+    };
+    state.cancelled.store(true, Ordering::Relaxed);
+    true
+}
+
+/// 受限执行的结果
+pub enum Guarded<T> {
+    Done(T),
+    TimedOut,
+    Cancelled,
+    /// 线程异常退出
+    Panicked,
+}
+
+/// 各自在独立线程中并行执行 jobs，每个 job 收到一个 timeout 后到期的派生句柄；
+/// 等待到全部完成、超时或扫描被取消，每个 job 结束（或被放弃）时以其序号调用 on_done
+pub fn run_guarded_all<T: Send + 'static>(
+    jobs: Vec<Box<dyn FnOnce(ScanToken) -> T + Send>>,
+    timeout: Option<Duration>,
+    token: &ScanToken,
+    mut on_done: impl FnMut(usize, Guarded<T>),
+) {
+    let token = token.with_timeout(timeout);
+    let (tx, rx) = channel();
+    let mut pending: Vec<bool> = vec![true; jobs.len()];
+    for (index, job) in jobs.into_iter().enumerate() {
+        let tx = tx.clone();
+        let job_token = token.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send((index, job(job_token)));
+        });
+    }
+    drop(tx);
+
+    let abandoned: Guarded<()> = loop {
+        if !pending.contains(&true) {
+            return;
+        }
+        if token.is_cancelled() {
+            break Guarded::Cancelled;
+        }
+        let wait = match token.remaining() {
+            Some(left) if left.is_zero() => break Guarded::TimedOut,
+            Some(left) => left.min(POLL_INTERVAL),
+            None => POLL_INTERVAL,
+        };
+        match rx.recv_timeout(wait) {
+            // 到期后才返回的结果可能不完整
+            Ok((index, _)) if token.should_stop() => {
+                pending[index] = false;
+                on_done(
+                    index,
+                    if token.is_cancelled() {
+                        Guarded::Cancelled
+                    } else {
+                        Guarded::TimedOut
+                    },
+                );
+            }
+            Ok((index, value)) => {
+                pending[index] = false;
+                on_done(index, Guarded::Done(value));
+            }
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break Guarded::Panicked,
+        }
+    };
+
+    // 其余的 job 已收到取消或超时，不再等待
+    for (index, _) in pending.iter().enumerate().filter(|(_, p)| **p) {
+        on_done(
+            index,
+            match &abandoned {
+                Guarded::Cancelled => Guarded::Cancelled,
+                Guarded::TimedOut => Guarded::TimedOut,
+                _ => Guarded::Panicked,
+            },
+        );
+    }
+}
+
+/// 在独立线程中执行无法检查句柄的阻塞调用，等待到完成、超时或扫描被取消
+pub fn run_guarded<T: Send + 'static>(
+    job: impl FnOnce() -> T + Send + 'static,
+    timeout: Option<Duration>,
+    token: &ScanToken,
+) -> Guarded<T> {
+    let mut result = Guarded::Panicked;
+    run_guarded_all(
+        vec![Box::new(move |_| job())],
+        timeout,
+        token,
+        |_, outcome| result = outcome,
+    );
+    result
+}
+
+/// 执行外部命令并收集输出，句柄被取消或超时时结束子进程
+#[cfg(target_os = "macos")]
+pub fn run_command(
+    command: &mut std::process::Command,
+    token: &ScanToken,
+) -> std::io::Result<std::process::Output> {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // 管道需要持续读取，否则输出较多时子进程会阻塞在写入上
+    let read_pipe = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = read_pipe(child.stdout.take().map(|p| Box::new(p) as _));
+    let stderr = read_pipe(child.stderr.take().map(|p| Box::new(p) as _));
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if token.should_stop() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Interrupted,
+                "扫描已取消或超时，已结束子进程",
+            ));
+        }
+        std::thread::sleep(POLL_INTERVAL.min(token.remaining().unwrap_or(POLL_INTERVAL)));
+    };
+    Ok(std::process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
@@ -0,0 +1,237 @@
+// 当前目录 - 最近一次扫描的结果，供变化监视增量更新
+//
+// 扫描命令记录各来源的原始条目与最终条目。来源变化时只重新扫描该来源，
+// 与其余来源的缓存结果重新合并；未变化的条目沿用已提取的图标与可执行信息，
+// 其余条目重新补全，与上次结果比较得到新增、更新与删除的条目（按身份键对应）。
+// 扫描与补全在锁外进行，期间目录被新的扫描替换时放弃这次结果。
+
+use crate::cancel::ScanToken;
+use crate::config::scan_settings;
+use crate::identity::identity_keys;
+use crate::sources::merge::MergeEngine;
+use crate::sources::scanner::ScanStatus;
+use crate::sources::{fill_executable_info, fill_icons, get_all_scanners, run_scanners_with};
+use crate::types::DesktopIcon;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+struct Catalog {
+    /// 每次记录目录时递增
+    generation: u64,
+    method: Option<String>,
+    /// 是否按来源优先级合并（单一来源的扫描不合并）
+    merge: bool,
+    /// 各来源的原始条目
+    sources: Vec<(String, Vec<DesktopIcon>)>,
+    /// 补全前与补全后的条目，按身份键索引
+    entries: HashMap<String, (DesktopIcon, DesktopIcon)>,
+}
+
+/// 目录的变化，随 catalog-changed 事件发送
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CatalogChange {
+    pub added: Vec<DesktopIcon>,
+    pub updated: Vec<CatalogUpdate>,
+    /// 已删除条目的 file_path
+    pub removed: Vec<String>,
+}
+
+/// 更新的条目，file_path 可能随之变化
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogUpdate {
+    /// 更新前的 file_path
+    pub previous_file_path: String,
+    pub icon: DesktopIcon,
+}
+
+impl CatalogChange {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+static CATALOG: OnceLock<Mutex<Option<Catalog>>> = OnceLock::new();
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+// 同一时间只进行一次重新扫描
+static RESCAN: Mutex<()> = Mutex::new(());
+
+fn catalog_lock() -> &'static Mutex<Option<Catalog>> {
+    CATALOG.get_or_init(|| Mutex::new(None))
+}
+
+/// 合并（或直接拼接）各来源的条目，补全可执行信息与图标，结果记录为当前目录
+pub fn build_catalog(
+    sources: Vec<(String, Vec<DesktopIcon>)>,
+    merge: bool,
+    method: Option<&str>,
+) -> Vec<DesktopIcon> {
+    let raw = combine_sources(&sources, merge);
+    let mut icons = raw.clone();
+    let token = ScanToken::uncancellable();
+    fill_executable_info(&mut icons, &token, |_, _| {});
+    fill_icons(&mut icons, method, &token);
+    record_catalog(sources, merge, method, raw, &icons);
+    icons
+}
+
+/// 记录当前目录：各来源的原始条目、合并后补全前的条目与补全后的条目（两者一一对应）
+pub fn record_catalog(
+    sources: Vec<(String, Vec<DesktopIcon>)>,
+    merge: bool,
+    method: Option<&str>,
+    raw: Vec<DesktopIcon>,
+    icons: &[DesktopIcon],
+) {
+    let entries = entry_keys(&raw)
+        .into_iter()
+        .zip(raw.into_iter().zip(icons.iter().cloned()))
+        .collect();
+    if let Ok(mut catalog) = catalog_lock().lock() {
+        *catalog = Some(Catalog {
+            generation: GENERATION.fetch_add(1, Ordering::Relaxed) + 1,
+            method: method.map(str::to_string),
+            merge,
+            sources,
+            entries,
+        });
+    }
+}
+
+/// 当前目录包含的来源
+pub fn catalog_sources() -> Vec<String> {
+    catalog_lock()
+        .lock()
+        .ok()
+        .and_then(|catalog| {
+            catalog
+                .as_ref()
+                .map(|c| c.sources.iter().map(|(id, _)| id.clone()).collect())
+        })
+        .unwrap_or_default()
+}
+
+/// 重新扫描指定来源并更新当前目录，返回与上次结果相比的变化
+pub fn rescan_sources(source_ids: &[String]) -> Option<CatalogChange> {
+    let _rescan = RESCAN.lock().ok()?;
+    let (generation, method, merge, mut sources) = {
+        let guard = catalog_lock().lock().ok()?;
+        let catalog = guard.as_ref()?;
+        (
+            catalog.generation,
+            catalog.method.clone(),
+            catalog.merge,
+            catalog.sources.clone(),
+        )
+    };
+
+    // 重新扫描与补全可执行信息不超过扫描器超时，各扫描器在独立线程中执行
+    let token = ScanToken::uncancellable().with_timeout(scan_settings().scanner_timeout());
+    let scanners: Vec<_> = get_all_scanners()
+        .into_iter()
+        .filter(|s| source_ids.iter().any(|id| id == s.id()))
+        .collect();
+    let mut rescanned: HashMap<String, (Vec<DesktopIcon>, ScanStatus)> =
+        run_scanners_with(scanners, method.as_deref(), &token, |_, _, _| {})
+            .into_iter()
+            .map(|(id, icons, status)| (id, (icons, status)))
+            .collect();
+    for (id, icons) in sources.iter_mut() {
+        if !source_ids.contains(id) {
+            continue;
+        }
+        // 来源已不可用（如目录被删除）时清空，失败或超时时保留上次的条目
+        *icons = match rescanned.remove(id) {
+            Some((found, ScanStatus::Ok)) => found,
+            Some(_) => continue,
+            None => Vec::new(),
+        };
+    }
+    let raws = combine_sources(&sources, merge);
+    let keys = entry_keys(&raws);
+
+    // 未变化的条目留在目录中，其余取出补全；上次的 file_path 为 None 表示新增
+    let mut pending: Vec<(String, DesktopIcon, Option<String>)> = Vec::new();
+    let removed = {
+        let mut guard = catalog_lock().lock().ok()?;
+        let catalog = guard.as_mut().filter(|c| c.generation == generation)?;
+        let mut previous = std::mem::take(&mut catalog.entries);
+        for (key, raw) in keys.into_iter().zip(raws) {
+            match previous.remove(&key) {
+                Some((old_raw, icon)) if same_entry(&old_raw, &raw) => {
+                    catalog.entries.insert(key, (old_raw, icon));
+                }
+                old => pending.push((key, raw, old.map(|(_, icon)| icon.file_path))),
+            }
+        }
+        catalog.sources = sources;
+        previous
+            .into_values()
+            .map(|(_, icon)| icon.file_path)
+            .collect()
+    };
+
+    let mut icons: Vec<DesktopIcon> = pending.iter().map(|(_, raw, _)| raw.clone()).collect();
+    fill_executable_info(&mut icons, &token, |_, _| {});
+    // 每个图标源各自受图标提取超时限制，不受重新扫描的截止时间影响
+    fill_icons(&mut icons, method.as_deref(), &ScanToken::uncancellable());
+
+    let mut change = CatalogChange {
+        removed,
+        ..Default::default()
+    };
+    let mut guard = catalog_lock().lock().ok()?;
+    let catalog = guard.as_mut().filter(|c| c.generation == generation)?;
+    for ((key, raw, previous_file_path), icon) in pending.into_iter().zip(icons) {
+        match previous_file_path {
+            Some(previous_file_path) => change.updated.push(CatalogUpdate {
+                previous_file_path,
+                icon: icon.clone(),
+            }),
+            None => change.added.push(icon.clone()),
+        }
+        catalog.entries.insert(key, (raw, icon));
+    }
+    Some(change)
+}
+
+/// 各条目在目录中的键：第一个身份键，不合并时可能重复，重复的再加上 file_path 区分
+fn entry_keys(icons: &[DesktopIcon]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    icons
+        .iter()
+        .map(|icon| {
+            let mut key = identity_keys(icon).swap_remove(0);
+            if !seen.insert(key.clone()) {
+                key = format!("{}\u{1f}{}", key, icon.file_path);
+                seen.insert(key.clone());
+            }
+            key
+        })
+        .collect()
+}
+
+/// 按来源优先级合并各来源的条目，不合并时按来源顺序拼接
+pub fn combine_sources(sources: &[(String, Vec<DesktopIcon>)], merge: bool) -> Vec<DesktopIcon> {
+    if !merge {
+        return sources
+            .iter()
+            .flat_map(|(_, icons)| icons.iter().cloned())
+            .collect();
+    }
+    let mut engine = MergeEngine::from_settings();
+    for (source_id, icons) in sources {
+        engine.add(source_id, icons.clone());
+    }
+    engine.finish()
+}
+
+/// 比较两个条目，忽略访问时间（扫描本身会更新它）
+fn same_entry(a: &DesktopIcon, b: &DesktopIcon) -> bool {
+    let without_access = |icon: &DesktopIcon| DesktopIcon {
+        accessed_time: None,
+        ..icon.clone()
+    };
+    without_access(a) == without_access(b)
+}
@@ -1,15 +1,24 @@
 // Tauri 命令模块
 
-use crate::path::normalize_path;
+use crate::cancel::ScanToken;
+#[cfg(target_os = "windows")]
+use crate::catalog::build_catalog;
+use crate::config::{scan_settings, ScanSettings};
+use crate::registry::uninstall::{read_uninstall_entries, visible_entries, UninstallEntry};
+use crate::registry::{self, RegistrySet};
+use crate::rules::{clear_rule_hits, default_rules, rule_hits, FilterRule, RuleHitSummary};
 #[cfg(target_os = "windows")]
 use crate::sources::desktop::{
     get_desktop_icons as get_user_desktop_icons, get_public_desktop_icons,
 };
 use crate::sources::{
-    fill_icons, get_all_icons, get_all_scanners, get_icons_from_source as get_source_icons,
-    IconSource,
+    get_all_icons, get_all_scanners, reset_scanner_availability, select_scanners,
 };
+use crate::stream::{stream_scan, ScanEvent, ScanReport};
 use crate::types::DesktopIcon;
+use crate::uninstall::{UninstallOutcome, UninstallTarget};
+use tauri::ipc::Channel;
+use tauri::AppHandle;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -23,42 +32,17 @@ pub async fn get_desktop_icons() -> std::result::Result<Vec<DesktopIcon>, String
     println!("收到前端调用 get_desktop_icons 命令");
 
     let handle = tokio::task::spawn_blocking(move || -> Vec<DesktopIcon> {
-        let mut all_icons = Vec::new();
-        let mut seen_targets = std::collections::HashSet::new();
-
+        let token = ScanToken::uncancellable();
+        let mut sources = Vec::new();
         // 获取用户桌面图标
-        if let Ok(icons) = get_user_desktop_icons(None) {
-            for icon in icons {
-                let name = icon.name.trim().to_lowercase();
-                let target = if cfg!(target_os = "windows") {
-                    normalize_path(&icon.target_path).to_lowercase()
-                } else {
-                    icon.target_path.to_lowercase()
-                };
-                let fingerprint = format!("{}:{}", name, target);
-                if seen_targets.insert(fingerprint) {
-                    all_icons.push(icon);
-                }
-            }
+        if let Ok(icons) = get_user_desktop_icons(None, &token) {
+            sources.push(("desktop".to_string(), icons));
         }
-
         // 获取公共桌面图标
-        if let Ok(icons) = get_public_desktop_icons(None) {
-            for icon in icons {
-                let name = icon.name.trim().to_lowercase();
-                let target = if cfg!(target_os = "windows") {
-                    normalize_path(&icon.target_path).to_lowercase()
-                } else {
-                    icon.target_path.to_lowercase()
-                };
-                let fingerprint = format!("{}:{}", name, target);
-                if seen_targets.insert(fingerprint) {
-                    all_icons.push(icon);
-                }
-            }
+        if let Ok(icons) = get_public_desktop_icons(None, &token) {
+            sources.push(("public_desktop".to_string(), icons));
         }
-        fill_icons(&mut all_icons, None);
-        all_icons
+        build_catalog(sources, true, None)
     });
 
     let all_icons: Vec<DesktopIcon> = handle.await.map_err(|e| e.to_string())?;
@@ -78,42 +62,17 @@ pub async fn get_desktop_icons_with_method(
 
     let method_clone = method.clone();
     let handle = tokio::task::spawn_blocking(move || -> Vec<DesktopIcon> {
-        let mut all_icons = Vec::new();
-        let mut seen_targets = std::collections::HashSet::new();
-
+        let token = ScanToken::uncancellable();
+        let mut sources = Vec::new();
         // 获取用户桌面图标
-        if let Ok(icons) = get_user_desktop_icons(None) {
-            for icon in icons {
-                let name = icon.name.trim().to_lowercase();
-                let target = if cfg!(target_os = "windows") {
-                    normalize_path(&icon.target_path).to_lowercase()
-                } else {
-                    icon.target_path.to_lowercase()
-                };
-                let fingerprint = format!("{}:{}", name, target);
-                if seen_targets.insert(fingerprint) {
-                    all_icons.push(icon);
-                }
-            }
+        if let Ok(icons) = get_user_desktop_icons(None, &token) {
+            sources.push(("desktop".to_string(), icons));
         }
-
         // 获取公共桌面图标
-        if let Ok(icons) = get_public_desktop_icons(None) {
-            for icon in icons {
-                let name = icon.name.trim().to_lowercase();
-                let target = if cfg!(target_os = "windows") {
-                    normalize_path(&icon.target_path).to_lowercase()
-                } else {
-                    icon.target_path.to_lowercase()
-                };
-                let fingerprint = format!("{}:{}", name, target);
-                if seen_targets.insert(fingerprint) {
-                    all_icons.push(icon);
-                }
-            }
+        if let Ok(icons) = get_public_desktop_icons(None, &token) {
+            sources.push(("public_desktop".to_string(), icons));
         }
-        fill_icons(&mut all_icons, Some(&method));
-        all_icons
+        build_catalog(sources, true, Some(&method))
     });
 
     let all_icons: Vec<DesktopIcon> = handle.await.map_err(|e| e.to_string())?;
@@ -189,54 +148,6 @@ pub fn get_available_icon_methods() -> Vec<serde_json::Value> {
 
 // ========== 软件来源命令 ==========
 
-/// 从指定来源获取图标
-#[tauri::command]
-pub async fn get_icons_from_source(
-    source: String,
-    method: Option<String>,
-) -> std::result::Result<Vec<DesktopIcon>, String> {
-    println!(
-        "[Backend] 收到 get_icons_from_source 命令, 来源: {}, 方式: {:?}",
-        source, method
-    );
-    let start = std::time::Instant::now();
-
-    let source_clone = source.clone();
-    let method_clone = method.clone();
-    let handle =
-        tokio::task::spawn_blocking(move || -> std::result::Result<Vec<DesktopIcon>, String> {
-            let icon_source = IconSource::from_str(&source)
-                .ok_or_else(|| format!("无效的软件来源: {}", source))?;
-
-            let mut icons =
-                get_source_icons(icon_source, method.as_deref()).map_err(|e| e.to_string())?;
-            fill_icons(&mut icons, method.as_deref());
-            Ok(icons)
-        });
-
-    let result: std::result::Result<Vec<DesktopIcon>, String> =
-        handle.await.map_err(|e| e.to_string())?;
-
-    let duration = start.elapsed();
-    match &result {
-        Ok(icons) => println!(
-            "[Backend] 从 {} 成功返回 {} 个图标, 方式: {:?}, 耗时: {:.3}s",
-            source_clone,
-            icons.len(),
-            method_clone,
-            duration.as_secs_f64()
-        ),
-        Err(e) => eprintln!(
-            "[Backend] 从 {} 返回错误: {}, 方式: {:?}, 耗时: {:.3}s",
-            source_clone,
-            e,
-            method_clone,
-            duration.as_secs_f64()
-        ),
-    }
-    result
-}
-
 /// 获取所有来源的图标（去重汇总）
 #[tauri::command]
 pub async fn get_all_source_icons(
@@ -275,201 +186,67 @@ pub async fn get_all_source_icons(
     result
 }
 
-/// 从多个来源获取图标（支持多选）
+/// 扫描指定来源（未指定时扫描全部来源），通过通道逐步发送结果与进度，返回扫描报告
+///
+/// 通道的第一个事件带有扫描 ID，可通过 cancel_scan 取消。
 #[tauri::command]
-pub async fn get_icons_from_multiple_sources(
-    sources: Vec<String>,
+pub async fn stream_source_icons(
+    sources: Option<Vec<String>>,
     method: Option<String>,
-) -> std::result::Result<Vec<DesktopIcon>, String> {
+    on_event: Channel<ScanEvent>,
+) -> std::result::Result<ScanReport, String> {
     println!(
-        "[Backend] 收到 get_icons_from_multiple_sources 命令, 来源: {:?}, 方式: {:?}",
+        "[Backend] 收到 stream_source_icons 命令, 来源: {:?}, 方式: {:?}",
         sources, method
     );
-    let start = std::time::Instant::now();
 
-    let _sources_clone = sources.clone();
     let method_clone = method.clone();
-    let handle =
-        tokio::task::spawn_blocking(move || -> std::result::Result<Vec<DesktopIcon>, String> {
-            // 定义来源优先级函数
-            let get_priority = |source_id: &str| -> i32 {
-                match source_id {
-                    "uwp_apps" => 100,
-                    "taskbar_pinned" => 90,
-                    "start_menu" => 85,
-                    "common_start_menu" => 80,
-                    "desktop" => 75,
-                    "public_desktop" => 70,
-                    "quick_launch" => 65,
-                    "appdata_programs" => 60,
-                    "installed_programs" => 50,
-                    "program_files" => 40,
-                    "program_files_x86" => 35,
-                    "applications" => 90,        // macOS
-                    "system_applications" => 80, // macOS
-                    "user_applications" => 85,   // macOS
-                    _ => 50,
-                }
-            };
-
-            // 如果在 macOS 下 sources 全全是 Windows 来源，则执行全量扫描
-            let actual_sources = sources.clone();
-            #[cfg(target_os = "macos")]
-            {
-                let has_mac_source = actual_sources.iter().any(|s| {
-                    s == "applications"
-                        || s == "system_applications"
-                        || s == "user_applications"
-                        || s == "spotlight"
-                        || s == "core_services"
-                });
-
-                if !has_mac_source {
-                    println!("[Backend] 检测到 macOS 环境但来源不匹配，自动执行全量扫描");
-                    return get_all_icons(method.as_deref()).map_err(|e| e.to_string());
-                }
-            }
-
-            // 第一步：汇总来源（去重）
-            let unique_sources: std::collections::HashSet<&str> =
-                actual_sources.iter().map(|s| s.as_str()).collect();
-            let mut unique_sources: Vec<&str> = unique_sources.into_iter().collect();
-            unique_sources.sort_unstable();
-
-            // 第二步：提取所有图标到列表
-            use rayon::prelude::*;
-            let all_icons: Vec<(DesktopIcon, i32, &str)> = unique_sources
-                .par_iter()
-                .flat_map_iter(|&source_str| {
-                    let icon_source = match IconSource::from_str(source_str) {
-                        Some(s) => s,
-                        None => {
-                            eprintln!("[Backend] 无效的软件来源: {}", source_str);
-                            return Vec::new();
-                        }
-                    };
-
-                    let priority = get_priority(source_str);
-
-                    match get_source_icons(icon_source, method.as_deref()) {
-                        Ok(icons) => icons
-                            .into_iter()
-                            .map(|icon| (icon, priority, source_str))
-                            .collect(),
-                        Err(e) => {
-                            eprintln!("[Backend] 获取来源 {} 失败: {}", source_str, e);
-                            Vec::new()
-                        }
-                    }
-                })
-                .collect();
-
-            // 第三步：去重并合并
-            let mut non_uwp_map: std::collections::HashMap<String, (DesktopIcon, i32)> =
-                std::collections::HashMap::new();
-            let mut uwp_map: std::collections::HashMap<String, (DesktopIcon, i32)> =
-                std::collections::HashMap::new();
-
-            for (icon, priority, _source_str) in all_icons {
-                let name = icon.name.trim().to_lowercase();
-                let target = if cfg!(target_os = "windows") {
-                    normalize_path(&icon.target_path).to_lowercase()
-                } else {
-                    icon.target_path.to_lowercase()
-                };
-                let is_uwp = icon.file_type.as_deref() == Some("UWP App");
-                let fingerprint = format!("{}:{}", name, target);
-
-                if is_uwp {
-                    match uwp_map.entry(fingerprint) {
-                        std::collections::hash_map::Entry::Vacant(entry) => {
-                            entry.insert((icon, priority));
-                        }
-                        std::collections::hash_map::Entry::Occupied(mut entry) => {
-                            let (existing_icon, existing_priority) = entry.get_mut();
-                            let should_replace = if priority > *existing_priority {
-                                true
-                            } else if priority == *existing_priority {
-                                existing_icon.icon_base64.is_empty() && !icon.icon_base64.is_empty()
-                            } else {
-                                false
-                            };
-                            if should_replace {
-                                *existing_icon = icon;
-                                *existing_priority = priority;
-                            }
-                        }
-                    }
-                } else {
-                    match non_uwp_map.entry(fingerprint) {
-                        std::collections::hash_map::Entry::Vacant(entry) => {
-                            entry.insert((icon, priority));
-                        }
-                        std::collections::hash_map::Entry::Occupied(mut entry) => {
-                            let (existing_icon, existing_priority) = entry.get_mut();
-                            let should_replace = if priority > *existing_priority {
-                                true
-                            } else if priority == *existing_priority {
-                                existing_icon.icon_base64.is_empty() && !icon.icon_base64.is_empty()
-                            } else {
-                                false
-                            };
-                            if should_replace {
-                                *existing_icon = icon;
-                                *existing_priority = priority;
-                            }
-                        }
-                    }
-                }
-            }
-
-            // 跨类型去重：移除UWP中与非UWP同名的
-            let non_uwp_names: std::collections::HashSet<String> = non_uwp_map
-                .keys()
-                .map(|s| s.split(':').next().unwrap_or("").to_string())
-                .collect();
-
-            // 合并结果
-            let mut result_icons: Vec<DesktopIcon> =
-                non_uwp_map.into_iter().map(|(_, (icon, _))| icon).collect();
-
-            for (fingerprint, (icon, _)) in uwp_map {
-                let name = fingerprint.split(':').next().unwrap_or("");
-                if !non_uwp_names.contains(name) {
-                    result_icons.push(icon);
-                }
-            }
-
-            // 按名称排序
-            result_icons.sort_by(|a, b| a.name.cmp(&b.name));
-
-            fill_icons(&mut result_icons, method.as_deref());
-            Ok(result_icons)
-        });
+    let handle = tokio::task::spawn_blocking(move || -> ScanReport {
+        let token = ScanToken::begin();
+        reset_scanner_availability();
+        crate::offline::reset_shared_root();
+        let scanners = select_scanners(sources.as_deref());
+        // 单一来源不做去重合并
+        let merge = scanners.len() > 1;
+        stream_scan(scanners, merge, method.as_deref(), &token, &on_event)
+    });
+    let report = handle.await.map_err(|e| e.to_string())?;
 
-    let result: std::result::Result<Vec<DesktopIcon>, String> =
-        handle.await.map_err(|e| e.to_string())?;
+    println!(
+        "[Backend] 流式扫描 {} 结束, 共 {} 个图标, 失败或超时的来源: {}, 取消: {}, 方式: {:?}, 耗时: {:.3}s",
+        report.scan_id,
+        report.total,
+        report.failures.len(),
+        report.cancelled,
+        method_clone,
+        report.elapsed_ms as f64 / 1000.0
+    );
+    Ok(report)
+}
 
-    let duration = start.elapsed();
-    match &result {
-        Ok(icons) => println!(
-            "[Backend] 从多个来源成功返回 {} 个图标, 方式: {:?}, 耗时: {:.3}s",
-            icons.len(),
-            method_clone,
-            duration.as_secs_f64()
-        ),
-        Err(e) => eprintln!(
-            "[Backend] 从多个来源返回错误: {}, 方式: {:?}, 耗时: {:.3}s",
-            e,
-            method_clone,
-            duration.as_secs_f64()
-        ),
-    }
-    result
+/// 取消正在进行的流式扫描，扫描已结束时返回 false
+#[tauri::command]
+pub fn cancel_scan(scan_id: u64) -> bool {
+    println!("[Backend] 收到 cancel_scan 命令: {}", scan_id);
+    crate::cancel::cancel_scan(scan_id)
 }
 
-/// 获取可用的软件来源列表（Windows）
-#[cfg(target_os = "windows")]
+/// 监视当前目录各来源的变化，变化时发送 catalog-changed 事件，返回监视目标的数量
+#[tauri::command]
+pub fn start_catalog_watch(app: AppHandle) -> Result<usize, String> {
+    println!("[Backend] 收到 start_catalog_watch 命令");
+    crate::watcher::start_watch(app).map_err(|e| format!("开始监视失败: {}", e))
+}
+
+/// 停止监视来源变化
+#[tauri::command]
+pub fn stop_catalog_watch() {
+    println!("[Backend] 收到 stop_catalog_watch 命令");
+    crate::watcher::stop_watch();
+}
+
+/// 获取可用的软件来源列表（Windows，以及扫描离线分区的 Linux）
+#[cfg(not(target_os = "macos"))]
 #[tauri::command]
 pub fn get_available_sources() -> Vec<serde_json::Value> {
     let mut sources = vec![];
@@ -480,7 +257,11 @@ pub fn get_available_sources() -> Vec<serde_json::Value> {
           "id": scanner.id(),
           "name": scanner.name(),
           "description": scanner.description(),
-          "icon": scanner.icon()
+          "icon": scanner.icon(),
+          "cost": scanner.cost(),
+          "default_priority": scanner.default_priority(),
+          "supports_watching": scanner.supports_watching(),
+          "settings": scanner.settings()
         }));
     }
 
@@ -518,9 +299,108 @@ pub fn get_available_sources() -> Vec<serde_json::Value> {
           "id": scanner.id(),
           "name": scanner.name(),
           "description": scanner.description(),
-          "icon": scanner.icon()
+          "icon": scanner.icon(),
+          "cost": scanner.cost(),
+          "default_priority": scanner.default_priority(),
+          "supports_watching": scanner.supports_watching(),
+          "settings": scanner.settings()
         }));
     }
 
     sources
 }
+
+/// 获取当前扫描设置
+#[tauri::command]
+pub fn get_scan_settings() -> ScanSettings {
+    scan_settings()
+}
+
+/// 同步前端的扫描设置（在下次扫描时生效）
+#[tauri::command]
+pub fn set_scan_settings(settings: ScanSettings) {
+    println!("[Backend] 收到 set_scan_settings 命令: {:?}", settings);
+    let previous = crate::config::scan_settings();
+    let rules_changed = previous.use_default_rules != settings.use_default_rules
+        || previous.filter_rules != settings.filter_rules;
+    crate::config::set_scan_settings(settings);
+    reset_scanner_availability();
+    crate::offline::reset_shared_root();
+    // 规则变化后旧的命中统计不再准确
+    if rules_changed {
+        clear_rule_hits();
+    }
+}
+
+/// 获取内置过滤规则
+#[tauri::command]
+pub fn get_default_filter_rules() -> Vec<FilterRule> {
+    default_rules()
+}
+
+/// 获取各过滤规则的命中统计
+#[tauri::command]
+pub fn get_rule_hits() -> Vec<RuleHitSummary> {
+    rule_hits()
+}
+
+/// 启动条目（Windows），控制台程序在终端中运行
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn launch_entry(icon: DesktopIcon) -> Result<(), String> {
+    println!("[Backend] 收到 launch_entry 命令: {}", icon.file_path);
+    crate::launch::launch_entry(&icon).map_err(|e| format!("启动失败: {}", e))
+}
+
+/// 启动条目（Linux）：Wine 条目在所属前缀中运行，.desktop 条目按其 Exec 启动
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn launch_entry(icon: DesktopIcon) -> Result<(), String> {
+    println!("[Backend] 收到 launch_entry 命令: {}", icon.file_path);
+    let result = if icon.wine_prefix.is_some() {
+        crate::wine::launch_entry(&icon)
+    } else {
+        crate::sources::xdg::launch_entry(&icon)
+    };
+    result.map_err(|e| format!("启动失败: {}", e))
+}
+
+/// 从注册表文件（.reg 导出或 SOFTWARE/NTUSER.DAT 等 hive）读取已安装程序清单
+#[tauri::command]
+pub fn get_registry_inventory(paths: Vec<String>) -> Result<Vec<UninstallEntry>, String> {
+    println!("[Backend] 收到 get_registry_inventory 命令: {:?}", paths);
+    let mut registry = RegistrySet::new();
+    for path in &paths {
+        let source = registry::open_file(std::path::Path::new(path))
+            .map_err(|e| format!("无法读取注册表文件 {}: {}", path, e))?;
+        registry.push(source);
+    }
+    if registry.is_empty() {
+        return Err("未指定注册表文件".to_string());
+    }
+    Ok(visible_entries(
+        &registry,
+        read_uninstall_entries(&registry),
+    ))
+}
+
+/// 查找条目对应的已安装产品与卸载方式，供卸载前确认
+#[tauri::command]
+pub fn find_uninstall_target(icon: DesktopIcon) -> Result<UninstallTarget, String> {
+    println!(
+        "[Backend] 收到 find_uninstall_target 命令: {}",
+        icon.file_path
+    );
+    crate::uninstall::prepare_uninstall(&icon).map_err(|e| e.to_string())
+}
+
+/// 执行用户确认的卸载操作（find_uninstall_target 返回的 ID 与命令），等待卸载程序结束后返回退出状态
+#[tauri::command]
+pub async fn uninstall_entry(id: u64, command: String) -> Result<UninstallOutcome, String> {
+    println!("[Backend] 收到 uninstall_entry 命令: {} {}", id, command);
+    tokio::task::spawn_blocking(move || {
+        crate::uninstall::uninstall_target(id, &command).map_err(|e| format!("卸载失败: {}", e))
+    })
+    .await
+    .map_err(|e| format!("卸载任务失败: {}", e))?
+}
@@ -0,0 +1,123 @@
+// 扫描设置 - 由前端配置同步到后端，扫描器在扫描时读取
+
+use crate::rules::FilterRule;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+/// 影响扫描结果的设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanSettings {
+    /// 目录扫描（Program Files、AppData）只保留 GUI 程序
+    pub gui_only: bool,
+    /// 是否启用内置的噪声过滤规则
+    pub use_default_rules: bool,
+    /// 用户自定义过滤规则，优先于内置规则
+    pub filter_rules: Vec<FilterRule>,
+    /// 离线扫描的 Windows 根目录（如 /mnt/c），设置后扫描该分区而不是本机
+    pub windows_root: Option<String>,
+    /// WSL 发行版所在目录（默认 \\wsl$），可指向发行版文件系统的本地副本
+    pub wsl_root: Option<String>,
+    /// 去重时的来源优先级覆盖，键为来源 ID，未设置的来源使用内置优先级
+    pub source_priorities: HashMap<String, i32>,
+    /// 各扫描器的设置值，键为扫描器 ID 与设置项 key，未设置的使用设置项的默认值
+    pub scanner_settings: HashMap<String, HashMap<String, serde_json::Value>>,
+    /// 用户添加的目录来源
+    pub folder_sources: Vec<FolderSource>,
+    /// 单个扫描器的超时秒数，0 表示不限制
+    pub scanner_timeout_secs: u64,
+    /// 单个图标源提取的超时秒数，0 表示不限制
+    pub extract_timeout_secs: u64,
+}
+
+impl Default for ScanSettings {
+    fn default() -> Self {
+        ScanSettings {
+            gui_only: false,
+            use_default_rules: true,
+            filter_rules: Vec::new(),
+            windows_root: None,
+            wsl_root: None,
+            source_priorities: HashMap::new(),
+            scanner_settings: HashMap::new(),
+            folder_sources: Vec::new(),
+            scanner_timeout_secs: 60,
+            extract_timeout_secs: 30,
+        }
+    }
+}
+
+impl ScanSettings {
+    pub fn scanner_timeout(&self) -> Option<Duration> {
+        (self.scanner_timeout_secs > 0).then(|| Duration::from_secs(self.scanner_timeout_secs))
+    }
+
+    pub fn extract_timeout(&self) -> Option<Duration> {
+        (self.extract_timeout_secs > 0).then(|| Duration::from_secs(self.extract_timeout_secs))
+    }
+}
+
+/// 用户添加的目录来源（如 D:\Tools、~/opt）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FolderSource {
+    /// 来源标识，扫描器 ID 为 folder:<id>
+    pub id: String,
+    /// 显示名称，为空时使用根目录
+    pub name: String,
+    /// 根目录，可含 %VAR%、已知文件夹 GUID 与 ~
+    pub root: String,
+    /// 向下遍历的目录层数，1 表示只扫描根目录
+    pub max_depth: usize,
+    /// 文件名须匹配其一的通配符，为空时不限制
+    pub include: Vec<String>,
+    /// 跳过名称匹配的文件与目录
+    pub exclude: Vec<String>,
+    /// 收集的文件类型：exe、lnk、url、desktop（仅 Linux）、app、script，为空时收集全部类型
+    pub file_types: Vec<String>,
+}
+
+impl Default for FolderSource {
+    fn default() -> Self {
+        FolderSource {
+            id: String::new(),
+            name: String::new(),
+            root: String::new(),
+            max_depth: 2,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            file_types: Vec::new(),
+        }
+    }
+}
+
+static SCAN_SETTINGS: OnceLock<RwLock<ScanSettings>> = OnceLock::new();
+
+fn settings_lock() -> &'static RwLock<ScanSettings> {
+    SCAN_SETTINGS.get_or_init(|| RwLock::new(ScanSettings::default()))
+}
+
+/// 获取当前扫描设置的副本
+pub fn scan_settings() -> ScanSettings {
+    settings_lock()
+        .read()
+        .map(|s| s.clone())
+        .unwrap_or_default()
+}
+
+/// 替换扫描设置
+pub fn set_scan_settings(settings: ScanSettings) {
+    if let Ok(mut current) = settings_lock().write() {
+        *current = settings;
+    }
+}
+
+/// 读取扫描器的设置值，未设置或类型不符时返回 None
+pub fn scanner_setting<T: DeserializeOwned>(scanner_id: &str, key: &str) -> Option<T> {
+    let settings = settings_lock().read().ok()?;
+    let value = settings.scanner_settings.get(scanner_id)?.get(key)?;
+    serde_json::from_value(value.clone()).ok()
+}
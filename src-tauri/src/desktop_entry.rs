@@ -0,0 +1,166 @@
+// freedesktop .desktop 文件解析 - WSL 发行版、XDG 应用目录与自定义目录来源共用
+//
+// 只读取 [Desktop Entry] 组中 Type=Application 的条目，Exec 中的字段代码（%f、%U 等）被去掉。
+// Icon 值按 freedesktop 图标主题查找：各尺寸 apps 目录中的 PNG，其次 scalable 中的 SVG，最后 pixmaps。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// .desktop 文件中 [Desktop Entry] 组的程序信息
+pub struct DesktopEntry {
+    pub name: String,
+    pub exec: String,
+    pub icon: Option<String>,
+    pub comment: Option<String>,
+    pub path: Option<String>,
+    pub terminal: bool,
+    pub keywords: Vec<String>,
+}
+
+impl DesktopEntry {
+    /// 解析 .desktop 文件，非程序、隐藏或只在特定桌面环境显示的条目返回 None
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut values: HashMap<&str, &str> = HashMap::new();
+        let mut in_entry = false;
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                in_entry = line == "[Desktop Entry]";
+                continue;
+            }
+            if let (true, Some((key, value))) = (in_entry, line.split_once('=')) {
+                values.entry(key.trim()).or_insert(value.trim());
+            }
+        }
+
+        let flag = |key: &str| values.get(key).is_some_and(|v| *v == "true");
+        let string = |key: &str| {
+            values
+                .get(key)
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string())
+        };
+        if values.get("Type") != Some(&"Application")
+            || flag("NoDisplay")
+            || flag("Hidden")
+            || values.contains_key("OnlyShowIn")
+        {
+            return None;
+        }
+
+        let mut keywords: Vec<String> = string("GenericName").into_iter().collect();
+        keywords.extend(
+            values
+                .get("Keywords")
+                .into_iter()
+                .flat_map(|k| k.split(';'))
+                .map(str::trim)
+                .filter(|k| !k.is_empty())
+                .map(str::to_string),
+        );
+        Some(DesktopEntry {
+            name: string("Name")?,
+            exec: strip_field_codes(&string("Exec")?),
+            icon: string("Icon"),
+            comment: string("Comment"),
+            path: string("Path"),
+            terminal: flag("Terminal"),
+            keywords,
+        })
+    }
+}
+
+/// 去掉 Exec 中的字段代码（%f、%U 等），%% 还原为 %
+fn strip_field_codes(exec: &str) -> String {
+    let mut result = String::new();
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        if let Some('%') = chars.next() {
+            result.push('%');
+        }
+    }
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// 主题图标的尺寸目录，按优先顺序
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+const ICON_SIZES: &[&str] = &[
+    "256x256", "128x128", "512x512", "96x96", "64x64", "48x48", "32x32",
+];
+
+/// freedesktop 图标主题目录与 pixmaps 目录
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+pub struct IconThemes {
+    themes: Vec<PathBuf>,
+    pixmaps: Vec<PathBuf>,
+}
+
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+impl IconThemes {
+    /// 读取各 icons 目录中的主题，hicolor 优先，其余按目录顺序
+    pub fn new(icon_dirs: &[PathBuf], pixmaps: Vec<PathBuf>) -> Self {
+        let mut themes: Vec<PathBuf> = icon_dirs
+            .iter()
+            .flat_map(|dir| {
+                let mut themes: Vec<PathBuf> = std::fs::read_dir(dir)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.is_dir())
+                    .collect();
+                themes.sort();
+                themes
+            })
+            .collect();
+        themes.sort_by_key(|p| !p.ends_with("hicolor"));
+        IconThemes { themes, pixmaps }
+    }
+
+    /// 按 Icon 值查找 PNG 或 SVG 图标：绝对路径映射到 root 下，否则依次查找各主题
+    /// 各尺寸的 apps 目录、scalable/apps 目录与 pixmaps
+    pub fn find(&self, root: &Path, icon: &str) -> Option<PathBuf> {
+        if icon.starts_with('/') {
+            let path = join_linux_path(root, icon);
+            let supported = path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("png") || e.eq_ignore_ascii_case("svg"));
+            return supported.then_some(path).filter(|p| p.is_file());
+        }
+
+        let name = icon
+            .strip_suffix(".png")
+            .or_else(|| icon.strip_suffix(".svg"))
+            .unwrap_or(icon);
+        let png = &format!("{}.png", name);
+        let svg = &format!("{}.svg", name);
+        let sized = self.themes.iter().flat_map(|theme| {
+            ICON_SIZES
+                .iter()
+                .map(move |size| theme.join(size).join("apps").join(png))
+        });
+        let scalable = self
+            .themes
+            .iter()
+            .map(|theme| theme.join("scalable").join("apps").join(svg));
+        let pixmaps = self
+            .pixmaps
+            .iter()
+            .flat_map(|dir| [dir.join(png), dir.join(svg)]);
+        sized.chain(scalable).chain(pixmaps).find(|p| p.is_file())
+    }
+}
+
+/// 将 Linux 路径拼接到根目录下
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+pub fn join_linux_path(root: &Path, path: &str) -> PathBuf {
+    path.split('/')
+        .filter(|c| !c.is_empty())
+        .fold(root.to_path_buf(), |dir, name| dir.join(name))
+}
@@ -0,0 +1,145 @@
+// 条目身份 - 去重时判断两个条目是否为同一应用
+//
+// 每个条目有一个或多个身份键，任一键相同即视为同一应用：
+//   AppUserModelID  快捷方式属性中设置的或应用商店应用的 ID，合并同一应用的桌面、开始菜单、
+//                   任务栏快捷方式与 UWP 条目
+//   Bundle ID       macOS 应用的 CFBundleIdentifier
+//   名称 + 目标     展开已知文件夹与环境变量，去掉 \\?\ 前缀，对本机磁盘上存在的文件解析
+//                   符号链接、目录联接与 8.3 短文件名（PROGRA~1），再统一分隔符；
+//                   Windows 与 Windows 形式的路径不区分大小写，统一为小写
+
+use crate::path::normalize_path;
+use crate::types::DesktopIcon;
+use std::collections::HashMap;
+
+/// 条目的全部身份键
+pub fn identity_keys(icon: &DesktopIcon) -> Vec<String> {
+    let mut keys = Vec::new();
+    let id = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(str::to_lowercase)
+    };
+    if let Some(aumid) = id(&icon.app_user_model_id) {
+        keys.push(format!("aumid:{}", aumid));
+    }
+    if let Some(bundle_id) = id(&icon.bundle_id) {
+        keys.push(format!("bundle:{}", bundle_id));
+    }
+    // 没有目标的条目按自身路径区分，避免同名的不同应用被合并
+    let mut target = canonical_target(&icon.target_path);
+    if target.is_empty() {
+        target = canonical_target(&icon.file_path);
+    }
+    keys.push(format!("path:{}:{}", normalize_name(&icon.name), target));
+    keys
+}
+
+/// 身份键到已合并条目位置的索引
+#[derive(Default)]
+pub struct IdentityIndex {
+    slots: HashMap<String, usize>,
+}
+
+impl IdentityIndex {
+    /// 与任一身份键相同的已合并条目
+    pub fn find(&self, keys: &[String]) -> Option<usize> {
+        keys.iter().find_map(|key| self.slots.get(key).copied())
+    }
+
+    /// 登记身份键，已登记的键保持原位置
+    pub fn insert(&mut self, keys: Vec<String>, slot: usize) {
+        for key in keys {
+            self.slots.entry(key).or_insert(slot);
+        }
+    }
+}
+
+/// 规范化的目标路径；URL、shell: 路径与 UWP 应用 ID 只统一大小写
+///
+/// 网络路径（UNC、映射的网络驱动器）不解析，canonicalize 在服务器无响应时会长时间阻塞。
+pub fn canonical_target(path: &str) -> String {
+    let path = normalize_path(path.trim().trim_matches('"'));
+    let path = strip_verbatim(&path);
+    if path.is_empty() || path.contains("://") || path.starts_with("shell:") {
+        return path.to_lowercase();
+    }
+
+    // canonicalize 在 Windows 上返回最终路径：长文件名、解析联接，并带 \\?\ 前缀
+    let resolved = if is_local_path(&path) {
+        std::fs::canonicalize(&path)
+            .map(|p| strip_verbatim(&p.to_string_lossy()))
+            .unwrap_or(path)
+    } else {
+        path
+    };
+
+    let windows_style = resolved.contains('\\') || resolved.get(1..2) == Some(":");
+    let resolved = if windows_style {
+        resolved.replace('/', "\\")
+    } else {
+        resolved
+    };
+    // 保留根目录（C:\、/）末尾的分隔符
+    let trimmed = resolved.trim_end_matches(['\\', '/']);
+    let trimmed = if trimmed.is_empty() || trimmed.ends_with(':') {
+        &resolved
+    } else {
+        trimmed
+    };
+    if windows_style || cfg!(target_os = "windows") {
+        trimmed.to_lowercase()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// 本机磁盘上的绝对路径：驱动器号路径且不是网络驱动器
+#[cfg(target_os = "windows")]
+fn is_local_path(path: &str) -> bool {
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDriveTypeW;
+    // GetDriveTypeW 的返回值
+    const DRIVE_NO_ROOT_DIR: u32 = 1;
+    const DRIVE_REMOTE: u32 = 4;
+
+    let bytes = path.as_bytes();
+    if bytes.len() < 3
+        || !bytes[0].is_ascii_alphabetic()
+        || bytes[1] != b':'
+        || !matches!(bytes[2], b'\\' | b'/')
+    {
+        return false;
+    }
+    let root: Vec<u16> = format!("{}:\\", bytes[0] as char)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let kind = unsafe { GetDriveTypeW(PCWSTR(root.as_ptr())) };
+    kind != DRIVE_NO_ROOT_DIR && kind != DRIVE_REMOTE
+}
+
+/// 本机的绝对路径；Windows 形式的路径（离线分区、Wine 前缀中的登记）不在本机文件系统中
+#[cfg(not(target_os = "windows"))]
+fn is_local_path(path: &str) -> bool {
+    path.starts_with('/')
+}
+
+/// 去掉 \\?\ 与 \\?\UNC\ 前缀
+fn strip_verbatim(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else {
+        path.strip_prefix(r"\\?\").unwrap_or(path).to_string()
+    }
+}
+
+/// 名称去掉首尾空白、合并连续空白并统一大小写
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
@@ -0,0 +1,216 @@
+// 启动模块 - 根据条目信息选择启动方式
+
+use crate::types::DesktopIcon;
+use std::error::Error;
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject, INFINITE};
+use windows::Win32::UI::Shell::{
+    ShellExecuteExW, SEE_MASK_NOASYNC, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW,
+};
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+// CreateProcess 的 ERROR_ELEVATION_REQUIRED
+const ERROR_ELEVATION_REQUIRED: i32 = 740;
+
+/// 启动条目：控制台程序在新的终端窗口中运行，其余交给 ShellExecute
+///
+/// 清单要求管理员权限的程序使用 runas 动词，由系统弹出 UAC 确认，
+/// 避免直接创建进程时因 ERROR_ELEVATION_REQUIRED 静默失败。
+pub fn launch_entry(icon: &DesktopIcon) -> Result<(), Box<dyn Error>> {
+    if let Some(distro) = &icon.wsl_distro {
+        return launch_in_wsl(icon, distro);
+    }
+    // 提升权限的启动经由 UAC，无法指定子进程的环境，只能按原样启动
+    let dirs = icon
+        .app_path_dirs
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty() && !icon.requires_elevation);
+    if let Some(dirs) = dirs {
+        if launch_with_app_path_dirs(icon, dirs)? {
+            return Ok(());
+        }
+    }
+    launch_local(icon)
+}
+
+fn launch_local(icon: &DesktopIcon) -> Result<(), Box<dyn Error>> {
+    let verb = if icon.requires_elevation {
+        "runas"
+    } else {
+        "open"
+    };
+
+    if icon.run_in_terminal {
+        return launch_in_terminal(icon, verb);
+    }
+
+    // UWP 应用通过 shell:AppsFolder 启动；其余使用位置而不是目标，以保留快捷方式的参数
+    if icon.file_type.as_deref() == Some("UWP App") {
+        let file = format!("shell:AppsFolder\\{}", icon.target_path);
+        return shell_execute("open", &file, None, None);
+    }
+    shell_execute(verb, &icon.file_path, None, None)
+}
+
+/// 按 App Paths 的 Path 值把目录加在 PATH 前面，直接创建进程，与通过登记名启动时一致
+///
+/// ShellExecute 不能指定子进程的环境，因此为子进程单独构造环境，不修改本进程的 PATH。
+/// 目标不是 exe 或程序需要提升权限时返回 false，由调用方改用 ShellExecute，此时不附加这些目录。
+fn launch_with_app_path_dirs(icon: &DesktopIcon, dirs: &str) -> Result<bool, Box<dyn Error>> {
+    use std::os::windows::process::CommandExt;
+    use windows::Win32::System::Threading::CREATE_NEW_CONSOLE;
+
+    let target = icon.target_path.trim().trim_matches('"');
+    if !target.to_lowercase().ends_with(".exe") {
+        return Ok(false);
+    }
+    let mut path = OsString::from(dirs.trim_end_matches(';'));
+    if let Some(original) = std::env::var_os("PATH") {
+        path.push(";");
+        path.push(original);
+    }
+
+    let mut command = if icon.run_in_terminal {
+        let mut command = Command::new("cmd.exe");
+        command
+            .raw_arg(terminal_parameters(icon, target))
+            .creation_flags(CREATE_NEW_CONSOLE.0);
+        command
+    } else {
+        let mut command = Command::new(target);
+        let arguments = icon.arguments.as_deref().unwrap_or("").trim();
+        if !arguments.is_empty() {
+            command.raw_arg(arguments);
+        }
+        command
+    };
+    command.env("PATH", path);
+    if let Some(dir) = working_directory(icon, target) {
+        command.current_dir(dir);
+    }
+    match command.spawn() {
+        Ok(_) => Ok(true),
+        // 清单为 highestAvailable 的程序在管理员账户下需要提升
+        Err(e) if e.raw_os_error() == Some(ERROR_ELEVATION_REQUIRED) => Ok(false),
+        Err(e) => Err(format!("无法启动 {}: {}", target, e).into()),
+    }
+}
+
+/// 通过 cmd /k 启动控制台程序，程序退出后窗口保留，便于查看输出
+fn launch_in_terminal(icon: &DesktopIcon, verb: &str) -> Result<(), Box<dyn Error>> {
+    let target = icon.target_path.trim().trim_matches('"');
+    shell_execute(
+        verb,
+        "cmd.exe",
+        Some(&terminal_parameters(icon, target)),
+        working_directory(icon, target).as_deref(),
+    )
+}
+
+/// cmd /k 的参数
+fn terminal_parameters(icon: &DesktopIcon, target: &str) -> String {
+    let arguments = icon.arguments.as_deref().unwrap_or("").trim();
+    // cmd 会去掉 /k 之后整串命令最外层的一对引号
+    if arguments.is_empty() {
+        format!("/k \"\"{}\"\"", target)
+    } else {
+        format!("/k \"\"{}\" {}\"", target, arguments)
+    }
+}
+
+/// 工作目录：条目中记录的目录，没有时为目标所在目录
+fn working_directory(icon: &DesktopIcon, target: &str) -> Option<String> {
+    icon.working_directory
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+        .map(Path::new)
+        .or_else(|| Path::new(target).parent())
+        .map(|d| d.to_string_lossy().to_string())
+}
+
+/// 在 WSL 发行版中运行 .desktop 的 Exec 命令，终端程序由 wsl.exe 的控制台窗口承载
+fn launch_in_wsl(icon: &DesktopIcon, distro: &str) -> Result<(), Box<dyn Error>> {
+    let command = icon.arguments.as_deref().unwrap_or("").trim();
+    if command.is_empty() {
+        return Err("缺少要运行的命令".into());
+    }
+    let mut parameters = format!("-d \"{}\"", distro);
+    if let Some(dir) = icon
+        .working_directory
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        parameters.push_str(&format!(" --cd \"{}\"", dir));
+    }
+    parameters.push_str(&format!(" -- {}", command));
+    shell_execute("open", "wsl.exe", Some(&parameters), None)
+}
+
+/// 通过 ShellExecute 运行程序并等待其退出，返回退出码
+///
+/// 程序请求提升时由系统弹出 UAC 确认，用户拒绝时返回错误。
+pub fn shell_execute_wait(file: &str, parameters: Option<&str>) -> Result<u32, Box<dyn Error>> {
+    let process = shell_execute_ex(
+        "open",
+        file,
+        parameters,
+        None,
+        SEE_MASK_NOASYNC | SEE_MASK_NOCLOSEPROCESS,
+    )?;
+    // 交给已运行的实例处理（DDE 等）时没有进程句柄
+    if process.is_invalid() {
+        return Err("未能获取进程句柄".into());
+    }
+    let mut exit_code = 0u32;
+    let result = unsafe {
+        WaitForSingleObject(process, INFINITE);
+        let result = GetExitCodeProcess(process, &mut exit_code);
+        let _ = CloseHandle(process);
+        result
+    };
+    result?;
+    Ok(exit_code)
+}
+
+fn shell_execute(
+    verb: &str,
+    file: &str,
+    parameters: Option<&str>,
+    directory: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    shell_execute_ex(verb, file, parameters, directory, SEE_MASK_NOASYNC).map(|_| ())
+}
+
+fn shell_execute_ex(
+    verb: &str,
+    file: &str,
+    parameters: Option<&str>,
+    directory: Option<&str>,
+    mask: u32,
+) -> Result<HANDLE, Box<dyn Error>> {
+    let to_wide = |s: &str| -> Vec<u16> { s.encode_utf16().chain(std::iter::once(0)).collect() };
+    let verb_wide = to_wide(verb);
+    let file_wide = to_wide(file);
+    let parameters_wide = parameters.map(to_wide);
+    let directory_wide = directory.map(to_wide);
+    let as_pcwstr =
+        |w: &Option<Vec<u16>>| w.as_ref().map_or(PCWSTR::null(), |w| PCWSTR(w.as_ptr()));
+
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: mask,
+        lpVerb: PCWSTR(verb_wide.as_ptr()),
+        lpFile: PCWSTR(file_wide.as_ptr()),
+        lpParameters: as_pcwstr(&parameters_wide),
+        lpDirectory: as_pcwstr(&directory_wide),
+        nShow: SW_SHOWNORMAL.0,
+        ..Default::default()
+    };
+    unsafe { ShellExecuteExW(&mut info)? };
+    Ok(info.hProcess)
+}
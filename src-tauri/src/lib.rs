@@ -1,17 +1,34 @@
 // 模块声明
 
+mod cancel;
+mod catalog;
 mod commands;
+mod config;
 #[cfg(target_os = "windows")]
 mod constants;
+mod desktop_entry;
 #[cfg(target_os = "windows")]
 mod extractor;
 #[cfg(target_os = "windows")]
 mod extractors;
-mod path;
+mod identity;
 #[cfg(target_os = "windows")]
+mod launch;
+mod lnk;
+mod localized;
+mod offline;
+mod path;
+mod pe;
+mod registry;
+mod rules;
 mod shortcut;
 mod sources;
+mod stream;
 mod types;
+mod uninstall;
+mod watcher;
+#[cfg(target_os = "linux")]
+mod wine;
 
 use commands::*;
 use tauri::{AppHandle, Manager};
@@ -43,10 +60,20 @@ pub fn run() {
             get_desktop_icons_with_method,
             get_available_icon_methods,
             // 软件来源命令
-            get_icons_from_source,
             get_all_source_icons,
-            get_icons_from_multiple_sources,
-            get_available_sources
+            stream_source_icons,
+            cancel_scan,
+            get_available_sources,
+            start_catalog_watch,
+            stop_catalog_watch,
+            get_scan_settings,
+            set_scan_settings,
+            get_default_filter_rules,
+            get_rule_hits,
+            get_registry_inventory,
+            find_uninstall_target,
+            uninstall_entry,
+            launch_entry
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -68,16 +95,59 @@ pub fn run() {
             greet,
             get_available_icon_methods,
             // 软件来源命令（macOS）
-            get_icons_from_source,
             get_all_source_icons,
-            get_icons_from_multiple_sources,
+            stream_source_icons,
+            cancel_scan,
             get_available_sources,
+            start_catalog_watch,
+            stop_catalog_watch,
+            get_scan_settings,
+            set_scan_settings,
+            get_default_filter_rules,
+            get_rule_hits,
+            get_registry_inventory,
+            find_uninstall_target,
+            uninstall_entry,
             reveal_file
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
+// Linux 入口，注册跨平台命令与 Wine 条目的启动（可扫描挂载的离线 Windows 分区）
+#[cfg(target_os = "linux")]
+pub fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_prevent_default::debug())
+        .plugin(tauri_plugin_single_instance::init(|app, _, _cwd| {
+          show_window(app);
+        }))
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_os::init())
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            // 软件来源命令
+            get_all_source_icons,
+            stream_source_icons,
+            cancel_scan,
+            get_available_sources,
+            start_catalog_watch,
+            stop_catalog_watch,
+            get_scan_settings,
+            set_scan_settings,
+            get_default_filter_rules,
+            get_rule_hits,
+            get_registry_inventory,
+            find_uninstall_target,
+            uninstall_entry,
+            launch_entry
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
 fn show_window(app: &AppHandle) {
   let windows = app.webview_windows();
 
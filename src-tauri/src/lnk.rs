@@ -0,0 +1,458 @@
+// Shell Link (.lnk) 二进制格式解析 - 纯 Rust 实现，不依赖 IShellLink，可用于离线文件
+//
+// 结构（MS-SHLLINK）：
+//   ShellLinkHeader    76 字节，含 LinkFlags、图标索引、显示命令、快捷键
+//   LinkTargetIDList   可选，目标的 Shell 项目标识列表
+//   LinkInfo           可选，卷信息与本地路径（或网络共享路径）
+//   StringData         可选，说明、相对路径、工作目录、参数、图标位置
+//   ExtraData          附加数据块，其中环境变量块保存含 %VAR% 的目标与图标路径，
+//                      属性存储块保存 System.AppUserModel.ID 等属性
+
+use std::error::Error;
+use std::path::Path;
+
+const HEADER_SIZE: usize = 0x4C;
+
+// LinkFlags
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x0000_0001;
+const HAS_LINK_INFO: u32 = 0x0000_0002;
+const HAS_NAME: u32 = 0x0000_0004;
+const HAS_RELATIVE_PATH: u32 = 0x0000_0008;
+const HAS_WORKING_DIR: u32 = 0x0000_0010;
+const HAS_ARGUMENTS: u32 = 0x0000_0020;
+const HAS_ICON_LOCATION: u32 = 0x0000_0040;
+const IS_UNICODE: u32 = 0x0000_0080;
+const HAS_EXP_STRING: u32 = 0x0000_0200;
+const HAS_EXP_ICON: u32 = 0x0000_4000;
+
+// LinkInfoFlags
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x1;
+const COMMON_NETWORK_RELATIVE_LINK: u32 = 0x2;
+
+// ExtraData 块签名
+const ENVIRONMENT_VARIABLE_BLOCK: u32 = 0xA000_0001;
+const ICON_ENVIRONMENT_BLOCK: u32 = 0xA000_0007;
+const PROPERTY_STORE_BLOCK: u32 = 0xA000_0009;
+
+// System.AppUserModel.ID：{9F4C2855-9F79-4B39-A8D0-E1D42DE1D5F3}, 5
+const APP_USER_MODEL_FMTID: [u8; 16] = [
+    0x55, 0x28, 0x4C, 0x9F, 0x79, 0x9F, 0x39, 0x4B, 0xA8, 0xD0, 0xE1, 0xD4, 0x2D, 0xE1, 0xD5, 0xF3,
+];
+const APP_USER_MODEL_ID_PID: u32 = 5;
+const VT_LPWSTR: u16 = 0x1F;
+
+/// 快捷方式内容，路径保持快捷方式中记录的 Windows 形式
+#[derive(Debug, Clone, Default)]
+pub struct ShellLink {
+    /// 目标路径，可能含 %VAR%
+    pub target_path: Option<String>,
+    /// 相对于快捷方式所在目录的目标路径
+    pub relative_path: Option<String>,
+    pub arguments: Option<String>,
+    pub working_directory: Option<String>,
+    pub description: Option<String>,
+    /// 图标文件，可能含 %VAR%
+    pub icon_location: Option<String>,
+    pub icon_index: i32,
+    pub show_command: i32,
+    pub hotkey: u16,
+    /// 属性存储中显式设置的 AppUserModelID
+    pub app_user_model_id: Option<String>,
+}
+
+impl ShellLink {
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    pub fn parse(data: &[u8]) -> Result<Self, Box<dyn Error>> {
+        if read_u32(data, 0) != Some(HEADER_SIZE as u32) {
+            return Err("不是有效的快捷方式文件".into());
+        }
+        let flags = read_u32(data, 0x14).ok_or("快捷方式头不完整")?;
+        let mut link = ShellLink {
+            icon_index: read_u32(data, 0x38).ok_or("快捷方式头不完整")? as i32,
+            show_command: read_u32(data, 0x3C).ok_or("快捷方式头不完整")? as i32,
+            hotkey: read_u16(data, 0x40).ok_or("快捷方式头不完整")?,
+            ..Default::default()
+        };
+
+        let mut offset = HEADER_SIZE;
+        let mut id_list_path = None;
+        if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+            let size = read_u16(data, offset).ok_or("目标标识列表不完整")? as usize;
+            let list = data
+                .get(offset + 2..offset + 2 + size)
+                .ok_or("目标标识列表不完整")?;
+            id_list_path = id_list_to_path(list);
+            offset += 2 + size;
+        }
+
+        let mut link_info_path = None;
+        if flags & HAS_LINK_INFO != 0 {
+            let size = read_u32(data, offset).ok_or("LinkInfo 不完整")? as usize;
+            let info = data.get(offset..offset + size).ok_or("LinkInfo 不完整")?;
+            link_info_path = parse_link_info(info);
+            offset += size;
+        }
+
+        let unicode = flags & IS_UNICODE != 0;
+        let mut read_string = |present: bool| -> Option<String> {
+            if !present {
+                return None;
+            }
+            let (text, next) = read_counted_string(data, offset, unicode)?;
+            offset = next;
+            Some(text).filter(|s| !s.is_empty())
+        };
+        link.description = read_string(flags & HAS_NAME != 0);
+        link.relative_path = read_string(flags & HAS_RELATIVE_PATH != 0);
+        link.working_directory = read_string(flags & HAS_WORKING_DIR != 0);
+        link.arguments = read_string(flags & HAS_ARGUMENTS != 0);
+        link.icon_location = read_string(flags & HAS_ICON_LOCATION != 0);
+
+        // 环境变量块中的路径未展开，优先使用
+        let mut env_target = None;
+        while let Some(size) = read_u32(data, offset).map(|s| s as usize) {
+            if size < 8 || offset + size > data.len() {
+                break;
+            }
+            let block = &data[offset..offset + size];
+            match read_u32(block, 4) {
+                Some(ENVIRONMENT_VARIABLE_BLOCK) if flags & HAS_EXP_STRING != 0 => {
+                    env_target = read_environment_block(block);
+                }
+                Some(ICON_ENVIRONMENT_BLOCK) if flags & HAS_EXP_ICON != 0 => {
+                    if let Some(icon) = read_environment_block(block) {
+                        link.icon_location = Some(icon);
+                    }
+                }
+                Some(PROPERTY_STORE_BLOCK) => {
+                    link.app_user_model_id = read_app_user_model_id(block);
+                }
+                _ => {}
+            }
+            offset += size;
+        }
+
+        link.target_path = env_target.or(link_info_path).or(id_list_path);
+        Ok(link)
+    }
+}
+
+/// LinkInfo 中的本地路径（LocalBasePath + CommonPathSuffix）或网络路径
+fn parse_link_info(info: &[u8]) -> Option<String> {
+    let header_size = read_u32(info, 4)? as usize;
+    let flags = read_u32(info, 8)?;
+    // 头部不小于 0x24 时带有 Unicode 版本的偏移
+    let unicode_offsets = header_size >= 0x24;
+    let suffix = if unicode_offsets {
+        read_u32(info, 0x20).and_then(|o| read_utf16z(info, o as usize))
+    } else {
+        None
+    }
+    .or_else(|| read_u32(info, 0x18).and_then(|o| read_ansiz(info, o as usize)))
+    .unwrap_or_default();
+
+    let base = if flags & VOLUME_ID_AND_LOCAL_BASE_PATH != 0 {
+        if unicode_offsets {
+            read_u32(info, 0x1C).and_then(|o| read_utf16z(info, o as usize))
+        } else {
+            None
+        }
+        .or_else(|| read_u32(info, 0x10).and_then(|o| read_ansiz(info, o as usize)))?
+    } else if flags & COMMON_NETWORK_RELATIVE_LINK != 0 {
+        // CommonNetworkRelativeLink：NetNameOffset 位于 8 字节处
+        let link_offset = read_u32(info, 0x14)? as usize;
+        let net_name = read_u32(info, link_offset + 8)? as usize;
+        read_ansiz(info, link_offset + net_name)?
+    } else {
+        return None;
+    };
+
+    Some(match (base.ends_with('\\'), suffix.is_empty()) {
+        (_, true) => base,
+        (true, false) => base + &suffix,
+        (false, false) => format!("{}\\{}", base, suffix),
+    })
+}
+
+/// 从 Shell 项目标识列表还原文件系统路径：卷项目（C:\）加上各级文件项目的长文件名
+fn id_list_to_path(list: &[u8]) -> Option<String> {
+    let mut path = String::new();
+    let mut offset = 0;
+    while let Some(size) = read_u16(list, offset).map(|s| s as usize) {
+        if size < 3 || offset + size > list.len() {
+            break;
+        }
+        let item = &list[offset..offset + size];
+        match item[2] & 0x70 {
+            // 卷项目：ASCII 盘符，如 "C:\"
+            0x20 => path = read_ansiz(item, 3)?,
+            // 文件项目：短文件名之后的 0xBEEF0004 扩展块中有长文件名
+            0x30 if !path.is_empty() => {
+                let name = file_entry_name(item)?;
+                if !path.ends_with('\\') {
+                    path.push('\\');
+                }
+                path.push_str(&name);
+            }
+            _ => {}
+        }
+        offset += size;
+    }
+    (!path.is_empty()).then_some(path)
+}
+
+fn file_entry_name(item: &[u8]) -> Option<String> {
+    let unicode = item[2] & 0x04 != 0;
+    let short_name = if unicode {
+        read_utf16z(item, 14)?
+    } else {
+        read_ansiz(item, 14)?
+    };
+    // 短文件名按 2 字节对齐，之后是扩展块
+    let name_len = if unicode {
+        (short_name.encode_utf16().count() + 1) * 2
+    } else {
+        short_name.len() + 1
+    };
+    let extension = (14 + name_len + 1) & !1;
+    let long_name = item.get(extension..).and_then(|block| {
+        if read_u32(block, 4)? != 0xBEEF_0004 {
+            return None;
+        }
+        let version = read_u16(block, 2)?;
+        let name_offset = match version {
+            8.. => 0x2A,
+            7 => 0x26,
+            3..=6 => 0x14,
+            _ => return None,
+        };
+        read_utf16z(block, name_offset)
+    });
+    long_name.filter(|n| !n.is_empty()).or(Some(short_name))
+}
+
+/// 环境变量块：260 字节 ANSI 路径之后是 520 字节 Unicode 路径
+fn read_environment_block(block: &[u8]) -> Option<String> {
+    read_utf16z(block, 8 + 260)
+        .filter(|s| !s.is_empty())
+        .or_else(|| read_ansiz(block, 8))
+        .filter(|s| !s.is_empty())
+}
+
+/// 属性存储块（MS-PROPSTORE）中的 AppUserModelID
+///
+/// 块内依次为序列化属性集：大小、版本 "1SPS"、16 字节 FMTID，之后是整数 ID 的属性值：
+/// 大小、属性 ID、保留字节、类型与值，大小为 0 时结束。
+fn read_app_user_model_id(block: &[u8]) -> Option<String> {
+    let mut offset = 8;
+    while let Some(size) = read_u32(block, offset).map(|s| s as usize) {
+        if size < 24 || offset + size > block.len() {
+            break;
+        }
+        let storage = &block[offset..offset + size];
+        offset += size;
+        if storage.get(8..24) != Some(&APP_USER_MODEL_FMTID[..]) {
+            continue;
+        }
+
+        let mut value_offset = 24;
+        while let Some(value_size) = read_u32(storage, value_offset).map(|s| s as usize) {
+            if value_size < 13 || value_offset + value_size > storage.len() {
+                break;
+            }
+            let value = &storage[value_offset..value_offset + value_size];
+            value_offset += value_size;
+            if read_u32(value, 4) != Some(APP_USER_MODEL_ID_PID)
+                || read_u16(value, 9) != Some(VT_LPWSTR)
+            {
+                continue;
+            }
+            // 字符数含结尾 NUL
+            let count = read_u32(value, 13)? as usize;
+            let text = decode_utf16(value.get(17..17 + count * 2)?);
+            return Some(text.trim_end_matches('\0').to_string()).filter(|s| !s.is_empty());
+        }
+    }
+    None
+}
+
+/// StringData 中的字符串：2 字节字符数加上字符数据（无结尾 NUL）
+fn read_counted_string(data: &[u8], offset: usize, unicode: bool) -> Option<(String, usize)> {
+    let count = read_u16(data, offset)? as usize;
+    let start = offset + 2;
+    if unicode {
+        let bytes = data.get(start..start + count * 2)?;
+        Some((decode_utf16(bytes), start + count * 2))
+    } else {
+        let bytes = data.get(start..start + count)?;
+        Some((decode_ansi(bytes), start + count))
+    }
+}
+
+fn read_utf16z(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes
+        .chunks_exact(2)
+        .position(|c| c == [0, 0])
+        .map(|i| i * 2)
+        .unwrap_or(bytes.len() & !1);
+    Some(decode_utf16(&bytes[..end]))
+}
+
+fn read_ansiz(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    Some(decode_ansi(&bytes[..end]))
+}
+
+/// ANSI 字符串的代码页未知，UTF-8 无效时按 Latin-1 解码
+fn decode_ansi(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+fn decode_utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 只有头部的快捷方式，LinkFlags 由调用方指定
+    fn header(flags: u32) -> Vec<u8> {
+        let mut data = vec![0u8; HEADER_SIZE];
+        data[0..4].copy_from_slice(&(HEADER_SIZE as u32).to_le_bytes());
+        data[0x14..0x18].copy_from_slice(&flags.to_le_bytes());
+        data
+    }
+
+    fn counted_utf16(text: &str) -> Vec<u8> {
+        let units: Vec<u16> = text.encode_utf16().collect();
+        let mut data = (units.len() as u16).to_le_bytes().to_vec();
+        data.extend(units.iter().flat_map(|u| u.to_le_bytes()));
+        data
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        assert!(ShellLink::parse(&[]).is_err());
+        assert!(ShellLink::parse(&header(0)[..0x20]).is_err());
+        assert!(ShellLink::parse(&header(0)).is_ok());
+    }
+
+    #[test]
+    fn reads_string_data() {
+        let mut data = header(IS_UNICODE | HAS_ARGUMENTS | HAS_ICON_LOCATION);
+        data.extend(counted_utf16("--safe"));
+        data.extend(counted_utf16("C:\\app.ico"));
+        let link = ShellLink::parse(&data).unwrap();
+        assert_eq!(link.arguments.as_deref(), Some("--safe"));
+        assert_eq!(link.icon_location.as_deref(), Some("C:\\app.ico"));
+    }
+
+    #[test]
+    fn truncated_string_data_is_ignored() {
+        let mut data = header(IS_UNICODE | HAS_NAME | HAS_ARGUMENTS);
+        // 字符数超出文件长度
+        data.extend(0x7FFFu16.to_le_bytes());
+        data.extend([b'a', 0]);
+        let link = ShellLink::parse(&data).unwrap();
+        assert_eq!(link.description, None);
+        assert_eq!(link.arguments, None);
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_id_list_and_link_info() {
+        let mut data = header(HAS_LINK_TARGET_ID_LIST);
+        data.extend(0xFFFFu16.to_le_bytes());
+        assert!(ShellLink::parse(&data).is_err());
+
+        let mut data = header(HAS_LINK_INFO);
+        data.extend(u32::MAX.to_le_bytes());
+        assert!(ShellLink::parse(&data).is_err());
+    }
+
+    #[test]
+    fn link_info_offsets_out_of_bounds() {
+        // 头部声明 Unicode 偏移，各偏移都指向块外
+        let mut info = vec![0u8; 0x24];
+        info[0..4].copy_from_slice(&0x24u32.to_le_bytes());
+        info[4..8].copy_from_slice(&0x24u32.to_le_bytes());
+        info[8..12].copy_from_slice(
+            &(VOLUME_ID_AND_LOCAL_BASE_PATH | COMMON_NETWORK_RELATIVE_LINK).to_le_bytes(),
+        );
+        for offset in [0x10, 0x14, 0x18, 0x1C, 0x20] {
+            info[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        }
+        let mut data = header(HAS_LINK_INFO);
+        data.extend(&info);
+        let link = ShellLink::parse(&data).unwrap();
+        assert_eq!(link.target_path, None);
+
+        // 块本身短于头部字段
+        let mut data = header(HAS_LINK_INFO);
+        data.extend(6u32.to_le_bytes());
+        data.extend([0xFF, 0xFF]);
+        assert_eq!(ShellLink::parse(&data).unwrap().target_path, None);
+    }
+
+    #[test]
+    fn extra_data_out_of_bounds() {
+        // 块大小超出文件长度
+        let mut data = header(HAS_EXP_STRING);
+        data.extend(0x1000u32.to_le_bytes());
+        data.extend(ENVIRONMENT_VARIABLE_BLOCK.to_le_bytes());
+        assert_eq!(ShellLink::parse(&data).unwrap().target_path, None);
+
+        // 环境变量块短于路径字段
+        let mut data = header(HAS_EXP_STRING);
+        data.extend(12u32.to_le_bytes());
+        data.extend(ENVIRONMENT_VARIABLE_BLOCK.to_le_bytes());
+        data.extend(b"C:\\a");
+        assert_eq!(
+            ShellLink::parse(&data).unwrap().target_path.as_deref(),
+            Some("C:\\a")
+        );
+
+        // 属性存储中的属性值大小与字符数越界
+        let mut storage = vec![0u8; 24];
+        storage[8..24].copy_from_slice(&APP_USER_MODEL_FMTID);
+        let mut value = vec![0u8; 17];
+        value[0..4].copy_from_slice(&17u32.to_le_bytes());
+        value[4..8].copy_from_slice(&APP_USER_MODEL_ID_PID.to_le_bytes());
+        value[9..11].copy_from_slice(&VT_LPWSTR.to_le_bytes());
+        value[13..17].copy_from_slice(&u32::MAX.to_le_bytes());
+        storage.extend(&value);
+        storage.extend(u32::MAX.to_le_bytes());
+        let storage_size = storage.len() as u32;
+        storage[0..4].copy_from_slice(&storage_size.to_le_bytes());
+        let mut block = vec![0u8; 8];
+        block.extend(&storage);
+        let block_size = block.len() as u32;
+        block[0..4].copy_from_slice(&block_size.to_le_bytes());
+        block[4..8].copy_from_slice(&PROPERTY_STORE_BLOCK.to_le_bytes());
+        let mut data = header(0);
+        data.extend(&block);
+        assert_eq!(ShellLink::parse(&data).unwrap().app_user_model_id, None);
+    }
+}
@@ -0,0 +1,156 @@
+// 本地化名称 - 纯 Rust 解析 desktop.ini 与间接字符串，不调用 Shell
+//
+// 开始菜单、桌面等文件夹中的 desktop.ini 为文件登记显示名称：
+//   [LocalizedFileNames]
+//   Notepad.lnk=@%SystemRoot%\system32\shell32.dll,-22067
+// 以 @ 开头的是间接字符串，指向 DLL 中 RT_STRING 的字符串 ID，
+// 界面语言的文本通常位于同目录 <语言>\<文件名>.mui 中（如 zh-CN\shell32.dll.mui）。
+// 路径经 PathResolver 转换，因此同样适用于离线分区与 Wine 前缀。
+// 语言目录名称在 Windows 上由 LCIDToLocaleName 取得，其余平台取自 LC_ALL/LC_MESSAGES/LANG。
+// 各目录的 desktop.ini 与各模块的字符串表按路径缓存，文件修改时间变化时重新读取。
+
+use crate::offline::find_child;
+use crate::path::PathResolver;
+use crate::pe::string::StringTable;
+use crate::pe::ui_language;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+// 找不到界面语言的 .mui 时使用的语言
+const FALLBACK_LOCALE: &str = "en-US";
+
+type FileCache<T> = OnceLock<Mutex<HashMap<PathBuf, (Option<SystemTime>, Option<Arc<T>>)>>>;
+
+static INI_CACHE: FileCache<String> = OnceLock::new();
+static STRING_TABLE_CACHE: FileCache<StringTable> = OnceLock::new();
+
+/// 文件在所在目录 desktop.ini 中登记的显示名称
+pub fn localized_file_name(path: &Path, paths: &dyn PathResolver) -> Option<String> {
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    let ini = find_child(path.parent()?, "desktop.ini");
+    let text = cached(&INI_CACHE, &ini, |p| {
+        std::fs::read(p).ok().map(|data| decode_ini(&data))
+    })?;
+    let value = ini_value(&text, "LocalizedFileNames", &file_name)?;
+    if value.starts_with('@') {
+        resolve_indirect_string(&value, paths)
+    } else {
+        Some(value)
+    }
+}
+
+/// 解析间接字符串 @<文件>,-<ID>，文件路径可含环境变量
+pub fn resolve_indirect_string(value: &str, paths: &dyn PathResolver) -> Option<String> {
+    let (file, id) = value.strip_prefix('@')?.rsplit_once(',')?;
+    // 部分登记在 ID 后附带 ;注释
+    let id = id.split(';').next()?.trim();
+    let id: u32 = id.strip_prefix('-').unwrap_or(id).parse().ok()?;
+    let module = PathBuf::from(paths.resolve(file.trim()));
+
+    let language = ui_language();
+    let mut locales: Vec<String> = ui_locale().into_iter().collect();
+    if !locales
+        .iter()
+        .any(|l| l.eq_ignore_ascii_case(FALLBACK_LOCALE))
+    {
+        locales.push(FALLBACK_LOCALE.to_string());
+    }
+    locales
+        .iter()
+        .filter_map(|locale| mui_path(&module, locale))
+        .chain(std::iter::once(module.clone()))
+        .filter(|p| p.is_file())
+        .find_map(|p| cached(&STRING_TABLE_CACHE, &p, StringTable::load)?.get(id, language))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// DLL 对应语言的 .mui 文件：<目录>\<语言>\<文件名>.mui
+fn mui_path(module: &Path, locale: &str) -> Option<PathBuf> {
+    let dir = find_child(module.parent()?, locale);
+    let file_name = module.file_name()?.to_string_lossy();
+    Some(find_child(&dir, &format!("{}.mui", file_name)))
+}
+
+/// 界面语言的区域名称（如 zh-CN），即 .mui 文件所在目录的名称
+#[cfg(target_os = "windows")]
+fn ui_locale() -> Option<String> {
+    use windows::Win32::Globalization::LCIDToLocaleName;
+    // LOCALE_NAME_MAX_LENGTH
+    let mut buf = [0u16; 85];
+    let len = unsafe { LCIDToLocaleName(ui_language() as u32, Some(&mut buf), 0) };
+    // 返回的长度包含结尾的 0
+    (len > 1).then(|| String::from_utf16_lossy(&buf[..len as usize - 1]))
+}
+
+/// 界面语言的区域名称（如 zh-CN），由 LC_ALL/LC_MESSAGES/LANG（如 zh_CN.UTF-8）转换
+#[cfg(not(target_os = "windows"))]
+fn ui_locale() -> Option<String> {
+    let value = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|v| !v.is_empty())?;
+    let name = value.split(['.', '@']).next()?.replace('_', "-");
+    (!name.is_empty() && name != "C" && name != "POSIX").then_some(name)
+}
+
+/// 读取并缓存文件的解析结果，文件修改时间变化（或文件出现、消失）时重新读取
+fn cached<T>(
+    cache: &'static FileCache<T>,
+    path: &Path,
+    load: impl FnOnce(&Path) -> Option<T>,
+) -> Option<Arc<T>> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let cache = cache.get_or_init(Default::default);
+    if let Some((time, value)) = cache.lock().ok()?.get(path) {
+        if *time == modified {
+            return value.clone();
+        }
+    }
+    let value = modified.and_then(|_| load(path)).map(Arc::new);
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(path.to_path_buf(), (modified, value.clone()));
+    }
+    value
+}
+
+/// desktop.ini 解码：UTF-16 LE（带 BOM）、UTF-8，其余按 ANSI（GBK）处理
+pub(crate) fn decode_ini(data: &[u8]) -> String {
+    if let Some(rest) = data.strip_prefix(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = rest
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let data = data.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(data);
+    match std::str::from_utf8(data) {
+        Ok(text) => text.to_string(),
+        Err(_) => encoding_rs::GBK.decode(data).0.to_string(),
+    }
+}
+
+/// 读取 ini 中指定节的值，节名与键名不区分大小写
+pub(crate) fn ini_value(text: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name.trim().eq_ignore_ascii_case(section);
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some((name, value)) = line.split_once('=') {
+            if name.trim().to_lowercase() == key.to_lowercase() {
+                return Some(value.trim().to_string()).filter(|v| !v.is_empty());
+            }
+        }
+    }
+    None
+}
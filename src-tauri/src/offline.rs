@@ -0,0 +1,364 @@
+// 离线 Windows 分区 - 在其它系统上（如 Linux 挂载的 /mnt/c）按 Windows 的目录布局查找文件与注册表
+//
+// 目录结构：
+//   <根目录>/Windows/System32/config/SOFTWARE   本机注册表
+//   <根目录>/Users/<用户名>/NTUSER.DAT          用户注册表
+// 注册表与快捷方式中记录的路径以 C:\ 开头，展开环境变量后映射到根目录下。
+//
+// Wine 前缀的布局不同（Linux）：
+//   <前缀>/drive_c                C: 盘
+//   <前缀>/system.reg, user.reg   本机与当前用户的注册表
+//   <前缀>/dosdevices/<盘符>:      其它盘符（如 z: 指向 /）
+
+use crate::config::scan_settings;
+use crate::path::{expand_known_folder, PathResolver};
+use crate::registry::{self, Registry, RegistryRoot};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+const SYSTEM_DRIVE: &str = "C:";
+
+// Users 下不属于真实用户的目录
+const SKIPPED_PROFILES: &[&str] = &[
+    "Public",
+    "Default",
+    "Default User",
+    "All Users",
+    "defaultuser0",
+];
+
+const CURRENT_VERSION_KEY: &str = "SOFTWARE\\Microsoft\\Windows\\CurrentVersion";
+const USER_SHELL_FOLDERS_KEY: &str =
+    "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\User Shell Folders";
+
+const COMMON_SHELL_FOLDERS_KEY: &str =
+    "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Explorer\\User Shell Folders";
+
+type LazyRegistry = OnceLock<Option<Box<dyn Registry>>>;
+
+/// 挂载的 Windows 分区或 Wine 前缀，注册表在首次使用时加载
+pub struct WindowsRoot {
+    root: PathBuf,
+    software_path: PathBuf,
+    software: LazyRegistry,
+    users: Vec<UserProfile>,
+    // C: 以外的盘符及其本地目录
+    drives: Vec<(String, PathBuf)>,
+}
+
+/// Users 下的一个用户配置文件
+pub struct UserProfile {
+    pub name: String,
+    hive_path: PathBuf,
+    hive: LazyRegistry,
+}
+
+impl UserProfile {
+    /// 用户注册表（NTUSER.DAT 或 Wine 的 user.reg，挂载到 HKEY_CURRENT_USER）
+    pub fn hive(&self) -> Option<&dyn Registry> {
+        self.hive
+            .get_or_init(|| load_hive(&self.hive_path))
+            .as_deref()
+    }
+}
+
+impl WindowsRoot {
+    pub fn open(root: &Path) -> Result<Self, Box<dyn Error>> {
+        if !find_child(root, "Windows").is_dir() {
+            return Err(format!("{} 不是 Windows 分区的根目录", root.display()).into());
+        }
+        let software_path = join_components(root, &["Windows", "System32", "config", "SOFTWARE"]);
+        let opened = Self::new(root, software_path, |profile| {
+            find_child(profile, "NTUSER.DAT")
+        });
+        println!(
+            "🔍 [扫描阶段] 打开离线 Windows 分区: {:?}, 用户: {:?}",
+            root,
+            opened.user_names()
+        );
+        Ok(opened)
+    }
+
+    /// 打开 Wine 前缀，所有用户共用前缀中的 user.reg
+    #[cfg(target_os = "linux")]
+    pub fn open_wine(prefix: &Path) -> Result<Self, Box<dyn Error>> {
+        let root = prefix.join("drive_c");
+        if !root.is_dir() {
+            return Err(format!("{} 不是 Wine 前缀", prefix.display()).into());
+        }
+        let user_hive = prefix.join("user.reg");
+        let mut opened = Self::new(&root, prefix.join("system.reg"), |_| user_hive.clone());
+
+        // dosdevices 中的盘符是指向本地目录的符号链接，c: 即 drive_c
+        opened.drives = std::fs::read_dir(prefix.join("dosdevices"))
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let drive = e.file_name().to_string_lossy().to_ascii_uppercase();
+                let is_drive = drive.len() == 2
+                    && drive.ends_with(':')
+                    && drive.starts_with(|c: char| c.is_ascii_alphabetic());
+                if !is_drive || drive == SYSTEM_DRIVE {
+                    return None;
+                }
+                Some((drive, std::fs::canonicalize(e.path()).ok()?))
+            })
+            .collect();
+
+        println!(
+            "🔍 [扫描阶段] 打开 Wine 前缀: {:?}, 用户: {:?}",
+            prefix,
+            opened.user_names()
+        );
+        Ok(opened)
+    }
+
+    /// 列出 Users 下的用户，user_hive 根据用户目录给出其注册表文件
+    fn new(root: &Path, software_path: PathBuf, user_hive: impl Fn(&Path) -> PathBuf) -> Self {
+        // file_type 不跟随符号链接，Users 下的链接目录一并跳过
+        let mut users: Vec<UserProfile> = std::fs::read_dir(find_child(root, "Users"))
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                let skipped = SKIPPED_PROFILES
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(&name));
+                (!skipped).then(|| UserProfile {
+                    name,
+                    hive_path: user_hive(&e.path()),
+                    hive: OnceLock::new(),
+                })
+            })
+            .collect();
+        users.sort_by(|a, b| a.name.cmp(&b.name));
+
+        WindowsRoot {
+            root: root.to_path_buf(),
+            software_path,
+            software: OnceLock::new(),
+            users,
+            drives: Vec::new(),
+        }
+    }
+
+    fn user_names(&self) -> Vec<&str> {
+        self.users.iter().map(|u| u.name.as_str()).collect()
+    }
+
+    pub fn users(&self) -> &[UserProfile] {
+        &self.users
+    }
+
+    /// 本机注册表（SOFTWARE hive 或 Wine 的 system.reg，挂载到 HKEY_LOCAL_MACHINE\SOFTWARE）
+    pub fn software(&self) -> Option<&dyn Registry> {
+        self.software
+            .get_or_init(|| load_hive(&self.software_path))
+            .as_deref()
+    }
+
+    /// 将 Windows 绝对路径映射到本地目录，未知盘符、网络路径与相对路径返回 None
+    pub fn to_host(&self, path: &str) -> Option<PathBuf> {
+        let path = path.trim().trim_matches('"');
+        let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+        let drive = path.get(..2)?;
+        let rest = &path[2..];
+        if !(rest.is_empty() || rest.starts_with(['\\', '/'])) {
+            return None;
+        }
+        let base = if drive.eq_ignore_ascii_case(SYSTEM_DRIVE) {
+            &self.root
+        } else {
+            &self
+                .drives
+                .iter()
+                .find(|(d, _)| d.eq_ignore_ascii_case(drive))?
+                .1
+        };
+        let components: Vec<&str> = rest
+            .split(['\\', '/'])
+            .filter(|c| !c.is_empty() && *c != "." && *c != "..")
+            .collect();
+        Some(join_components(base, &components))
+    }
+
+    /// 展开 %VAR%，user 为 None 时只有系统变量，未知变量保持原样
+    pub fn expand(&self, path: &str, user: Option<&UserProfile>) -> String {
+        let mut result = String::new();
+        let mut rest = path;
+        while let Some(start) = rest.find('%') {
+            let Some(len) = rest[start + 1..].find('%') else {
+                break;
+            };
+            let name = &rest[start + 1..start + 1 + len];
+            result.push_str(&rest[..start]);
+            match self.variable(name, user) {
+                Some(value) => result.push_str(&value),
+                None => result.push_str(&rest[start..start + len + 2]),
+            }
+            rest = &rest[start + len + 2..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// 含环境变量的 Windows 文件夹路径对应的本地目录
+    pub fn folder(&self, path: &str, user: Option<&UserProfile>) -> Option<PathBuf> {
+        self.to_host(&self.expand(path, user))
+    }
+
+    /// 用户文件夹：优先使用用户注册表 User Shell Folders 中登记的位置（可能被重定向）
+    pub fn user_folder(
+        &self,
+        user: &UserProfile,
+        shell_folder: Option<&str>,
+        default: &str,
+    ) -> Option<PathBuf> {
+        let registered = shell_folder.and_then(|name| {
+            user.hive()?
+                .string(RegistryRoot::CurrentUser, USER_SHELL_FOLDERS_KEY, name)
+        });
+        self.folder(registered.as_deref().unwrap_or(default), Some(user))
+    }
+
+    /// 公共文件夹：优先使用 SOFTWARE 中 User Shell Folders 登记的位置（如 Common Programs）
+    pub fn common_folder(&self, shell_folder: &str, default: &str) -> Option<PathBuf> {
+        let registered = self.software().and_then(|r| {
+            r.string(
+                RegistryRoot::LocalMachine,
+                COMMON_SHELL_FOLDERS_KEY,
+                shell_folder,
+            )
+        });
+        self.folder(registered.as_deref().unwrap_or(default), None)
+    }
+
+    fn variable(&self, name: &str, user: Option<&UserProfile>) -> Option<String> {
+        let name = name.to_ascii_uppercase();
+        let value = match name.as_str() {
+            "SYSTEMDRIVE" | "HOMEDRIVE" => SYSTEM_DRIVE.to_string(),
+            "SYSTEMROOT" | "WINDIR" => format!("{}\\Windows", SYSTEM_DRIVE),
+            "PROGRAMFILES" | "PROGRAMW6432" => {
+                self.registered_dir("ProgramFilesDir", "Program Files")
+            }
+            "PROGRAMFILES(X86)" => {
+                self.registered_dir("ProgramFilesDir (x86)", "Program Files (x86)")
+            }
+            "COMMONPROGRAMFILES" | "COMMONPROGRAMW6432" => {
+                self.registered_dir("CommonFilesDir", "Program Files\\Common Files")
+            }
+            "COMMONPROGRAMFILES(X86)" => {
+                self.registered_dir("CommonFilesDir (x86)", "Program Files (x86)\\Common Files")
+            }
+            "PROGRAMDATA" | "ALLUSERSPROFILE" => format!("{}\\ProgramData", SYSTEM_DRIVE),
+            "PUBLIC" => format!("{}\\Users\\Public", SYSTEM_DRIVE),
+            _ => {
+                let user = user?;
+                let profile = format!("{}\\Users\\{}", SYSTEM_DRIVE, user.name);
+                match name.as_str() {
+                    "USERPROFILE" => profile,
+                    "HOMEPATH" => format!("\\Users\\{}", user.name),
+                    "USERNAME" => user.name.clone(),
+                    "APPDATA" => format!("{}\\AppData\\Roaming", profile),
+                    "LOCALAPPDATA" => format!("{}\\AppData\\Local", profile),
+                    "TEMP" | "TMP" => format!("{}\\AppData\\Local\\Temp", profile),
+                    _ => return None,
+                }
+            }
+        };
+        Some(value)
+    }
+
+    /// SOFTWARE 中登记的系统目录，未登记时使用默认位置
+    fn registered_dir(&self, value: &str, default: &str) -> String {
+        self.software()
+            .and_then(|r| r.string(RegistryRoot::LocalMachine, CURRENT_VERSION_KEY, value))
+            .unwrap_or_else(|| format!("{}\\{}", SYSTEM_DRIVE, default))
+    }
+}
+
+/// 离线分区的路径转换，user 决定 %USERPROFILE% 等用户变量
+pub struct OfflinePaths<'a> {
+    pub root: &'a WindowsRoot,
+    pub user: Option<&'a UserProfile>,
+}
+
+impl PathResolver for OfflinePaths<'_> {
+    /// 无法映射的路径（其它盘符等）保留展开后的 Windows 形式
+    fn resolve(&self, path: &str) -> String {
+        let expanded = self.root.expand(&expand_known_folder(path), self.user);
+        self.root
+            .to_host(&expanded)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or(expanded)
+    }
+}
+
+/// 设置中的离线根目录，未设置时扫描本机
+pub fn offline_root() -> Option<String> {
+    scan_settings()
+        .windows_root
+        .filter(|root| !root.trim().is_empty())
+}
+
+// 已打开的根目录，扫描之间复用
+static CURRENT: OnceLock<Mutex<Option<Arc<WindowsRoot>>>> = OnceLock::new();
+
+/// 打开根目录，根目录不变时复用已加载的注册表
+pub fn shared_root(root: &str) -> Result<Arc<WindowsRoot>, Box<dyn Error>> {
+    let root = Path::new(root.trim());
+    let mut current = CURRENT
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|_| "离线分区状态不可用")?;
+    if let Some(opened) = current.as_ref().filter(|r| r.root == root) {
+        return Ok(opened.clone());
+    }
+    let opened = Arc::new(WindowsRoot::open(root)?);
+    *current = Some(opened.clone());
+    Ok(opened)
+}
+
+/// 丢弃已打开的根目录，设置变化或开始扫描时重新加载注册表与用户列表
+pub fn reset_shared_root() {
+    if let Some(Ok(mut current)) = CURRENT.get().map(|c| c.lock()) {
+        *current = None;
+    }
+}
+
+fn load_hive(path: &Path) -> Option<Box<dyn Registry>> {
+    if !path.is_file() {
+        return None;
+    }
+    registry::open_file(path)
+        .map_err(|e| eprintln!("加载注册表文件失败 {:?}: {}", path, e))
+        .ok()
+}
+
+/// 逐级按不区分大小写的名称拼接路径
+fn join_components(base: &Path, components: &[&str]) -> PathBuf {
+    components
+        .iter()
+        .fold(base.to_path_buf(), |dir, name| find_child(&dir, name))
+}
+
+/// 在目录中按不区分大小写的名称查找子项，找不到时按原名拼接
+pub(crate) fn find_child(dir: &Path, name: &str) -> PathBuf {
+    let exact = dir.join(name);
+    if exact.exists() {
+        return exact;
+    }
+    std::fs::read_dir(dir)
+        .ok()
+        .and_then(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .find(|e| e.file_name().to_string_lossy().eq_ignore_ascii_case(name))
+                .map(|e| e.path())
+        })
+        .unwrap_or(exact)
+}
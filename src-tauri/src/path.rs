@@ -1,6 +1,5 @@
 // 路径处理模块
 
-#[cfg(target_os = "windows")]
 use std::env;
 #[cfg(target_os = "windows")]
 use std::path::PathBuf;
@@ -10,56 +9,151 @@ use windows::{
     Win32::Foundation::HANDLE,
     Win32::System::Com::CoTaskMemFree,
     Win32::UI::Shell::{
-        FOLDERID_CommonPrograms, FOLDERID_Desktop, FOLDERID_ProgramFiles, FOLDERID_ProgramFilesX86,
-        FOLDERID_Programs, FOLDERID_Public, FOLDERID_PublicDesktop, FOLDERID_System,
-        FOLDERID_SystemX86, FOLDERID_UserProfiles, FOLDERID_Windows, SHGetKnownFolderPath,
-        KF_FLAG_DEFAULT,
+        FOLDERID_CommonPrograms, FOLDERID_Desktop, FOLDERID_Programs, FOLDERID_PublicDesktop,
+        SHGetKnownFolderPath, KF_FLAG_DEFAULT,
     },
 };
 
-/// 将 GUID 格式的路径规范化为物理路径
+// KNOWNFOLDERID 与默认位置，快捷方式目标常记录为 {GUID}\相对路径
+// 默认位置用于离线分区，以及本机查询失败的情况
+#[rustfmt::skip]
+const KNOWN_FOLDERS: &[(&str, &str)] = &[
+    // 系统与程序目录
+    ("F38BF404-1D43-42F2-9305-67DE0B28FC23", r"%SystemRoot%"),
+    ("1AC14E77-02E7-4E5D-B744-2EB1AE5198B7", r"%SystemRoot%\System32"),
+    ("D65231B0-B2F1-4857-A4CE-A8E7C6EA7D27", r"%SystemRoot%\SysWOW64"),
+    ("FD228CB7-AE11-4AE3-864C-16F3910AB8FE", r"%SystemRoot%\Fonts"),
+    ("8AD10C31-2ADB-4296-A8F7-E4701232C972", r"%SystemRoot%\Resources"),
+    ("905E63B6-C1BF-494E-B29C-65B732D3D21A", r"%ProgramFiles%"),
+    ("6D809377-6AF0-444B-8957-A3773F02200E", r"%ProgramW6432%"),
+    ("7C5A40EF-A0FB-4BFC-874A-C0F2E0B9FA8E", r"%ProgramFiles(x86)%"),
+    ("F7F1ED05-9F6D-47A2-AAAE-29D317C6F066", r"%CommonProgramFiles%"),
+    ("6365D5A7-0F0D-45E5-87F6-0DA56B6A4F7D", r"%CommonProgramW6432%"),
+    ("DE974D24-D9C6-4D3E-BF91-F4455120B917", r"%CommonProgramFiles(x86)%"),
+    ("62AB5D82-FDC1-4DC3-A9DD-070D1D495D97", r"%ProgramData%"),
+    ("C1BAE2D0-10DF-4334-BEDD-7AA20B227A9D", r"%ProgramData%\OEM Links"),
+    // 公共开始菜单
+    ("A4115719-D62E-491D-AA7C-E74B8BE3B067", r"%ProgramData%\Microsoft\Windows\Start Menu"),
+    ("0139D44E-6AFE-49F2-8690-3DAFCAE6FFB8", r"%ProgramData%\Microsoft\Windows\Start Menu\Programs"),
+    ("82A5EA35-D9CD-47C5-9629-E15D2F714E6E", r"%ProgramData%\Microsoft\Windows\Start Menu\Programs\StartUp"),
+    ("D0384E7D-BAC3-4797-8F14-CBA229B392B5", r"%ProgramData%\Microsoft\Windows\Start Menu\Programs\Administrative Tools"),
+    ("B94237E7-57AC-4347-9151-B08C6C32D1F7", r"%ProgramData%\Microsoft\Windows\Templates"),
+    // 公共用户目录
+    ("0762D272-C50A-4BB0-A382-697DCD729B80", r"%SystemDrive%\Users"),
+    ("DFDF76A2-C82A-4D63-906A-5644AC457385", r"%PUBLIC%"),
+    ("C4AA340D-F20F-4863-AFEF-F87EF2E6BA25", r"%PUBLIC%\Desktop"),
+    ("ED4824AF-DCE4-45A8-81E2-FC7965083634", r"%PUBLIC%\Documents"),
+    ("3D644C9B-1FB8-4F30-9B45-F670235F79C0", r"%PUBLIC%\Downloads"),
+    ("3214FAB5-9757-4298-BB61-92A9DEAA44FF", r"%PUBLIC%\Music"),
+    ("B6EBFB86-6907-413C-9AF7-4FC2ABF07CC5", r"%PUBLIC%\Pictures"),
+    ("2400183A-6185-49FB-A2D8-4A392A602BA3", r"%PUBLIC%\Videos"),
+    // 当前用户目录
+    ("5E6C858F-0E22-4760-9AFE-EA3317B67173", r"%USERPROFILE%"),
+    ("B4BFCC3A-DB2C-424C-B029-7FE99A87C641", r"%USERPROFILE%\Desktop"),
+    ("FDD39AD0-238F-46AF-ADB4-6C85480369C7", r"%USERPROFILE%\Documents"),
+    ("374DE290-123F-4565-9164-39C4925E467B", r"%USERPROFILE%\Downloads"),
+    ("4BD8D571-6D19-48D3-BE97-422220080E43", r"%USERPROFILE%\Music"),
+    ("33E28130-4E1E-4676-835A-98395C3BC3BB", r"%USERPROFILE%\Pictures"),
+    ("18989B1D-99B5-455B-841C-AB7C74E4DDFC", r"%USERPROFILE%\Videos"),
+    ("1777F761-68AD-4D8A-87BD-30B759FA33DD", r"%USERPROFILE%\Favorites"),
+    ("BFB9D5E0-C6A9-404C-B2B2-AE6DB6AF4968", r"%USERPROFILE%\Links"),
+    ("4C5C32FF-BB9D-43B0-B5B4-2D72E54EAAA4", r"%USERPROFILE%\Saved Games"),
+    ("56784854-C6CB-462B-8169-88E350ACB882", r"%USERPROFILE%\Contacts"),
+    ("A520A1A4-1780-4FF6-BD18-167343C5AF16", r"%USERPROFILE%\AppData\LocalLow"),
+    ("3EB685DB-65F9-4CF6-A03A-E3EF65729F3D", r"%APPDATA%"),
+    ("F1B32785-6FBA-4FCF-9D55-7B8E7F157091", r"%LOCALAPPDATA%"),
+    ("5CD7AEE2-2219-4A67-B85D-6C9CE15660CB", r"%LOCALAPPDATA%\Programs"),
+    ("BCBD3057-CA5C-4622-B42D-BC56DB0AE516", r"%LOCALAPPDATA%\Programs\Common"),
+    ("A3918781-E5F2-4890-B3D9-A7E54332328C", r"%LOCALAPPDATA%\Microsoft\Windows\Application Shortcuts"),
+    // 当前用户开始菜单与 Shell 文件夹
+    ("625B53C3-AB48-4EC1-BA1F-A1EF4146FC19", r"%APPDATA%\Microsoft\Windows\Start Menu"),
+    ("A77F5D77-2E2B-44C3-A6A2-ABA601054A51", r"%APPDATA%\Microsoft\Windows\Start Menu\Programs"),
+    ("B97D20BB-F46A-4C97-BA10-5E3608430854", r"%APPDATA%\Microsoft\Windows\Start Menu\Programs\Startup"),
+    ("724EF170-A42D-4FEF-9F26-B60E846FBA4F", r"%APPDATA%\Microsoft\Windows\Start Menu\Programs\Administrative Tools"),
+    ("52A4F021-7B75-48A9-9F6B-4B87A210BC8F", r"%APPDATA%\Microsoft\Internet Explorer\Quick Launch"),
+    ("9E3995AB-1F9C-4F13-B827-48B24B6C7174", r"%APPDATA%\Microsoft\Internet Explorer\Quick Launch\User Pinned"),
+    ("BCB5256F-79F6-4CEE-B725-DC34E402FD46", r"%APPDATA%\Microsoft\Internet Explorer\Quick Launch\User Pinned\ImplicitAppShortcuts"),
+    ("8983036C-27C0-404B-8F08-102D10DCFD74", r"%APPDATA%\Microsoft\Windows\SendTo"),
+    ("A63293E8-664E-48DB-A079-DF759E0509F7", r"%APPDATA%\Microsoft\Windows\Templates"),
+    ("AE50C081-EBD2-438A-8655-8A092E34987A", r"%APPDATA%\Microsoft\Windows\Recent"),
+];
+
+/// 规范化快捷方式与注册表中记录的路径：已知文件夹 GUID 换为实际位置，并展开 %VAR%
 /// 例如：{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\services.msc -> C:\Windows\System32\services.msc
-#[cfg(target_os = "windows")]
 pub fn normalize_path(path: &str) -> String {
-    if !path.starts_with('{') {
-        return path.to_string();
-    }
+    expand_env_vars(&known_folder_path(path))
+}
 
-    let end_bracket = match path.find('}') {
-        Some(pos) => pos,
-        None => return path.to_string(),
-    };
-
-    let guid_str = &path[1..end_bracket];
-    let remaining = &path[end_bracket + 1..].trim_start_matches('\\');
-
-    let folder_id = match guid_str.to_uppercase().as_str() {
-        "1AC14E77-02E7-4E5D-B744-2EB1AE5198B7" => Some(&FOLDERID_System),
-        "F38BF404-1D43-42F2-9305-67DE0B28FC23" => Some(&FOLDERID_Windows),
-        "D65231B0-B2F1-4857-A4CE-A8E7C6EA7D27" => Some(&FOLDERID_SystemX86),
-        "7C5A40EF-A0FB-4BFC-874A-C0F2E0B9FA8E" => Some(&FOLDERID_ProgramFiles),
-        "50233421-DB61-45A4-9A2F-F5962B859194" => Some(&FOLDERID_ProgramFilesX86),
-        "AE054212-3519-4430-83ED-D70627221F3C" => Some(&FOLDERID_UserProfiles),
-        "DFDF76A2-C82A-4D63-906A-5644AC457385" => Some(&FOLDERID_Public),
-        _ => None,
-    };
-
-    if let Some(fid) = folder_id {
-        if let Ok(base_path) = get_known_folder_path(fid) {
-            let mut full_path = base_path;
-            if !remaining.is_empty() {
-                full_path.push(remaining);
-            }
-            return full_path.to_string_lossy().to_string();
-        }
+/// 将开头的已知文件夹 GUID 换为默认位置（保留 %VAR%），其它路径原样返回
+pub fn expand_known_folder(path: &str) -> String {
+    match split_known_folder(path) {
+        Some((_, default, "")) => default.to_string(),
+        Some((_, default, rest)) => format!("{}\\{}", default, rest),
+        None => path.to_string(),
     }
+}
+
+/// 拆分 {GUID}\相对路径：(GUID, 默认位置, 相对路径)，GUID 不在表中时返回 None
+fn split_known_folder(path: &str) -> Option<(&'static str, &'static str, &str)> {
+    let (guid, rest) = path.strip_prefix('{')?.split_once('}')?;
+    let (id, default) = KNOWN_FOLDERS
+        .iter()
+        .find(|(id, _)| id.eq_ignore_ascii_case(guid))?;
+    Some((id, default, rest.trim_start_matches(['\\', '/'])))
+}
 
-    path.to_string()
+/// 本机优先通过 SHGetKnownFolderPath 取得文件夹的实际位置（可能被重定向）
+#[cfg(target_os = "windows")]
+fn known_folder_path(path: &str) -> String {
+    let live = split_known_folder(path).and_then(|(guid, _, rest)| {
+        let mut full_path = get_known_folder_path(&GUID::try_from(guid).ok()?).ok()?;
+        if !rest.is_empty() {
+            full_path.push(rest);
+        }
+        Some(full_path.to_string_lossy().to_string())
+    });
+    live.unwrap_or_else(|| expand_known_folder(path))
 }
 
 #[cfg(not(target_os = "windows"))]
-pub fn normalize_path(path: &str) -> String {
-    path.to_string()
+fn known_folder_path(path: &str) -> String {
+    expand_known_folder(path)
+}
+
+/// 将注册表与快捷方式中记录的 Windows 路径转换为可访问的路径
+///
+/// 本机扫描只需展开已知文件夹与环境变量；离线扫描还要把盘符映射到挂载的根目录。
+pub trait PathResolver: Sync {
+    fn resolve(&self, path: &str) -> String;
+}
+
+/// 本机路径：展开已知文件夹与 %VAR% 环境变量
+pub struct LocalPaths;
+
+impl PathResolver for LocalPaths {
+    fn resolve(&self, path: &str) -> String {
+        normalize_path(path)
+    }
+}
+
+/// 展开路径中的 %VAR% 环境变量，未定义的变量保持原样
+pub fn expand_env_vars(path: &str) -> String {
+    let mut result = String::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        result.push_str(&rest[..start]);
+        match env::var(name) {
+            Ok(value) if !name.is_empty() => result.push_str(&value),
+            _ => result.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    result.push_str(rest);
+    result
 }
 
 #[cfg(target_os = "windows")]
@@ -0,0 +1,164 @@
+// 图标读取 - 从 PE 的 RT_GROUP_ICON/RT_ICON 资源或 .ico 文件中取最大的一张图标
+//
+// 图标组（GRPICONDIR）与 .ico 文件目录结构相同，区别在于每项最后的 4 字节：
+// 图标组中为 RT_ICON 资源 ID（2 字节），.ico 文件中为图像数据的文件偏移。
+// 图像数据为 PNG 或不带 BITMAPFILEHEADER 的 DIB，DIB 封装成单图 .ico 后解码再转为 PNG。
+
+use super::{read_u16, read_u32, PeImage, ResourceId};
+use crate::types::IconData;
+use base64::prelude::*;
+use std::io::Cursor;
+use std::path::Path;
+
+const RT_ICON: u16 = 3;
+const RT_GROUP_ICON: u16 = 14;
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// 图标目录项
+struct IconEntry {
+    width: u32,
+    height: u32,
+    bit_count: u16,
+    /// 图标组中为 RT_ICON 资源 ID，.ico 文件中为数据偏移
+    location: u32,
+    size: u32,
+}
+
+/// 读取文件中的图标：.ico 文件取最大的一张，PNG 与 SVG 文件（图标主题中的图标）直接使用，
+/// PE 文件按索引选择图标组
+///
+/// 索引与 ExtractIcon 一致：非负数为第几个图标组，负数为图标组的资源 ID。
+pub fn read_icon(path: &Path, index: i32) -> Option<IconData> {
+    let has_extension = |ext: &str| {
+        path.extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+    };
+    if has_extension("png") {
+        let png = std::fs::read(path).ok()?;
+        return png.starts_with(PNG_SIGNATURE).then(|| png_data(png))?;
+    }
+    if has_extension("svg") {
+        return svg_data(std::fs::read(path).ok()?);
+    }
+    let is_ico = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("ico"));
+    let (entry, data) = if is_ico {
+        read_ico_file(path)?
+    } else {
+        read_pe_icon(path, index)?
+    };
+    encode_png(&entry, &data)
+}
+
+fn read_ico_file(path: &Path) -> Option<(IconEntry, Vec<u8>)> {
+    let file = std::fs::read(path).ok()?;
+    let entry = best_entry(parse_directory(&file, 16)?)?;
+    let start = entry.location as usize;
+    let data = file.get(start..start.checked_add(entry.size as usize)?)?;
+    Some((entry, data.to_vec()))
+}
+
+fn read_pe_icon(path: &Path, index: i32) -> Option<(IconEntry, Vec<u8>)> {
+    let image = PeImage::open(path).ok()?;
+    let mut groups = image.named_resources(&ResourceId::Id(RT_GROUP_ICON)).ok()?;
+    // 同一图标组的多个语言版本只取第一个
+    groups.dedup_by(|a, b| a.0 == b.0);
+    let group = if index >= 0 {
+        groups.get(index as usize)?
+    } else {
+        let id = ResourceId::Id(index.unsigned_abs() as u16);
+        groups.iter().find(|(name, _)| *name == id)?
+    };
+
+    let entry = best_entry(parse_directory(&group.1.data, 14)?)?;
+    let icon_id = ResourceId::Id(entry.location as u16);
+    let data = image
+        .named_resources(&ResourceId::Id(RT_ICON))
+        .ok()?
+        .into_iter()
+        .find(|(name, _)| *name == icon_id)
+        .map(|(_, resource)| resource.data)?;
+    Some((entry, data))
+}
+
+/// 解析目录头（保留 0、类型 1、数量）与各项，entry_size 为 16（.ico）或 14（图标组）
+fn parse_directory(data: &[u8], entry_size: usize) -> Option<Vec<IconEntry>> {
+    if read_u16(data, 2)? != 1 {
+        return None;
+    }
+    let count = read_u16(data, 4)? as usize;
+    let entries = (0..count)
+        .filter_map(|i| {
+            let at = 6 + i * entry_size;
+            let dimension = |b: u8| if b == 0 { 256 } else { b as u32 };
+            let location = if entry_size == 16 {
+                read_u32(data, at + 12)?
+            } else {
+                read_u16(data, at + 12)? as u32
+            };
+            Some(IconEntry {
+                width: dimension(*data.get(at)?),
+                height: dimension(*data.get(at + 1)?),
+                bit_count: read_u16(data, at + 6)?,
+                size: read_u32(data, at + 8)?,
+                location,
+            })
+        })
+        .collect();
+    Some(entries)
+}
+
+/// 面积最大者优先，面积相同时位深更高者优先
+fn best_entry(entries: Vec<IconEntry>) -> Option<IconEntry> {
+    entries
+        .into_iter()
+        .max_by_key(|e| (e.width * e.height, e.bit_count))
+}
+
+/// PNG 数据直接使用，DIB 数据封装为单图 .ico 后解码
+fn encode_png(entry: &IconEntry, data: &[u8]) -> Option<IconData> {
+    let png = if data.starts_with(PNG_SIGNATURE) {
+        data.to_vec()
+    } else {
+        let mut ico = Vec::with_capacity(22 + data.len());
+        ico.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+        // 解码器要求目录项尺寸与 DIB 一致，256 写作 0
+        ico.extend_from_slice(&[entry.width as u8, entry.height as u8, 0, 0, 1, 0]);
+        ico.extend_from_slice(&entry.bit_count.to_le_bytes());
+        ico.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&22u32.to_le_bytes());
+        ico.extend_from_slice(data);
+        let image = image::load_from_memory_with_format(&ico, image::ImageFormat::Ico).ok()?;
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .ok()?;
+        png
+    };
+    png_data(png)
+}
+
+/// 矢量图标不做栅格化，以 data URL 交给前端显示，尺寸按可缩放的最大图标计
+fn svg_data(svg: Vec<u8>) -> Option<IconData> {
+    let head = String::from_utf8_lossy(&svg[..svg.len().min(1024)]).to_lowercase();
+    if !head.contains("<svg") {
+        return None;
+    }
+    Some(IconData {
+        base64: format!("data:image/svg+xml;base64,{}", BASE64_STANDARD.encode(&svg)),
+        width: 256,
+        height: 256,
+    })
+}
+
+fn png_data(png: Vec<u8>) -> Option<IconData> {
+    // IHDR 中的宽高为大端序
+    let width = u32::from_be_bytes(png.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(png.get(20..24)?.try_into().ok()?);
+    Some(IconData {
+        base64: format!("data:image/png;base64,{}", BASE64_STANDARD.encode(&png)),
+        width,
+        height,
+    })
+}
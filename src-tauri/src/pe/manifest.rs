@@ -0,0 +1,176 @@
+// 应用程序清单（RT_MANIFEST）解析
+
+use super::{PeImage, ResourceId, RT_MANIFEST};
+use std::path::Path;
+
+// supportedOS 的 GUID 与系统版本对应关系
+const SUPPORTED_OS_IDS: &[(&str, &str)] = &[
+    ("{e2011457-1546-43c5-a5fe-008deee3d3f0}", "Windows Vista"),
+    ("{35138b9a-5d96-4fbd-8e2d-a2440225f93a}", "Windows 7"),
+    ("{4a2f28e3-53b9-4441-ba9c-d69d4a4a6e38}", "Windows 8"),
+    ("{1f676c76-80e1-4239-95bb-83d0f6d0da78}", "Windows 8.1"),
+    ("{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}", "Windows 10"),
+];
+
+/// 清单中与启动相关的信息
+#[derive(Debug, Clone, Default)]
+pub struct ManifestInfo {
+    /// requestedExecutionLevel：asInvoker/highestAvailable/requireAdministrator
+    pub execution_level: Option<String>,
+    pub ui_access: bool,
+    /// dpiAwareness 优先，其次 dpiAware，如 "PerMonitorV2"、"true"
+    pub dpi_awareness: Option<String>,
+    /// 声明支持的系统版本，未知 GUID 原样保留
+    pub supported_os: Vec<String>,
+}
+
+impl ManifestInfo {
+    /// 启动时必须提升权限
+    pub fn requires_elevation(&self) -> bool {
+        self.execution_level.as_deref() == Some("requireAdministrator")
+    }
+}
+
+/// 读取 PE 文件嵌入的应用程序清单（资源 ID 1 为 exe 清单）
+pub fn read_manifest_info(path: &Path) -> Option<ManifestInfo> {
+    let image = PeImage::open(path).ok()?;
+    let resources = image.resources(&ResourceId::Id(RT_MANIFEST)).ok()?;
+    resources
+        .iter()
+        .find_map(|r| parse_manifest(&decode_xml(&r.data)))
+}
+
+/// 解析清单 XML，只关心少数几个元素，不做完整的 XML 校验
+pub fn parse_manifest(xml: &str) -> Option<ManifestInfo> {
+    if !xml.contains("assembly") {
+        return None;
+    }
+
+    let mut info = ManifestInfo::default();
+    let mut dpi_aware = None;
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with(['/', '?', '!']) {
+            continue;
+        }
+
+        let (name, attrs) = tag
+            .trim_end_matches('/')
+            .split_once(char::is_whitespace)
+            .unwrap_or((tag.trim_end_matches('/'), ""));
+        match local_name(name) {
+            "requestedExecutionLevel" => {
+                info.execution_level = attribute(attrs, "level");
+                info.ui_access =
+                    attribute(attrs, "uiAccess").is_some_and(|v| v.eq_ignore_ascii_case("true"));
+            }
+            "supportedOS" => {
+                if let Some(id) = attribute(attrs, "Id") {
+                    let id = id.to_lowercase();
+                    let name = SUPPORTED_OS_IDS
+                        .iter()
+                        .find(|(guid, _)| *guid == id)
+                        .map(|(_, name)| name.to_string())
+                        .unwrap_or(id);
+                    info.supported_os.push(name);
+                }
+            }
+            "dpiAwareness" if !tag.ends_with('/') => {
+                info.dpi_awareness = element_text(rest);
+            }
+            "dpiAware" if !tag.ends_with('/') => {
+                dpi_aware = element_text(rest);
+            }
+            _ => {}
+        }
+    }
+
+    // dpiAwareness 是逗号分隔的回退列表，取第一项
+    info.dpi_awareness = info
+        .dpi_awareness
+        .and_then(|v| v.split(',').next().map(|s| s.trim().to_string()))
+        .filter(|v| !v.is_empty())
+        .or(dpi_aware);
+    Some(info)
+}
+
+/// 去掉命名空间前缀，如 "asmv3:dpiAware"
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let mut rest = attrs;
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim();
+        let value = rest[eq + 1..].trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = &value[1..];
+        let close = value.find(quote)?;
+        if local_name(key) == name {
+            return Some(value[..close].trim().to_string());
+        }
+        rest = &value[close + 1..];
+    }
+    None
+}
+
+/// 元素的文本内容（到下一个标签为止）
+fn element_text(rest: &str) -> Option<String> {
+    let text = rest[..rest.find('<').unwrap_or(rest.len())].trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// 清单通常为 UTF-8，少数为 UTF-16 LE（带 BOM）
+fn decode_xml(data: &[u8]) -> String {
+    if data.starts_with(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = data[2..]
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let data = data.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(data);
+    String::from_utf8_lossy(data).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"<?xml version="1.0"?>
+<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+  <trustInfo><security><requestedPrivileges>
+    <requestedExecutionLevel level="requireAdministrator" uiAccess="false"/>
+  </requestedPrivileges></security></trustInfo>
+  <compatibility><application>
+    <supportedOS Id="{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}"/>
+  </application></compatibility>
+  <application><windowsSettings>
+    <dpiAwareness xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings">PerMonitorV2, PerMonitor</dpiAwareness>
+  </windowsSettings></application>
+</assembly>"#;
+
+    #[test]
+    fn reads_manifest() {
+        let info = parse_manifest(MANIFEST).unwrap();
+        assert!(info.requires_elevation());
+        assert_eq!(info.supported_os, vec!["Windows 10".to_string()]);
+        assert_eq!(info.dpi_awareness.as_deref(), Some("PerMonitorV2"));
+    }
+
+    #[test]
+    fn truncated_manifest_does_not_panic() {
+        for end in (0..=MANIFEST.len()).filter(|&i| MANIFEST.is_char_boundary(i)) {
+            let _ = parse_manifest(&MANIFEST[..end]);
+        }
+        let _ = parse_manifest("<assembly <requestedExecutionLevel level=\"a level='b>");
+        assert_eq!(decode_xml(&[0xFF, 0xFE, 0x41]), "");
+    }
+}
@@ -0,0 +1,618 @@
+// PE 文件解析模块 - 纯 Rust 实现，不依赖 Win32 API，可用于离线文件
+
+pub mod icon; // RT_GROUP_ICON 图标组、.ico 与图标主题中的文件（Windows 上程序图标由系统 API 提取）
+pub mod manifest; // RT_MANIFEST 应用程序清单
+pub mod signature; // Authenticode 签名
+pub mod string; // RT_STRING 字符串表（间接字符串）
+pub mod version; // VS_VERSIONINFO 版本资源
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+// 资源类型常量
+pub const RT_VERSION: u16 = 16;
+pub const RT_MANIFEST: u16 = 24;
+
+// 数据目录索引
+pub const IMAGE_DIRECTORY_ENTRY_SECURITY: usize = 4;
+pub const IMAGE_DIRECTORY_ENTRY_RESOURCE: usize = 2;
+pub const IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG: usize = 10;
+
+// e_lfanew 的上限，正常的 PE 头位于文件开头附近，避免畸形的偏移导致读取整个文件
+const MAX_PE_OFFSET: usize = 0x10000;
+
+// COFF 机器类型
+pub const IMAGE_FILE_MACHINE_I386: u16 = 0x014C;
+pub const IMAGE_FILE_MACHINE_ARMNT: u16 = 0x01C4;
+pub const IMAGE_FILE_MACHINE_IA64: u16 = 0x0200;
+pub const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+pub const IMAGE_FILE_MACHINE_ARM64: u16 = 0xAA64;
+
+// 子系统
+pub const IMAGE_SUBSYSTEM_NATIVE: u16 = 1;
+pub const IMAGE_SUBSYSTEM_WINDOWS_GUI: u16 = 2;
+pub const IMAGE_SUBSYSTEM_WINDOWS_CUI: u16 = 3;
+
+// COFF 文件特征
+pub const IMAGE_FILE_DLL: u16 = 0x2000;
+
+// IMAGE_LOAD_CONFIG_DIRECTORY64 中 CHPEMetadataPointer 的偏移
+const LOAD_CONFIG_CHPE_METADATA_OFFSET: usize = 0xC8;
+
+// 单个资源数据的大小上限，防止损坏的文件导致超大内存分配
+const MAX_RESOURCE_SIZE: u32 = 16 * 1024 * 1024;
+
+/// 资源标识：数字 ID 或字符串名称
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceId {
+    Id(u16),
+    Name(String),
+}
+
+/// 一条资源数据（已解析到具体语言）
+#[derive(Debug, Clone)]
+pub struct Resource {
+    pub language: u16,
+    pub data: Vec<u8>,
+}
+
+/// 节表项
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub virtual_size: u32,
+    pub virtual_address: u32,
+    pub raw_size: u32,
+    pub raw_offset: u32,
+}
+
+/// 已解析头部的 PE 文件，数据按需从磁盘读取
+pub struct PeImage {
+    path: PathBuf,
+    pub file_size: u64,
+    /// COFF 头中的机器类型
+    pub machine: u16,
+    /// COFF 头中的文件特征
+    pub characteristics: u16,
+    /// 可选头中的子系统
+    pub subsystem: u16,
+    pub is_64: bool,
+    /// 可选头中 CheckSum 字段的文件偏移
+    pub checksum_offset: u64,
+    /// 证书表数据目录项（IMAGE_DIRECTORY_ENTRY_SECURITY）的文件偏移
+    pub security_entry_offset: u64,
+    pub data_directories: Vec<(u32, u32)>,
+    pub sections: Vec<Section>,
+}
+
+impl PeImage {
+    /// 打开并解析 PE 头部
+    pub fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut file = File::open(path)?;
+        let file_size = file.metadata()?.len();
+
+        let mut header = vec![0u8; 4096.min(file_size as usize)];
+        file.read_exact(&mut header)?;
+
+        if header.len() < 0x40 || &header[0..2] != b"MZ" {
+            return Err("不是有效的 PE 文件（缺少 MZ 签名）".into());
+        }
+        let pe_offset = read_u32(&header, 0x3C).ok_or("PE 头偏移无效")? as usize;
+        if pe_offset > MAX_PE_OFFSET {
+            return Err("PE 头偏移超出范围".into());
+        }
+
+        // 节表可能超出首个 4KB，按需补读
+        let coff = pe_offset + 4;
+        let needed = coff + 20;
+        if needed > header.len() {
+            header = read_range(&mut file, 0, needed.min(file_size as usize))?;
+        }
+        if header.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0") {
+            return Err("不是有效的 PE 文件（缺少 PE 签名）".into());
+        }
+
+        let machine = read_u16(&header, coff).ok_or("COFF 头不完整")?;
+        let section_count = read_u16(&header, coff + 2).ok_or("COFF 头不完整")? as usize;
+        let optional_size = read_u16(&header, coff + 16).ok_or("COFF 头不完整")? as usize;
+        let characteristics = read_u16(&header, coff + 18).ok_or("COFF 头不完整")?;
+
+        let optional = coff + 20;
+        let section_table = optional + optional_size;
+        let headers_end = section_table + section_count * 40;
+        if headers_end > header.len() {
+            if headers_end as u64 > file_size {
+                return Err("节表超出文件范围".into());
+            }
+            header = read_range(&mut file, 0, headers_end)?;
+        }
+
+        let magic = read_u16(&header, optional).ok_or("可选头不完整")?;
+        let is_64 = match magic {
+            0x10B => false,
+            0x20B => true,
+            _ => return Err(format!("未知的可选头类型: {:#x}", magic).into()),
+        };
+
+        let subsystem = read_u16(&header, optional + 68).ok_or("可选头不完整")?;
+
+        let (count_offset, dirs_offset) = if is_64 {
+            (optional + 108, optional + 112)
+        } else {
+            (optional + 92, optional + 96)
+        };
+        let dir_count = read_u32(&header, count_offset).unwrap_or(0).min(16) as usize;
+        let data_directories = (0..dir_count)
+            .filter(|i| dirs_offset + i * 8 + 8 <= section_table)
+            .filter_map(|i| {
+                let at = dirs_offset + i * 8;
+                Some((read_u32(&header, at)?, read_u32(&header, at + 4)?))
+            })
+            .collect();
+
+        let sections = (0..section_count)
+            .filter_map(|i| {
+                let at = section_table + i * 40;
+                Some(Section {
+                    virtual_size: read_u32(&header, at + 8)?,
+                    virtual_address: read_u32(&header, at + 12)?,
+                    raw_size: read_u32(&header, at + 16)?,
+                    raw_offset: read_u32(&header, at + 20)?,
+                })
+            })
+            .collect();
+
+        Ok(PeImage {
+            path: path.to_path_buf(),
+            file_size,
+            machine,
+            characteristics,
+            subsystem,
+            is_64,
+            checksum_offset: (optional + 64) as u64,
+            security_entry_offset: (dirs_offset + IMAGE_DIRECTORY_ENTRY_SECURITY * 8) as u64,
+            data_directories,
+            sections,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_dll(&self) -> bool {
+        self.characteristics & IMAGE_FILE_DLL != 0
+    }
+
+    /// 目标架构名称；x64 与 ARM64 需要检查 CHPE 元数据来区分 ARM64EC/ARM64X
+    pub fn architecture(&self) -> Option<&'static str> {
+        match self.machine {
+            IMAGE_FILE_MACHINE_I386 => Some("x86"),
+            IMAGE_FILE_MACHINE_AMD64 if self.has_chpe_metadata() => Some("ARM64EC"),
+            IMAGE_FILE_MACHINE_AMD64 => Some("x64"),
+            IMAGE_FILE_MACHINE_ARM64 if self.has_chpe_metadata() => Some("ARM64X"),
+            IMAGE_FILE_MACHINE_ARM64 => Some("ARM64"),
+            IMAGE_FILE_MACHINE_ARMNT => Some("ARM"),
+            IMAGE_FILE_MACHINE_IA64 => Some("IA64"),
+            _ => None,
+        }
+    }
+
+    /// 子系统名称
+    pub fn subsystem_name(&self) -> &'static str {
+        match self.subsystem {
+            IMAGE_SUBSYSTEM_WINDOWS_GUI => "gui",
+            IMAGE_SUBSYSTEM_WINDOWS_CUI => "console",
+            IMAGE_SUBSYSTEM_NATIVE => "native",
+            10..=13 => "efi",
+            _ => "other",
+        }
+    }
+
+    /// 加载配置目录中是否存在混合（CHPE）元数据
+    fn has_chpe_metadata(&self) -> bool {
+        if !self.is_64 {
+            return false;
+        }
+        let Some(offset) = self
+            .data_directory(IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG)
+            .and_then(|(rva, _)| self.rva_to_offset(rva))
+        else {
+            return false;
+        };
+        let Ok(config) = self.read_at(offset, LOAD_CONFIG_CHPE_METADATA_OFFSET + 8) else {
+            return false;
+        };
+        // 结构体 Size 字段必须覆盖 CHPEMetadataPointer
+        let size = read_u32(&config, 0).unwrap_or(0) as usize;
+        size >= LOAD_CONFIG_CHPE_METADATA_OFFSET + 8
+            && config[LOAD_CONFIG_CHPE_METADATA_OFFSET..]
+                .iter()
+                .any(|&b| b != 0)
+    }
+
+    /// 获取数据目录项 (RVA, 大小)
+    pub fn data_directory(&self, index: usize) -> Option<(u32, u32)> {
+        self.data_directories
+            .get(index)
+            .copied()
+            .filter(|&(rva, size)| rva != 0 && size != 0)
+    }
+
+    /// 将 RVA 转换为文件偏移
+    pub fn rva_to_offset(&self, rva: u32) -> Option<u64> {
+        self.sections.iter().find_map(|s| {
+            let span = s.virtual_size.max(s.raw_size);
+            if rva >= s.virtual_address && rva < s.virtual_address.saturating_add(span) {
+                let delta = rva - s.virtual_address;
+                if delta < s.raw_size {
+                    return Some(s.raw_offset as u64 + delta as u64);
+                }
+            }
+            None
+        })
+    }
+
+    /// 从文件中读取指定范围的数据
+    pub fn read_at(&self, offset: u64, len: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+        if offset.saturating_add(len as u64) > self.file_size {
+            return Err("读取范围超出文件大小".into());
+        }
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut buf = vec![0u8; len];
+        file.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// 读取指定类型的全部资源（所有名称、所有语言）
+    pub fn resources(&self, resource_type: &ResourceId) -> Result<Vec<Resource>, Box<dyn Error>> {
+        Ok(self
+            .named_resources(resource_type)?
+            .into_iter()
+            .map(|(_, resource)| resource)
+            .collect())
+    }
+
+    /// 读取指定类型的全部资源并保留资源名称，按资源目录顺序（先字符串名称，后数字 ID）
+    pub fn named_resources(
+        &self,
+        resource_type: &ResourceId,
+    ) -> Result<Vec<(ResourceId, Resource)>, Box<dyn Error>> {
+        let (dir_rva, _) = self
+            .data_directory(IMAGE_DIRECTORY_ENTRY_RESOURCE)
+            .ok_or("没有资源目录")?;
+        let section = self
+            .sections
+            .iter()
+            .find(|s| {
+                let span = s.virtual_size.max(s.raw_size);
+                dir_rva >= s.virtual_address && dir_rva < s.virtual_address.saturating_add(span)
+            })
+            .ok_or("资源目录不在任何节中")?;
+        let rsrc = self.read_at(section.raw_offset as u64, section.raw_size as usize)?;
+        let root = (dir_rva - section.virtual_address) as usize;
+
+        let mut results = Vec::new();
+        // 第一层：资源类型
+        let Some(type_dir) = read_directory(&rsrc, root, root)
+            .into_iter()
+            .find(|(id, _, _)| id == resource_type)
+            .and_then(|(_, offset, is_dir)| is_dir.then_some(offset))
+        else {
+            return Ok(results);
+        };
+
+        // 第二层：资源名称；第三层：语言
+        for (name, name_offset, is_dir) in read_directory(&rsrc, root, type_dir) {
+            if !is_dir {
+                continue;
+            }
+            for (lang, data_entry, is_dir) in read_directory(&rsrc, root, name_offset) {
+                if is_dir {
+                    continue;
+                }
+                let language = match lang {
+                    ResourceId::Id(id) => id,
+                    ResourceId::Name(_) => 0,
+                };
+                let (Some(data_rva), Some(size)) =
+                    (read_u32(&rsrc, data_entry), read_u32(&rsrc, data_entry + 4))
+                else {
+                    continue;
+                };
+                if size == 0 || size > MAX_RESOURCE_SIZE {
+                    continue;
+                }
+                let Some(offset) = self.rva_to_offset(data_rva) else {
+                    continue;
+                };
+                if let Ok(data) = self.read_at(offset, size as usize) {
+                    results.push((name.clone(), Resource { language, data }));
+                }
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// 可执行文件的头部摘要
+#[derive(Debug, Clone)]
+pub struct ImageSummary {
+    /// 目标架构，16 位程序为 None
+    pub architecture: Option<&'static str>,
+    /// 子系统：gui/console/native/efi/other，16 位 DOS/NE 程序为 dos
+    pub subsystem: &'static str,
+    pub is_dll: bool,
+}
+
+impl ImageSummary {
+    pub fn is_gui(&self) -> bool {
+        self.subsystem == "gui" && !self.is_dll
+    }
+}
+
+/// 读取可执行文件的架构与子系统，不是 MZ 文件时返回 None
+pub fn read_image_summary(path: &Path) -> Option<ImageSummary> {
+    match PeImage::open(path) {
+        Ok(image) => Some(ImageSummary {
+            architecture: image.architecture(),
+            subsystem: image.subsystem_name(),
+            is_dll: image.is_dll(),
+        }),
+        Err(_) => {
+            // 有 MZ 头但没有 PE 头的是 DOS 或 16 位 NE 程序
+            let mut magic = [0u8; 2];
+            File::open(path).ok()?.read_exact(&mut magic).ok()?;
+            (&magic == b"MZ").then_some(ImageSummary {
+                architecture: None,
+                subsystem: "dos",
+                is_dll: false,
+            })
+        }
+    }
+}
+
+/// 读取一层资源目录，返回 (标识, 相对资源根的偏移, 是否为子目录)
+fn read_directory(rsrc: &[u8], root: usize, dir: usize) -> Vec<(ResourceId, usize, bool)> {
+    let (Some(named), Some(ids)) = (read_u16(rsrc, dir + 12), read_u16(rsrc, dir + 14)) else {
+        return Vec::new();
+    };
+
+    (0..named as usize + ids as usize)
+        .filter_map(|i| {
+            let at = dir + 16 + i * 8;
+            let name = read_u32(rsrc, at)?;
+            let target = read_u32(rsrc, at + 4)?;
+
+            let id = if name & 0x8000_0000 != 0 {
+                // 名称为 长度 + UTF-16 字符串
+                let str_at = root + (name & 0x7FFF_FFFF) as usize;
+                let len = read_u16(rsrc, str_at)? as usize;
+                let units: Vec<u16> = (0..len)
+                    .filter_map(|j| read_u16(rsrc, str_at + 2 + j * 2))
+                    .collect();
+                ResourceId::Name(String::from_utf16_lossy(&units))
+            } else {
+                ResourceId::Id(name as u16)
+            };
+
+            let is_dir = target & 0x8000_0000 != 0;
+            let offset = root + (target & 0x7FFF_FFFF) as usize;
+            // 防止循环引用的目录指回自身
+            if is_dir && offset <= dir {
+                return None;
+            }
+            Some((id, offset, is_dir))
+        })
+        .collect()
+}
+
+fn read_range(file: &mut File, offset: u64, len: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut buf = vec![0u8; len];
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// 当前用户界面语言 (LANGID)
+#[cfg(target_os = "windows")]
+pub fn ui_language() -> u16 {
+    unsafe { windows::Win32::Globalization::GetUserDefaultUILanguage() }
+}
+
+/// 当前用户界面语言 (LANGID)，非 Windows 平台根据 LANG 环境变量推断
+#[cfg(not(target_os = "windows"))]
+pub fn ui_language() -> u16 {
+    let lang = std::env::var("LANG").unwrap_or_default().to_lowercase();
+    if lang.starts_with("zh_tw") || lang.starts_with("zh_hk") {
+        0x0404
+    } else if lang.starts_with("zh") {
+        0x0804
+    } else if lang.starts_with("ja") {
+        0x0411
+    } else if lang.starts_with("ko") {
+        0x0412
+    } else if lang.starts_with("de") {
+        0x0407
+    } else if lang.starts_with("fr") {
+        0x040C
+    } else {
+        0x0409
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // 测试映像的布局：PE 头在 0x40，唯一的节（资源节）从文件偏移 0x200 开始，RVA 为 0x1000
+    const PE_OFFSET: usize = 0x40;
+    const OPTIONAL: usize = PE_OFFSET + 24;
+    const DIRECTORIES: usize = OPTIONAL + 112;
+    const SECTION_TABLE: usize = OPTIONAL + 0xF0;
+    pub(crate) const SECTION_OFFSET: usize = 0x200;
+    pub(crate) const SECTION_RVA: u32 = 0x1000;
+
+    fn put_u16(data: &mut [u8], at: usize, value: u16) {
+        data[at..at + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(data: &mut [u8], at: usize, value: u32) {
+        data[at..at + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// 64 位 GUI 程序，资源节内容为 rsrc
+    pub(crate) fn image(rsrc: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; SECTION_OFFSET];
+        data[..2].copy_from_slice(b"MZ");
+        put_u32(&mut data, 0x3C, PE_OFFSET as u32);
+        data[PE_OFFSET..PE_OFFSET + 4].copy_from_slice(b"PE\0\0");
+        put_u16(&mut data, PE_OFFSET + 4, IMAGE_FILE_MACHINE_AMD64);
+        put_u16(&mut data, PE_OFFSET + 6, 1);
+        put_u16(&mut data, PE_OFFSET + 20, 0xF0);
+        put_u16(&mut data, OPTIONAL, 0x20B);
+        put_u16(&mut data, OPTIONAL + 68, IMAGE_SUBSYSTEM_WINDOWS_GUI);
+        put_u32(&mut data, OPTIONAL + 108, 16);
+        put_u32(&mut data, SECTION_TABLE + 8, rsrc.len() as u32);
+        put_u32(&mut data, SECTION_TABLE + 12, SECTION_RVA);
+        put_u32(&mut data, SECTION_TABLE + 16, rsrc.len() as u32);
+        put_u32(&mut data, SECTION_TABLE + 20, SECTION_OFFSET as u32);
+        if !rsrc.is_empty() {
+            set_directory(
+                &mut data,
+                IMAGE_DIRECTORY_ENTRY_RESOURCE,
+                SECTION_RVA,
+                rsrc.len() as u32,
+            );
+        }
+        data.extend(rsrc);
+        data
+    }
+
+    pub(crate) fn set_directory(data: &mut [u8], index: usize, rva: u32, size: u32) {
+        put_u32(data, DIRECTORIES + index * 8, rva);
+        put_u32(data, DIRECTORIES + index * 8 + 4, size);
+    }
+
+    /// 只含一条资源（类型、名称、语言各一层）的资源节
+    pub(crate) fn resource_section(
+        resource_type: u16,
+        name: u16,
+        language: u16,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut rsrc = vec![0u8; 0x58];
+        for (dir, id, target) in [
+            (0x00, resource_type, 0x8000_0018),
+            (0x18, name, 0x8000_0030),
+            (0x30, language, 0x48),
+        ] {
+            put_u16(&mut rsrc, dir + 14, 1);
+            put_u32(&mut rsrc, dir + 16, id as u32);
+            put_u32(&mut rsrc, dir + 20, target);
+        }
+        put_u32(&mut rsrc, 0x48, SECTION_RVA + 0x58);
+        put_u32(&mut rsrc, 0x4C, data.len() as u32);
+        rsrc.extend(data);
+        rsrc
+    }
+
+    /// 写入临时文件，文件名带进程 ID，避免并行的测试互相覆盖
+    pub(crate) fn write_temp(name: &str, data: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("qi-launch-{}-{}", std::process::id(), name));
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_headers_and_resources() {
+        let path = write_temp(
+            "pe-valid.exe",
+            &image(&resource_section(RT_VERSION, 1, 0x409, b"data")),
+        );
+        let image = PeImage::open(&path).unwrap();
+        assert_eq!(image.architecture(), Some("x64"));
+        assert_eq!(image.subsystem_name(), "gui");
+        let resources = image.resources(&ResourceId::Id(RT_VERSION)).unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].language, 0x409);
+        assert_eq!(resources[0].data, b"data");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_bad_pe_offset() {
+        let mut data = image(&[]);
+        // 超出上限、指向文件之外、指向 MZ 头中间（奇数偏移）
+        for offset in [u32::MAX, MAX_PE_OFFSET as u32, 0x1F0, 0x3B, 0x3D] {
+            put_u32(&mut data, 0x3C, offset);
+            let path = write_temp("pe-offset.exe", &data);
+            assert!(PeImage::open(&path).is_err(), "{:#x}", offset);
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn truncated_image_does_not_panic() {
+        let data = image(&resource_section(RT_VERSION, 1, 0x409, b"data"));
+        let path =
+            std::env::temp_dir().join(format!("qi-launch-{}-pe-truncated.exe", std::process::id()));
+        for len in 0..data.len() {
+            std::fs::write(&path, &data[..len]).unwrap();
+            if let Ok(image) = PeImage::open(&path) {
+                let _ = image.resources(&ResourceId::Id(RT_VERSION));
+                let _ = image.architecture();
+            }
+            let _ = read_image_summary(&path);
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn resource_directory_cycles_are_ignored() {
+        let mut rsrc = resource_section(RT_VERSION, 1, 0x409, b"data");
+        // 名称目录指回根目录，语言目录指回自身
+        put_u32(&mut rsrc, 0x18 + 20, 0x8000_0000);
+        let path = write_temp("pe-cycle-1.exe", &image(&rsrc));
+        let resources = PeImage::open(&path)
+            .unwrap()
+            .resources(&ResourceId::Id(RT_VERSION));
+        assert!(resources.unwrap().is_empty());
+        std::fs::remove_file(path).unwrap();
+
+        let mut rsrc = resource_section(RT_VERSION, 1, 0x409, b"data");
+        put_u32(&mut rsrc, 0x30 + 20, 0x8000_0030);
+        let path = write_temp("pe-cycle-2.exe", &image(&rsrc));
+        let resources = PeImage::open(&path)
+            .unwrap()
+            .resources(&ResourceId::Id(RT_VERSION));
+        assert!(resources.unwrap().is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn out_of_range_resource_entries_are_skipped() {
+        let mut rsrc = resource_section(RT_VERSION, 1, 0x409, b"data");
+        // 目录项个数远超节大小，数据项大小超出文件
+        put_u16(&mut rsrc, 12, u16::MAX);
+        put_u32(&mut rsrc, 0x4C, MAX_RESOURCE_SIZE);
+        let path = write_temp("pe-range.exe", &image(&rsrc));
+        let resources = PeImage::open(&path)
+            .unwrap()
+            .resources(&ResourceId::Id(RT_VERSION));
+        assert!(resources.unwrap().is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+}
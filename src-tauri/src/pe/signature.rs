@@ -0,0 +1,606 @@
+// Authenticode 签名解析 - 读取证书表并比较嵌入的 PE 摘要与文件内容
+//
+// 摘要一致只说明签名块属于这个文件的内容，签名块本身可以从别的程序复制过来。
+// Windows 上再由 WinVerifyTrust 校验签名与证书链；其他平台无法校验，只报告摘要是否一致。
+// 摘要一致时都给出证书中的签名者、主题与颁发者，状态不是 verified 时由界面标为未校验。
+// 需要读取整个文件，结果按路径、大小与修改时间缓存。
+
+use super::{read_u16, read_u32, PeImage, IMAGE_DIRECTORY_ENTRY_SECURITY};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+// WIN_CERTIFICATE 常量
+const WIN_CERT_REVISION_2_0: u16 = 0x0200;
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+
+// 相关 OID
+const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+const OID_SPC_INDIRECT_DATA: &str = "1.3.6.1.4.1.311.2.1.4";
+const OID_SHA1: &str = "1.3.14.3.2.26";
+const OID_SHA256: &str = "2.16.840.1.101.3.4.2.1";
+const OID_SHA384: &str = "2.16.840.1.101.3.4.2.2";
+const OID_SHA512: &str = "2.16.840.1.101.3.4.2.3";
+const OID_COMMON_NAME: &str = "2.5.4.3";
+const OID_ORGANIZATION: &str = "2.5.4.10";
+
+/// 签名状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// 摘要一致，且 WinVerifyTrust 校验签名与证书链通过
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Verified,
+    /// 摘要一致，但 WinVerifyTrust 校验未通过（签名无效、证书不受信任等）
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Untrusted,
+    /// 摘要一致，签名本身未校验（非 Windows 平台）
+    #[cfg_attr(target_os = "windows", allow(dead_code))]
+    DigestMatch,
+    /// 存在签名但文件内容已被修改
+    HashMismatch,
+    /// 没有嵌入签名
+    Unsigned,
+    /// 证书表或 PKCS#7 数据无法解析
+    Invalid,
+    /// 使用了不支持的摘要算法
+    Unsupported,
+}
+
+impl SignatureStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureStatus::Verified => "verified",
+            SignatureStatus::Untrusted => "untrusted",
+            SignatureStatus::DigestMatch => "digest_match",
+            SignatureStatus::HashMismatch => "hash_mismatch",
+            SignatureStatus::Unsigned => "unsigned",
+            SignatureStatus::Invalid => "invalid",
+            SignatureStatus::Unsupported => "unsupported",
+        }
+    }
+}
+
+/// 签名信息，证书字段在摘要一致（Verified/Untrusted/DigestMatch）时给出
+#[derive(Debug, Clone)]
+pub struct SignatureInfo {
+    pub status: SignatureStatus,
+    /// 签名者（签名证书主题的 CN，没有时取 O）
+    pub signer: Option<String>,
+    /// 签名证书主题，如 "CN=Microsoft Corporation, O=Microsoft Corporation, C=US"
+    pub subject: Option<String>,
+    /// 签名证书颁发者
+    pub issuer: Option<String>,
+}
+
+// 缓存键：路径、文件大小与修改时间
+type CacheKey = (PathBuf, u64, Option<SystemTime>);
+
+static SIGNATURE_CACHE: OnceLock<Mutex<HashMap<CacheKey, Option<SignatureInfo>>>> = OnceLock::new();
+
+impl SignatureInfo {
+    fn status_only(status: SignatureStatus) -> Self {
+        SignatureInfo {
+            status,
+            signer: None,
+            subject: None,
+            issuer: None,
+        }
+    }
+}
+
+/// 读取并校验 PE 文件的 Authenticode 签名，文件未变化时使用缓存结果
+pub fn read_signature_info(path: &Path) -> Option<SignatureInfo> {
+    let meta = std::fs::metadata(path).ok()?;
+    let key = (path.to_path_buf(), meta.len(), meta.modified().ok());
+    let cache = SIGNATURE_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(info) = cache.lock().ok().and_then(|c| c.get(&key).cloned()) {
+        return info;
+    }
+    let info = check_signature(path);
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(key, info.clone());
+    }
+    info
+}
+
+fn check_signature(path: &Path) -> Option<SignatureInfo> {
+    let image = PeImage::open(path).ok()?;
+
+    let Some((cert_offset, cert_size)) = image.data_directory(IMAGE_DIRECTORY_ENTRY_SECURITY)
+    else {
+        return Some(SignatureInfo::status_only(SignatureStatus::Unsigned));
+    };
+    // 证书表目录项中的地址是文件偏移而不是 RVA
+    let Ok(table) = image.read_at(cert_offset as u64, cert_size as usize) else {
+        return Some(SignatureInfo::status_only(SignatureStatus::Invalid));
+    };
+    let Some(pkcs7) = first_pkcs7_certificate(&table) else {
+        return Some(SignatureInfo::status_only(SignatureStatus::Invalid));
+    };
+    let Some(signed) = parse_signed_data(pkcs7) else {
+        return Some(SignatureInfo::status_only(SignatureStatus::Invalid));
+    };
+
+    let status = match compute_pe_hash(&image, cert_offset as u64, &signed.digest_oid) {
+        Some(hash) if hash == signed.digest => verify_signature(path),
+        Some(_) => SignatureStatus::HashMismatch,
+        None if digest_supported(&signed.digest_oid) => SignatureStatus::Invalid,
+        None => SignatureStatus::Unsupported,
+    };
+    // 摘要不一致时签名块不属于这个文件，证书字段没有意义
+    if !matches!(
+        status,
+        SignatureStatus::Verified | SignatureStatus::Untrusted | SignatureStatus::DigestMatch
+    ) {
+        return Some(SignatureInfo::status_only(status));
+    }
+
+    Some(SignatureInfo {
+        status,
+        signer: signed.signer,
+        subject: signed.subject,
+        issuer: signed.issuer,
+    })
+}
+
+/// 由 WinVerifyTrust 校验签名与证书链（不检查吊销，避免联网）
+#[cfg(target_os = "windows")]
+fn verify_signature(path: &Path) -> SignatureStatus {
+    use windows::core::{GUID, PCWSTR};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Security::WinTrust::*;
+
+    let path_wide: Vec<u16> = path
+        .as_os_str()
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut file_info = WINTRUST_FILE_INFO {
+        cbStruct: std::mem::size_of::<WINTRUST_FILE_INFO>() as u32,
+        pcwszFilePath: PCWSTR(path_wide.as_ptr()),
+        ..Default::default()
+    };
+    let mut data = WINTRUST_DATA {
+        cbStruct: std::mem::size_of::<WINTRUST_DATA>() as u32,
+        dwUIChoice: WTD_UI_NONE,
+        fdwRevocationChecks: WTD_REVOKE_NONE,
+        dwUnionChoice: WTD_CHOICE_FILE,
+        Anonymous: WINTRUST_DATA_0 {
+            pFile: &mut file_info,
+        },
+        dwStateAction: WTD_STATEACTION_VERIFY,
+        dwProvFlags: WTD_REVOCATION_CHECK_NONE | WTD_CACHE_ONLY_URL_RETRIEVAL,
+        ..Default::default()
+    };
+    let mut action: GUID = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+    unsafe {
+        let result = WinVerifyTrust(
+            HWND(-1isize as *mut _),
+            &mut action,
+            &mut data as *mut _ as *mut _,
+        );
+        // 释放校验过程中分配的状态
+        data.dwStateAction = WTD_STATEACTION_CLOSE;
+        WinVerifyTrust(
+            HWND(-1isize as *mut _),
+            &mut action,
+            &mut data as *mut _ as *mut _,
+        );
+        if result == 0 {
+            SignatureStatus::Verified
+        } else {
+            SignatureStatus::Untrusted
+        }
+    }
+}
+
+/// 其他平台没有系统信任库，只能确认摘要一致
+#[cfg(not(target_os = "windows"))]
+fn verify_signature(_path: &Path) -> SignatureStatus {
+    SignatureStatus::DigestMatch
+}
+
+/// 取出证书表中第一条 PKCS#7 SignedData
+fn first_pkcs7_certificate(table: &[u8]) -> Option<&[u8]> {
+    let mut offset = 0;
+    while offset + 8 <= table.len() {
+        let length = read_u32(table, offset)? as usize;
+        let revision = read_u16(table, offset + 4)?;
+        let cert_type = read_u16(table, offset + 6)?;
+        if length < 8 || offset + length > table.len() {
+            return None;
+        }
+        if revision == WIN_CERT_REVISION_2_0 && cert_type == WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+            return Some(&table[offset + 8..offset + length]);
+        }
+        // 每条 WIN_CERTIFICATE 按 8 字节对齐
+        offset += (length + 7) & !7;
+    }
+    None
+}
+
+/// 从 SignedData 中提取出的内容
+struct SignedData {
+    digest_oid: String,
+    digest: Vec<u8>,
+    signer: Option<String>,
+    subject: Option<String>,
+    issuer: Option<String>,
+}
+
+/// 解析 ContentInfo { signedData }，取出 SpcIndirectDataContent 摘要与签名证书
+fn parse_signed_data(data: &[u8]) -> Option<SignedData> {
+    // ContentInfo ::= SEQUENCE { contentType OID, [0] EXPLICIT content }
+    let (content_info, _) = Tlv::parse(data)?;
+    let ci = content_info.children();
+    if ci.first()?.oid()? != OID_SIGNED_DATA {
+        return None;
+    }
+    let signed_data = ci.get(1)?.children().into_iter().next()?;
+
+    // SignedData ::= SEQUENCE { version, digestAlgorithms, contentInfo, [0] certificates, [1] crls, signerInfos }
+    let sd = signed_data.children();
+    let encap = sd.get(2)?.children();
+    if encap.first()?.oid()? != OID_SPC_INDIRECT_DATA {
+        return None;
+    }
+    // SpcIndirectDataContent ::= SEQUENCE { data, messageDigest DigestInfo }
+    let indirect = encap.get(1)?.children().into_iter().next()?;
+    let digest_info = indirect.children().into_iter().nth(1)?;
+    let di = digest_info.children();
+    let digest_oid = di.first()?.children().first()?.oid()?;
+    let digest = di.get(1)?.content.to_vec();
+
+    let certificates: Vec<Tlv> = sd
+        .iter()
+        .find(|t| t.tag == 0xA0)
+        .map(|t| t.children())
+        .unwrap_or_default();
+    let signer_infos = sd.iter().rev().find(|t| t.tag == 0x31)?.children();
+
+    // SignerInfo ::= SEQUENCE { version, issuerAndSerialNumber { issuer, serialNumber }, ... }
+    let signer_id = signer_infos
+        .first()
+        .and_then(|si| si.children().into_iter().nth(1))
+        .map(|isn| isn.children());
+    let signer_cert = signer_id
+        .as_ref()
+        .and_then(|id| {
+            let (issuer, serial) = (id.first()?, id.get(1)?);
+            certificates.iter().find(|cert| {
+                cert_fields(cert).is_some_and(|f| {
+                    f.issuer.raw == issuer.raw && f.serial.content == serial.content
+                })
+            })
+        })
+        .or_else(|| certificates.first());
+
+    let (signer, subject, issuer) = match signer_cert.and_then(cert_fields) {
+        Some(fields) => {
+            let signer = name_attribute(&fields.subject, OID_COMMON_NAME)
+                .or_else(|| name_attribute(&fields.subject, OID_ORGANIZATION));
+            (
+                signer,
+                Some(format_name(&fields.subject)),
+                Some(format_name(&fields.issuer)),
+            )
+        }
+        None => (None, None, None),
+    };
+
+    Some(SignedData {
+        digest_oid,
+        digest,
+        signer,
+        subject,
+        issuer,
+    })
+}
+
+/// 证书中需要的字段
+struct CertFields<'a> {
+    serial: Tlv<'a>,
+    issuer: Tlv<'a>,
+    subject: Tlv<'a>,
+}
+
+/// Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signature }
+/// TBSCertificate ::= SEQUENCE { [0] version OPTIONAL, serialNumber, signature, issuer, validity, subject, ... }
+fn cert_fields<'a>(cert: &Tlv<'a>) -> Option<CertFields<'a>> {
+    let tbs = cert.children().into_iter().next()?;
+    let mut fields = tbs.children().into_iter();
+    let mut serial = fields.next()?;
+    if serial.tag == 0xA0 {
+        serial = fields.next()?;
+    }
+    let _signature = fields.next()?;
+    let issuer = fields.next()?;
+    let _validity = fields.next()?;
+    let subject = fields.next()?;
+    Some(CertFields {
+        serial,
+        issuer,
+        subject,
+    })
+}
+
+/// Name ::= SEQUENCE OF SET OF SEQUENCE { type OID, value }
+fn name_entries(name: &Tlv) -> Vec<(String, String)> {
+    name.children()
+        .iter()
+        .flat_map(|rdn| rdn.children())
+        .filter_map(|atv| {
+            let parts = atv.children();
+            Some((parts.first()?.oid()?, parts.get(1)?.string()?))
+        })
+        .collect()
+}
+
+fn name_attribute(name: &Tlv, oid: &str) -> Option<String> {
+    name_entries(name)
+        .into_iter()
+        .find(|(o, _)| o == oid)
+        .map(|(_, v)| v)
+}
+
+/// 按常见缩写格式化可分辨名称
+fn format_name(name: &Tlv) -> String {
+    name_entries(name)
+        .into_iter()
+        .rev()
+        .map(|(oid, value)| {
+            let key = match oid.as_str() {
+                "2.5.4.3" => "CN",
+                "2.5.4.6" => "C",
+                "2.5.4.7" => "L",
+                "2.5.4.8" => "S",
+                "2.5.4.10" => "O",
+                "2.5.4.11" => "OU",
+                "1.2.840.113549.1.9.1" => "E",
+                _ => return format!("{}={}", oid, value),
+            };
+            format!("{}={}", key, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn digest_supported(oid: &str) -> bool {
+    matches!(oid, OID_SHA1 | OID_SHA256 | OID_SHA384 | OID_SHA512)
+}
+
+/// 计算 Authenticode PE 摘要：跳过 CheckSum、证书表目录项和证书表本身
+fn compute_pe_hash(image: &PeImage, cert_offset: u64, digest_oid: &str) -> Option<Vec<u8>> {
+    match digest_oid {
+        OID_SHA1 => hash_pe_ranges::<Sha1>(image, cert_offset),
+        OID_SHA256 => hash_pe_ranges::<Sha256>(image, cert_offset),
+        OID_SHA384 => hash_pe_ranges::<Sha384>(image, cert_offset),
+        OID_SHA512 => hash_pe_ranges::<Sha512>(image, cert_offset),
+        _ => None,
+    }
+}
+
+fn hash_pe_ranges<D: Digest>(image: &PeImage, cert_offset: u64) -> Option<Vec<u8>> {
+    let end = cert_offset.min(image.file_size);
+    let checksum = image.checksum_offset;
+    let security = image.security_entry_offset;
+    if checksum + 4 > security || security + 8 > end {
+        return None;
+    }
+
+    let mut file = File::open(image.path()).ok()?;
+    let mut hasher = D::new();
+    for (start, stop) in [(0, checksum), (checksum + 4, security), (security + 8, end)] {
+        hash_file_range(&mut file, &mut hasher, start, stop)?;
+    }
+    Some(hasher.finalize().to_vec())
+}
+
+fn hash_file_range<D: Digest>(
+    file: &mut File,
+    hasher: &mut D,
+    start: u64,
+    stop: u64,
+) -> Option<()> {
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut remaining = stop.saturating_sub(start);
+    let mut buf = vec![0u8; 1024 * 1024];
+    while remaining > 0 {
+        let want = remaining.min(buf.len() as u64) as usize;
+        file.read_exact(&mut buf[..want]).ok()?;
+        hasher.update(&buf[..want]);
+        remaining -= want as u64;
+    }
+    Some(())
+}
+
+// ========== 最小化的 DER 解析 ==========
+
+/// 一个 DER 编码的 TLV 元素
+#[derive(Clone)]
+struct Tlv<'a> {
+    tag: u8,
+    content: &'a [u8],
+    /// 包含标签和长度的完整编码
+    raw: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    /// 解析一个元素，返回 (元素, 剩余数据)
+    fn parse(data: &'a [u8]) -> Option<(Tlv<'a>, &'a [u8])> {
+        let tag = *data.first()?;
+        let first = *data.get(1)? as usize;
+        let (len, header) = if first < 0x80 {
+            (first, 2)
+        } else {
+            let count = first & 0x7F;
+            // 不支持不定长编码和超过 4 字节的长度
+            if count == 0 || count > 4 {
+                return None;
+            }
+            let len = data
+                .get(2..2 + count)?
+                .iter()
+                .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+            (len, 2 + count)
+        };
+        let end = header.checked_add(len)?;
+        let raw = data.get(..end)?;
+        Some((
+            Tlv {
+                tag,
+                content: &raw[header..],
+                raw,
+            },
+            &data[end..],
+        ))
+    }
+
+    /// 构造类型（SEQUENCE、SET、上下文标签）的子元素
+    fn children(&self) -> Vec<Tlv<'a>> {
+        let mut items = Vec::new();
+        let mut rest = self.content;
+        while !rest.is_empty() {
+            let Some((item, next)) = Tlv::parse(rest) else {
+                break;
+            };
+            items.push(item);
+            rest = next;
+        }
+        items
+    }
+
+    fn oid(&self) -> Option<String> {
+        if self.tag != 0x06 || self.content.is_empty() {
+            return None;
+        }
+        let first = self.content[0];
+        let mut parts = vec![(first / 40) as u64, (first % 40) as u64];
+        let mut value = 0u64;
+        for &b in &self.content[1..] {
+            value = (value << 7) | (b & 0x7F) as u64;
+            if b & 0x80 == 0 {
+                parts.push(value);
+                value = 0;
+            }
+        }
+        Some(
+            parts
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join("."),
+        )
+    }
+
+    fn string(&self) -> Option<String> {
+        match self.tag {
+            // UTF8String, PrintableString, T61String, IA5String
+            0x0C | 0x13 | 0x14 | 0x16 => Some(String::from_utf8_lossy(self.content).to_string()),
+            // BMPString (UTF-16BE)
+            0x1E => {
+                let units: Vec<u16> = self
+                    .content
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                Some(String::from_utf16_lossy(&units))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pe::tests::{image, set_directory, write_temp};
+
+    fn win_certificate(length: u32, content: &[u8]) -> Vec<u8> {
+        let mut data = length.to_le_bytes().to_vec();
+        data.extend(WIN_CERT_REVISION_2_0.to_le_bytes());
+        data.extend(WIN_CERT_TYPE_PKCS_SIGNED_DATA.to_le_bytes());
+        data.extend(content);
+        data
+    }
+
+    #[test]
+    fn certificate_length_overrunning_table() {
+        assert!(first_pkcs7_certificate(&win_certificate(0x1000, &[0x30, 0x00])).is_none());
+        assert!(first_pkcs7_certificate(&win_certificate(4, &[])).is_none());
+        assert!(first_pkcs7_certificate(&win_certificate(u32::MAX, &[])).is_none());
+        assert_eq!(
+            first_pkcs7_certificate(&win_certificate(10, &[0x30, 0x00])),
+            Some(&[0x30, 0x00][..])
+        );
+    }
+
+    #[test]
+    fn certificate_table_overrunning_file() {
+        let mut data = image(&[]);
+        let cert_offset = data.len() as u32;
+        data.extend(win_certificate(16, &[0x30, 0x06, 0, 0, 0, 0, 0, 0]));
+        // 目录项中的大小超出文件末尾
+        set_directory(
+            &mut data,
+            IMAGE_DIRECTORY_ENTRY_SECURITY,
+            cert_offset,
+            0x10_0000,
+        );
+        let path = write_temp("signature-overrun.exe", &data);
+        let info = check_signature(&path).unwrap();
+        assert_eq!(info.status, SignatureStatus::Invalid);
+        std::fs::remove_file(&path).unwrap();
+
+        // 偏移本身在文件之外
+        set_directory(&mut data, IMAGE_DIRECTORY_ENTRY_SECURITY, u32::MAX - 4, 16);
+        let path = write_temp("signature-offset.exe", &data);
+        let info = check_signature(&path).unwrap();
+        assert_eq!(info.status, SignatureStatus::Invalid);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn unsigned_and_garbage_signatures() {
+        let path = write_temp("signature-none.exe", &image(&[]));
+        assert_eq!(
+            check_signature(&path).unwrap().status,
+            SignatureStatus::Unsigned
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        // 证书表完整，但其中的 DER 长度字段越界
+        let mut data = image(&[]);
+        let cert_offset = data.len() as u32;
+        data.extend(win_certificate(
+            16,
+            &[0x30, 0x84, 0xFF, 0xFF, 0xFF, 0xFF, 0x06, 0x00],
+        ));
+        set_directory(&mut data, IMAGE_DIRECTORY_ENTRY_SECURITY, cert_offset, 16);
+        let path = write_temp("signature-garbage.exe", &data);
+        assert_eq!(
+            check_signature(&path).unwrap().status,
+            SignatureStatus::Invalid
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn truncated_der_does_not_panic() {
+        let der = [
+            0x30, 0x82, 0x00, 0x10, 0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07,
+            0x02, 0xA0, 0x03, 0x30, 0x01, 0x00,
+        ];
+        for len in 0..der.len() {
+            assert!(parse_signed_data(&der[..len]).is_none());
+        }
+        assert!(Tlv::parse(&[0x30, 0x80]).is_none());
+        assert!(Tlv::parse(&[0x30, 0x85, 1, 2, 3, 4, 5]).is_none());
+    }
+}
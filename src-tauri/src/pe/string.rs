@@ -0,0 +1,127 @@
+// RT_STRING 字符串表
+//
+// 字符串以 16 个为一块保存，块的资源 ID 为 字符串ID / 16 + 1，
+// 块内依次为 16 个 (长度 u16, UTF-16 字符) 项，空字符串长度为 0。
+
+use super::{read_u16, PeImage, Resource, ResourceId};
+use std::path::Path;
+
+pub const RT_STRING: u16 = 6;
+
+/// 模块中的全部字符串块，读取一次后可多次查找
+pub struct StringTable {
+    blocks: Vec<(ResourceId, Resource)>,
+}
+
+impl StringTable {
+    pub fn load(path: &Path) -> Option<Self> {
+        let image = PeImage::open(path).ok()?;
+        let blocks = image.named_resources(&ResourceId::Id(RT_STRING)).ok()?;
+        Some(StringTable { blocks })
+    }
+
+    /// 读取字符串，优先指定语言，其次同一主语言，最后任意语言
+    pub fn get(&self, id: u32, language: u16) -> Option<String> {
+        let block_id = ResourceId::Id((id / 16 + 1) as u16);
+        let blocks: Vec<&Resource> = self
+            .blocks
+            .iter()
+            .filter(|(name, _)| *name == block_id)
+            .map(|(_, resource)| resource)
+            .collect();
+
+        let primary = |lang: u16| lang & 0x3FF;
+        let block = blocks
+            .iter()
+            .find(|r| r.language == language)
+            .or_else(|| {
+                blocks
+                    .iter()
+                    .find(|r| primary(r.language) == primary(language))
+            })
+            .or_else(|| blocks.first())?;
+        string_in_block(&block.data, (id % 16) as usize)
+    }
+}
+
+/// 块内第 index 个字符串，空字符串返回 None
+fn string_in_block(data: &[u8], index: usize) -> Option<String> {
+    let mut offset = 0usize;
+    for _ in 0..index {
+        offset += 2 + read_u16(data, offset)? as usize * 2;
+    }
+    let len = read_u16(data, offset)? as usize;
+    let units: Vec<u16> = (0..len)
+        .map(|i| read_u16(data, offset + 2 + i * 2))
+        .collect::<Option<_>>()?;
+    (!units.is_empty()).then(|| String::from_utf16_lossy(&units))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 一块 16 个字符串，只有 index 处非空
+    fn block(index: usize, text: &str) -> Vec<u8> {
+        let mut data = Vec::new();
+        for i in 0..16 {
+            let units: Vec<u16> = if i == index {
+                text.encode_utf16().collect()
+            } else {
+                Vec::new()
+            };
+            data.extend((units.len() as u16).to_le_bytes());
+            data.extend(units.iter().flat_map(|u| u.to_le_bytes()));
+        }
+        data
+    }
+
+    fn table(blocks: Vec<(u16, u16, Vec<u8>)>) -> StringTable {
+        StringTable {
+            blocks: blocks
+                .into_iter()
+                .map(|(id, language, data)| (ResourceId::Id(id), Resource { language, data }))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn reads_string_by_id_and_language() {
+        let strings = table(vec![
+            (2, 0x409, block(3, "English")),
+            (2, 0x804, block(3, "中文")),
+        ]);
+        assert_eq!(strings.get(19, 0x804), Some("中文".to_string()));
+        assert_eq!(strings.get(19, 0x411), Some("English".to_string()));
+        assert_eq!(strings.get(18, 0x409), None);
+    }
+
+    #[test]
+    fn out_of_range_blocks_return_none() {
+        // 字符串长度超出块的范围
+        let mut data = block(0, "abc");
+        data[..2].copy_from_slice(&u16::MAX.to_le_bytes());
+        let strings = table(vec![(1, 0x409, data), (2, 0x409, vec![0x05])]);
+        for id in [0, 1, 15, 16, 17, u32::MAX] {
+            assert_eq!(strings.get(id, 0x409), None, "{}", id);
+        }
+        // 块在第 6 个字符串结束之前截断（前 5 个空字符串共 10 字节）
+        let data = block(5, "text");
+        for len in 0..10 + 2 + 8 {
+            assert_eq!(string_in_block(&data[..len], 5), None);
+        }
+        assert_eq!(string_in_block(&data[..20], 5), Some("text".to_string()));
+    }
+
+    #[test]
+    fn loads_string_table_from_image() {
+        use crate::pe::tests::{image, resource_section, write_temp};
+
+        let rsrc = resource_section(RT_STRING, 1, 0x409, &block(2, "Hello"));
+        let path = write_temp("string.exe", &image(&rsrc));
+        let strings = StringTable::load(&path).unwrap();
+        assert_eq!(strings.get(2, 0x409), Some("Hello".to_string()));
+        assert_eq!(strings.get(16 * 100, 0x409), None);
+        std::fs::remove_file(path).unwrap();
+    }
+}
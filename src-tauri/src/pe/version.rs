@@ -0,0 +1,334 @@
+// VS_VERSIONINFO 版本资源解析
+
+use super::{read_u16, read_u32, PeImage, ResourceId, RT_VERSION};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// VS_FIXEDFILEINFO 签名
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF04BD;
+// 节点嵌套深度上限，正常的版本资源只有 4 层，防止畸形资源导致栈溢出
+const MAX_DEPTH: usize = 8;
+
+/// 版本资源中的一张 StringFileInfo 表
+#[derive(Debug, Clone, Default)]
+pub struct StringTable {
+    /// 语言与代码页，例如 "080404b0"
+    pub key: String,
+    pub strings: BTreeMap<String, String>,
+}
+
+impl StringTable {
+    /// 表对应的语言 ID
+    pub fn language(&self) -> Option<u16> {
+        self.key
+            .get(0..4)
+            .and_then(|s| u16::from_str_radix(s, 16).ok())
+    }
+}
+
+/// 解析后的版本信息
+#[derive(Debug, Clone, Default)]
+pub struct VersionInfo {
+    /// VS_FIXEDFILEINFO 中的文件版本
+    pub fixed_file_version: Option<String>,
+    /// VS_FIXEDFILEINFO 中的产品版本
+    pub fixed_product_version: Option<String>,
+    /// VarFileInfo\Translation 中声明的 (语言, 代码页)
+    pub translations: Vec<(u16, u16)>,
+    pub string_tables: Vec<StringTable>,
+}
+
+impl VersionInfo {
+    /// 选择最合适的字符串表：用户界面语言 > Translation 声明顺序 > 第一张表
+    pub fn preferred_table(&self, ui_language: u16) -> Option<&StringTable> {
+        self.string_tables
+            .iter()
+            .find(|t| t.language() == Some(ui_language))
+            .or_else(|| {
+                // 同一主语言（如 zh-CN 与 zh-TW）次之
+                self.string_tables
+                    .iter()
+                    .find(|t| t.language().map(|l| l & 0x3FF) == Some(ui_language & 0x3FF))
+            })
+            .or_else(|| {
+                self.translations.iter().find_map(|(lang, codepage)| {
+                    let key = format!("{:04x}{:04x}", lang, codepage);
+                    self.string_tables
+                        .iter()
+                        .find(|t| t.key.eq_ignore_ascii_case(&key))
+                })
+            })
+            .or_else(|| self.string_tables.first())
+    }
+
+    /// 从首选表中读取字符串，找不到时依次查找其它表
+    pub fn get(&self, ui_language: u16, key: &str) -> Option<String> {
+        let preferred = self.preferred_table(ui_language);
+        preferred
+            .into_iter()
+            .chain(self.string_tables.iter())
+            .find_map(|t| t.strings.get(key))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
+
+    pub fn file_description(&self, ui_language: u16) -> Option<String> {
+        self.get(ui_language, "FileDescription")
+    }
+
+    pub fn product_name(&self, ui_language: u16) -> Option<String> {
+        self.get(ui_language, "ProductName")
+    }
+
+    pub fn company_name(&self, ui_language: u16) -> Option<String> {
+        self.get(ui_language, "CompanyName")
+    }
+
+    pub fn file_version(&self, ui_language: u16) -> Option<String> {
+        self.get(ui_language, "FileVersion")
+            .or_else(|| self.fixed_file_version.clone())
+    }
+
+    pub fn product_version(&self, ui_language: u16) -> Option<String> {
+        self.get(ui_language, "ProductVersion")
+            .or_else(|| self.fixed_product_version.clone())
+    }
+
+    /// 所有字符串表，键为语言代码页
+    pub fn all_tables(&self) -> BTreeMap<String, BTreeMap<String, String>> {
+        self.string_tables
+            .iter()
+            .map(|t| (t.key.to_lowercase(), t.strings.clone()))
+            .collect()
+    }
+}
+
+/// 读取 PE 文件的版本资源
+pub fn read_version_info(path: &Path) -> Option<VersionInfo> {
+    let image = PeImage::open(path).ok()?;
+    let mut resources = image.resources(&ResourceId::Id(RT_VERSION)).ok()?;
+    // 通常只有一个版本资源；存在多个语言版本时优先用户界面语言
+    let ui_language = super::ui_language();
+    resources.sort_by_key(|r| r.language != ui_language);
+    resources.iter().find_map(|r| parse_version_info(&r.data))
+}
+
+/// 解析 VS_VERSIONINFO 资源数据
+pub fn parse_version_info(data: &[u8]) -> Option<VersionInfo> {
+    let root = parse_node(data, 0, data.len(), 0)?;
+    if root.key != "VS_VERSION_INFO" {
+        return None;
+    }
+
+    let mut info = VersionInfo::default();
+
+    if root.value.len() >= 52 && read_u32(root.value, 0) == Some(FIXED_FILE_INFO_SIGNATURE) {
+        info.fixed_file_version =
+            format_version(read_u32(root.value, 8)?, read_u32(root.value, 12)?);
+        info.fixed_product_version =
+            format_version(read_u32(root.value, 16)?, read_u32(root.value, 20)?);
+    }
+
+    for child in &root.children {
+        match child.key.as_str() {
+            "StringFileInfo" => {
+                for table in &child.children {
+                    let strings = table
+                        .children
+                        .iter()
+                        .map(|s| (s.key.clone(), decode_text_value(s.value)))
+                        .collect();
+                    info.string_tables.push(StringTable {
+                        key: table.key.clone(),
+                        strings,
+                    });
+                }
+            }
+            "VarFileInfo" => {
+                for var in child.children.iter().filter(|v| v.key == "Translation") {
+                    info.translations.extend(var.value.chunks_exact(4).map(|c| {
+                        (
+                            u16::from_le_bytes([c[0], c[1]]),
+                            u16::from_le_bytes([c[2], c[3]]),
+                        )
+                    }));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
+/// 版本资源中的通用节点：wLength, wValueLength, wType, szKey, Value, Children
+struct Node<'a> {
+    key: String,
+    value: &'a [u8],
+    children: Vec<Node<'a>>,
+}
+
+fn parse_node(data: &[u8], offset: usize, limit: usize, depth: usize) -> Option<Node<'_>> {
+    if depth >= MAX_DEPTH {
+        return None;
+    }
+    let length = read_u16(data, offset)? as usize;
+    let value_length = read_u16(data, offset + 2)? as usize;
+    let value_type = read_u16(data, offset + 4)?;
+    if length < 6 {
+        return None;
+    }
+    let end = (offset + length).min(limit).min(data.len());
+
+    // szKey：以 0 结尾的 UTF-16 字符串
+    let mut cursor = offset + 6;
+    let mut key_units = Vec::new();
+    while cursor + 2 <= end {
+        let c = read_u16(data, cursor)?;
+        cursor += 2;
+        if c == 0 {
+            break;
+        }
+        key_units.push(c);
+    }
+    let key = String::from_utf16_lossy(&key_units);
+
+    // Value 按 4 字节对齐；文本类型的 wValueLength 以 WORD 计
+    cursor = align4(cursor);
+    let value_bytes = if value_type == 1 {
+        value_length * 2
+    } else {
+        value_length
+    };
+    let value_end = (cursor + value_bytes).min(end);
+    let value = data.get(cursor.min(value_end)..value_end).unwrap_or(&[]);
+
+    // Children 同样按 4 字节对齐
+    let mut children = Vec::new();
+    cursor = align4(value_end);
+    while cursor + 6 <= end {
+        let Some(child) = parse_node(data, cursor, end, depth + 1) else {
+            break;
+        };
+        let child_length = read_u16(data, cursor)? as usize;
+        children.push(child);
+        cursor = align4(cursor + child_length);
+    }
+
+    Some(Node {
+        key,
+        value,
+        children,
+    })
+}
+
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+/// 解码 UTF-16 文本值（部分文件的 wValueLength 不准确，以 0 结尾为准）
+fn decode_text_value(value: &[u8]) -> String {
+    let units: Vec<u16> = value
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&c| c != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+fn format_version(ms: u32, ls: u32) -> Option<String> {
+    if ms == 0 && ls == 0 {
+        return None;
+    }
+    Some(format!(
+        "{}.{}.{}.{}",
+        ms >> 16,
+        ms & 0xFFFF,
+        ls >> 16,
+        ls & 0xFFFF
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 版本资源节点，文本值的 wValueLength 以 WORD 计
+    fn node(key: &str, text: Option<&str>, children: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = vec![0u8; 6];
+        data.extend(key.encode_utf16().chain([0]).flat_map(u16::to_le_bytes));
+        while !data.len().is_multiple_of(4) {
+            data.push(0);
+        }
+        if let Some(text) = text {
+            let units: Vec<u16> = text.encode_utf16().chain([0]).collect();
+            data[2..4].copy_from_slice(&(units.len() as u16).to_le_bytes());
+            data[4..6].copy_from_slice(&1u16.to_le_bytes());
+            data.extend(units.iter().flat_map(|u| u.to_le_bytes()));
+        }
+        for child in children {
+            while !data.len().is_multiple_of(4) {
+                data.push(0);
+            }
+            data.extend(child);
+        }
+        let length = data.len() as u16;
+        data[..2].copy_from_slice(&length.to_le_bytes());
+        data
+    }
+
+    fn sample() -> Vec<u8> {
+        let string = node("FileDescription", Some("Demo"), &[]);
+        let table = node("040904b0", None, &[string]);
+        let string_file_info = node("StringFileInfo", None, &[table]);
+        node("VS_VERSION_INFO", None, &[string_file_info])
+    }
+
+    #[test]
+    fn reads_string_table() {
+        let info = parse_version_info(&sample()).unwrap();
+        assert_eq!(info.file_description(0x409), Some("Demo".to_string()));
+    }
+
+    #[test]
+    fn truncated_and_over_long_nodes_do_not_panic() {
+        let data = sample();
+        for len in 0..data.len() {
+            let _ = parse_version_info(&data[..len]);
+        }
+        // wLength 与 wValueLength 远超实际数据
+        let mut data = sample();
+        data[..2].copy_from_slice(&u16::MAX.to_le_bytes());
+        data[2..4].copy_from_slice(&u16::MAX.to_le_bytes());
+        data[4..6].copy_from_slice(&1u16.to_le_bytes());
+        let _ = parse_version_info(&data);
+        let info = parse_version_info(&{
+            let mut data = sample();
+            data[..2].copy_from_slice(&u16::MAX.to_le_bytes());
+            data
+        })
+        .unwrap();
+        assert_eq!(info.file_description(0x409), Some("Demo".to_string()));
+    }
+
+    #[test]
+    fn deep_nesting_is_cut_off() {
+        // 每 8 字节嵌套一层，直到填满 64 KiB
+        let mut data = Vec::new();
+        while data.len() + 8 <= u16::MAX as usize {
+            let offset = data.len();
+            let remaining = (u16::MAX as usize - offset) as u16;
+            data.extend(remaining.to_le_bytes());
+            data.extend([0u8; 6]);
+        }
+        let root = parse_node(&data, 0, data.len(), 0).unwrap();
+        let mut depth = 1;
+        let mut node = &root;
+        while let Some(child) = node.children.first() {
+            depth += 1;
+            node = child;
+        }
+        assert_eq!(depth, MAX_DEPTH);
+        assert!(parse_version_info(&data).is_none());
+    }
+}
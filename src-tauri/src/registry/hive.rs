@@ -0,0 +1,420 @@
+// regf 二进制 hive 文件 - 纯 Rust 实现，只读
+//
+// 结构：4KB 基本块（"regf"，0x24 处为根键单元偏移）之后是若干 hbin 块，
+// 单元偏移均相对于第一个 hbin（文件偏移 0x1000）。每个单元以 i32 大小开头，
+// 负数表示已分配。常用单元：
+//   nk  键      子键列表 lf/lh/li/ri，值列表为 vk 偏移数组
+//   vk  值      数据不超过 4 字节时内联在偏移字段中
+//   db  大数据  超过 16344 字节的值分段存储
+// 未合并事务日志（.LOG1/.LOG2）的脏 hive 仍可读取，但可能缺少最近的修改。
+
+use super::{strip_mount, RegValue, Registry, RegistryRoot};
+use std::collections::HashSet;
+use std::error::Error;
+
+pub const REGF_SIGNATURE: &[u8] = b"regf";
+
+const HBIN_START: usize = 0x1000;
+const KEY_COMP_NAME: u16 = 0x0020;
+const VALUE_COMP_NAME: u16 = 0x0001;
+const DATA_INLINE: u32 = 0x8000_0000;
+const BIG_DATA_SEGMENT: usize = 16344;
+// 子键列表嵌套与键路径的深度上限，防止损坏文件导致无限递归
+const MAX_DEPTH: usize = 64;
+
+/// 已加载到内存的 hive 文件，挂载在 root\mount 之下
+pub struct HiveFile {
+    data: Vec<u8>,
+    root_cell: usize,
+    root: RegistryRoot,
+    mount: String,
+}
+
+impl HiveFile {
+    /// 从文件内容加载，如 SOFTWARE 挂载到 (LocalMachine, "SOFTWARE")
+    pub fn from_bytes(
+        data: Vec<u8>,
+        root: RegistryRoot,
+        mount: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        if !data.starts_with(REGF_SIGNATURE) {
+            return Err("不是有效的 hive 文件".into());
+        }
+        let root_offset = read_u32(&data, 0x24).ok_or("hive 文件头损坏")?;
+        let mut hive = HiveFile {
+            data,
+            root_cell: 0,
+            root,
+            mount: mount.trim_matches('\\').to_string(),
+        };
+        hive.root_cell = hive.cell(root_offset).ok_or("根键单元无效")?;
+        if hive.signature(hive.root_cell) != Some(*b"nk") {
+            return Err("根键单元不是 nk 记录".into());
+        }
+        Ok(hive)
+    }
+
+    /// 单元数据的起始位置（跳过大小字段）
+    fn cell(&self, offset: u32) -> Option<usize> {
+        if offset == u32::MAX {
+            return None;
+        }
+        let start = HBIN_START.checked_add(offset as usize)?;
+        let size = read_u32(&self.data, start)? as i32;
+        let len = size.unsigned_abs() as usize;
+        (len >= 4 && start + len <= self.data.len()).then_some(start + 4)
+    }
+
+    fn signature(&self, cell: usize) -> Option<[u8; 2]> {
+        self.data.get(cell..cell + 2).map(|s| [s[0], s[1]])
+    }
+
+    fn key_name(&self, nk: usize) -> Option<String> {
+        let flags = read_u16(&self.data, nk + 2)?;
+        let len = read_u16(&self.data, nk + 72)? as usize;
+        let raw = self.data.get(nk + 76..nk + 76 + len)?;
+        Some(decode_name(raw, flags & KEY_COMP_NAME != 0))
+    }
+
+    /// 键的所有子键单元
+    fn child_keys(&self, nk: usize) -> Vec<usize> {
+        let count = read_u32(&self.data, nk + 20).unwrap_or(0);
+        let mut children = Vec::new();
+        if count > 0 {
+            if let Some(list) = read_u32(&self.data, nk + 28).and_then(|o| self.cell(o)) {
+                self.collect_list(list, &mut children, &mut HashSet::new(), 0);
+            }
+        }
+        children
+    }
+
+    /// 收集子键列表中的 nk 单元，损坏文件中相互引用的 ri 列表只访问一次
+    fn collect_list(
+        &self,
+        list: usize,
+        out: &mut Vec<usize>,
+        visited: &mut HashSet<usize>,
+        depth: usize,
+    ) {
+        if depth > MAX_DEPTH || !visited.insert(list) {
+            return;
+        }
+        let count = read_u16(&self.data, list + 2).unwrap_or(0) as usize;
+        let (stride, nested) = match self.signature(list) {
+            Some(sig) if &sig == b"lf" || &sig == b"lh" => (8, false),
+            Some(sig) if &sig == b"li" => (4, false),
+            Some(sig) if &sig == b"ri" => (4, true),
+            _ => return,
+        };
+        for i in 0..count {
+            let Some(cell) = read_u32(&self.data, list + 4 + i * stride).and_then(|o| self.cell(o))
+            else {
+                continue;
+            };
+            if nested {
+                self.collect_list(cell, out, visited, depth + 1);
+            } else if self.signature(cell) == Some(*b"nk") {
+                out.push(cell);
+            }
+        }
+    }
+
+    /// 按相对挂载点的路径查找键
+    fn find_key(&self, root: RegistryRoot, path: &str) -> Option<usize> {
+        if root != self.root {
+            return None;
+        }
+        let relative = strip_mount(path, &self.mount)?;
+        let mut current = self.root_cell;
+        for part in relative
+            .split('\\')
+            .filter(|p| !p.is_empty())
+            .take(MAX_DEPTH)
+        {
+            current = self.child_keys(current).into_iter().find(|&child| {
+                self.key_name(child)
+                    .is_some_and(|name| name.eq_ignore_ascii_case(part))
+            })?;
+        }
+        Some(current)
+    }
+
+    fn read_value(&self, vk: usize) -> Option<(String, RegValue)> {
+        if self.signature(vk) != Some(*b"vk") {
+            return None;
+        }
+        let name_len = read_u16(&self.data, vk + 2)? as usize;
+        let size = read_u32(&self.data, vk + 4)?;
+        let value_type = read_u32(&self.data, vk + 12)?;
+        let flags = read_u16(&self.data, vk + 16)?;
+        let name = decode_name(
+            self.data.get(vk + 20..vk + 20 + name_len)?,
+            flags & VALUE_COMP_NAME != 0,
+        );
+
+        let data = if size & DATA_INLINE != 0 {
+            let len = ((size & !DATA_INLINE) as usize).min(4);
+            self.data.get(vk + 8..vk + 8 + len)?.to_vec()
+        } else {
+            let len = size as usize;
+            let cell = self.cell(read_u32(&self.data, vk + 8)?)?;
+            if len > BIG_DATA_SEGMENT && self.signature(cell) == Some(*b"db") {
+                self.read_big_data(cell, len)?
+            } else {
+                self.data.get(cell..cell + len)?.to_vec()
+            }
+        };
+        Some((name, RegValue::from_raw(value_type, &data)))
+    }
+
+    fn read_big_data(&self, db: usize, len: usize) -> Option<Vec<u8>> {
+        let segments = read_u16(&self.data, db + 2)? as usize;
+        let list = self.cell(read_u32(&self.data, db + 4)?)?;
+        // 长度字段可能已损坏，预分配不超过文件大小
+        let mut data = Vec::with_capacity(len.min(self.data.len()));
+        for i in 0..segments {
+            let segment = self.cell(read_u32(&self.data, list + i * 4)?)?;
+            let take = (len - data.len()).min(BIG_DATA_SEGMENT);
+            data.extend_from_slice(self.data.get(segment..segment + take)?);
+            if data.len() >= len {
+                break;
+            }
+        }
+        Some(data)
+    }
+
+    fn values(&self, nk: usize) -> Vec<(String, RegValue)> {
+        let count = read_u32(&self.data, nk + 36).unwrap_or(0) as usize;
+        let Some(list) = read_u32(&self.data, nk + 40).and_then(|o| self.cell(o)) else {
+            return Vec::new();
+        };
+        // 值个数可能已损坏，读到文件末尾即停止
+        (0..count)
+            .map_while(|i| read_u32(&self.data, list + i * 4))
+            .filter_map(|o| self.cell(o))
+            .filter_map(|vk| self.read_value(vk))
+            .collect()
+    }
+}
+
+impl Registry for HiveFile {
+    fn subkeys(&self, root: RegistryRoot, path: &str) -> Option<Vec<String>> {
+        let nk = self.find_key(root, path)?;
+        Some(
+            self.child_keys(nk)
+                .into_iter()
+                .filter_map(|child| self.key_name(child))
+                .collect(),
+        )
+    }
+
+    fn value(&self, root: RegistryRoot, path: &str, name: &str) -> Option<RegValue> {
+        let nk = self.find_key(root, path)?;
+        self.values(nk)
+            .into_iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+}
+
+/// 压缩名称为 Latin-1，否则为 UTF-16 LE
+fn decode_name(raw: &[u8], compressed: bool) -> String {
+    if compressed {
+        raw.iter().map(|&b| b as char).collect()
+    } else {
+        let units: Vec<u16> = raw
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按单元追加内容的 hive，返回单元相对第一个 hbin 的偏移
+    struct Builder {
+        data: Vec<u8>,
+    }
+
+    impl Builder {
+        fn new() -> Self {
+            let mut data = vec![0u8; HBIN_START + 0x20];
+            data[..4].copy_from_slice(REGF_SIGNATURE);
+            data[HBIN_START..HBIN_START + 4].copy_from_slice(b"hbin");
+            Builder { data }
+        }
+
+        fn cell(&mut self, content: &[u8]) -> u32 {
+            let offset = (self.data.len() - HBIN_START) as u32;
+            let size = -((content.len() + 4) as i32);
+            self.data.extend(size.to_le_bytes());
+            self.data.extend(content);
+            offset
+        }
+
+        fn patch_u32(&mut self, cell: u32, field: usize, value: u32) {
+            let at = HBIN_START + cell as usize + 4 + field;
+            self.data[at..at + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        fn set_root(&mut self, cell: u32) {
+            self.data[0x24..0x28].copy_from_slice(&cell.to_le_bytes());
+        }
+
+        fn open(self) -> Result<HiveFile, Box<dyn Error>> {
+            HiveFile::from_bytes(self.data, RegistryRoot::LocalMachine, "SOFTWARE")
+        }
+    }
+
+    fn nk(name: &str) -> Vec<u8> {
+        let mut cell = vec![0u8; 76];
+        cell[..2].copy_from_slice(b"nk");
+        cell[2..4].copy_from_slice(&KEY_COMP_NAME.to_le_bytes());
+        cell[28..32].copy_from_slice(&u32::MAX.to_le_bytes());
+        cell[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+        cell[72..74].copy_from_slice(&(name.len() as u16).to_le_bytes());
+        cell.extend(name.as_bytes());
+        cell
+    }
+
+    fn vk(name: &str, value_type: u32, size: u32, data_offset: u32) -> Vec<u8> {
+        let mut cell = vec![0u8; 20];
+        cell[..2].copy_from_slice(b"vk");
+        cell[2..4].copy_from_slice(&(name.len() as u16).to_le_bytes());
+        cell[4..8].copy_from_slice(&size.to_le_bytes());
+        cell[8..12].copy_from_slice(&data_offset.to_le_bytes());
+        cell[12..16].copy_from_slice(&value_type.to_le_bytes());
+        cell[16..18].copy_from_slice(&VALUE_COMP_NAME.to_le_bytes());
+        cell.extend(name.as_bytes());
+        cell
+    }
+
+    /// 根键 ROOT 下有子键 App，App 有 DWORD 值 Size = 7
+    fn sample() -> Builder {
+        let mut hive = Builder::new();
+        let root = hive.cell(&nk("ROOT"));
+        let app = hive.cell(&nk("App"));
+        let value = hive.cell(&vk("Size", 4, DATA_INLINE | 4, 7));
+        let values = hive.cell(&value.to_le_bytes());
+        hive.patch_u32(app, 36, 1);
+        hive.patch_u32(app, 40, values);
+        let mut list = b"li\x01\x00".to_vec();
+        list.extend(app.to_le_bytes());
+        let list = hive.cell(&list);
+        hive.patch_u32(root, 20, 1);
+        hive.patch_u32(root, 28, list);
+        hive.set_root(root);
+        hive
+    }
+
+    #[test]
+    fn reads_sample_hive() {
+        let hive = sample().open().unwrap();
+        let root = RegistryRoot::LocalMachine;
+        assert_eq!(
+            hive.subkeys(root, "SOFTWARE"),
+            Some(vec!["App".to_string()])
+        );
+        assert_eq!(
+            hive.value(root, "SOFTWARE\\App", "Size"),
+            Some(RegValue::Dword(7))
+        );
+    }
+
+    #[test]
+    fn truncated_hive_does_not_panic() {
+        let data = sample().data;
+        for len in 0..data.len() {
+            let Ok(hive) =
+                HiveFile::from_bytes(data[..len].to_vec(), RegistryRoot::LocalMachine, "SOFTWARE")
+            else {
+                continue;
+            };
+            let _ = hive.subkeys(RegistryRoot::LocalMachine, "SOFTWARE");
+            let _ = hive.value(RegistryRoot::LocalMachine, "SOFTWARE\\App", "Size");
+        }
+    }
+
+    #[test]
+    fn rejects_bad_header_and_root() {
+        let open = |data: Vec<u8>| {
+            HiveFile::from_bytes(data, RegistryRoot::LocalMachine, "SOFTWARE").is_err()
+        };
+        assert!(open(Vec::new()));
+        assert!(open(b"regf".to_vec()));
+
+        let mut hive = sample();
+        hive.set_root(u32::MAX - 1);
+        assert!(hive.open().is_err());
+
+        // 根键单元不是 nk
+        let mut hive = Builder::new();
+        let root = hive.cell(b"vk\x00\x00");
+        hive.set_root(root);
+        assert!(hive.open().is_err());
+    }
+
+    #[test]
+    fn cyclic_subkey_lists_terminate() {
+        let mut hive = Builder::new();
+        let root = hive.cell(&nk("ROOT"));
+        // ri 列表的每一项都指回自身
+        let mut list = b"ri\xff\xff".to_vec();
+        list.extend(vec![0u8; 0xFFFF * 4]);
+        let list = hive.cell(&list);
+        for i in 0..0xFFFF {
+            hive.patch_u32(list, 4 + i * 4, list);
+        }
+        hive.patch_u32(root, 20, 1);
+        hive.patch_u32(root, 28, list);
+        hive.set_root(root);
+        let hive = hive.open().unwrap();
+        assert_eq!(
+            hive.subkeys(RegistryRoot::LocalMachine, "SOFTWARE"),
+            Some(Vec::new())
+        );
+    }
+
+    #[test]
+    fn corrupt_value_fields_are_skipped() {
+        let mut hive = Builder::new();
+        let root = hive.cell(&nk("ROOT"));
+        // 数据偏移越界、长度远超文件、大数据段列表越界
+        let out_of_range = hive.cell(&vk("A", 1, 16, u32::MAX - 8));
+        let data = hive.cell(&[0u8; 8]);
+        let too_long = hive.cell(&vk("B", 3, 0x7FFF_FFFF, data));
+        let mut db = b"db\x02\x00".to_vec();
+        db.extend((u32::MAX - 8).to_le_bytes());
+        let db = hive.cell(&db);
+        let big = hive.cell(&vk("C", 3, 0x7FFF_FFFF, db));
+        let mut values = Vec::new();
+        for cell in [out_of_range, too_long, big] {
+            values.extend(cell.to_le_bytes());
+        }
+        let values = hive.cell(&values);
+        // 值个数远大于列表长度
+        hive.patch_u32(root, 36, u32::MAX);
+        hive.patch_u32(root, 40, values);
+        hive.set_root(root);
+        let hive = hive.open().unwrap();
+        for name in ["A", "B", "C"] {
+            assert_eq!(
+                hive.value(RegistryRoot::LocalMachine, "SOFTWARE", name),
+                None
+            );
+        }
+    }
+}
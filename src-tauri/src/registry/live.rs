@@ -0,0 +1,149 @@
+// 本机注册表（Win32 API）
+
+use super::{RegValue, Registry, RegistryRoot};
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::Foundation::{ERROR_MORE_DATA, ERROR_SUCCESS};
+use windows::Win32::System::Registry::*;
+
+pub struct LiveRegistry;
+
+fn root_key(root: RegistryRoot) -> HKEY {
+    match root {
+        RegistryRoot::LocalMachine => HKEY_LOCAL_MACHINE,
+        RegistryRoot::CurrentUser => HKEY_CURRENT_USER,
+        RegistryRoot::Users => HKEY_USERS,
+        RegistryRoot::ClassesRoot => HKEY_CLASSES_ROOT,
+    }
+}
+
+fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+impl Registry for LiveRegistry {
+    fn subkeys(&self, root: RegistryRoot, path: &str) -> Option<Vec<String>> {
+        unsafe {
+            let path_wide = to_wide(path.trim_matches('\\'));
+            let mut key = HKEY::default();
+            if RegOpenKeyExW(
+                root_key(root),
+                PCWSTR(path_wide.as_ptr()),
+                Some(0),
+                KEY_READ,
+                &mut key,
+            ) != ERROR_SUCCESS
+            {
+                return None;
+            }
+
+            let mut names = Vec::new();
+            let mut index = 0;
+            loop {
+                // 键名最长 255 个字符
+                let mut name_buffer = [0u16; 256];
+                let mut name_len = name_buffer.len() as u32;
+                if RegEnumKeyExW(
+                    key,
+                    index,
+                    Some(PWSTR(name_buffer.as_mut_ptr())),
+                    &mut name_len,
+                    None,
+                    None,
+                    None,
+                    None,
+                ) != ERROR_SUCCESS
+                {
+                    break;
+                }
+                names.push(String::from_utf16_lossy(&name_buffer[..name_len as usize]));
+                index += 1;
+            }
+            let _ = RegCloseKey(key);
+            Some(names)
+        }
+    }
+
+    fn value(&self, root: RegistryRoot, path: &str, name: &str) -> Option<RegValue> {
+        unsafe {
+            let path_wide = to_wide(path.trim_matches('\\'));
+            let name_wide = to_wide(name);
+            let mut buffer = vec![0u8; 512];
+            loop {
+                let mut value_type = REG_VALUE_TYPE::default();
+                let mut size = buffer.len() as u32;
+                // RRF_NOEXPAND 保留 REG_EXPAND_SZ 原文，与离线文件的行为一致
+                let result = RegGetValueW(
+                    root_key(root),
+                    PCWSTR(path_wide.as_ptr()),
+                    PCWSTR(name_wide.as_ptr()),
+                    RRF_RT_ANY | RRF_NOEXPAND,
+                    Some(&mut value_type),
+                    Some(buffer.as_mut_ptr() as *mut _),
+                    Some(&mut size),
+                );
+                if result == ERROR_MORE_DATA {
+                    buffer.resize((size as usize).max(buffer.len() * 2), 0);
+                    continue;
+                }
+                if result != ERROR_SUCCESS {
+                    return None;
+                }
+                buffer.truncate(size as usize);
+                return Some(RegValue::from_raw(value_type.0, &buffer));
+            }
+        }
+    }
+}
+
+/// 监视注册表键（含子键）的变化，每次变化调用 on_change，stop 置位后返回
+///
+/// 键不存在时直接返回 false。
+pub fn watch_key(
+    root: RegistryRoot,
+    path: &str,
+    stop: &std::sync::atomic::AtomicBool,
+    mut on_change: impl FnMut(),
+) -> bool {
+    use std::sync::atomic::Ordering;
+    use windows::Win32::Foundation::{CloseHandle, WAIT_OBJECT_0};
+    use windows::Win32::System::Threading::{CreateEventW, WaitForSingleObject};
+
+    unsafe {
+        let path_wide = to_wide(path.trim_matches('\\'));
+        let mut key = HKEY::default();
+        if RegOpenKeyExW(
+            root_key(root),
+            PCWSTR(path_wide.as_ptr()),
+            Some(0),
+            KEY_NOTIFY,
+            &mut key,
+        ) != ERROR_SUCCESS
+        {
+            return false;
+        }
+        let Ok(event) = CreateEventW(None, false, false, PCWSTR::null()) else {
+            let _ = RegCloseKey(key);
+            return false;
+        };
+
+        // 异步通知只触发一次，每次触发后重新登记；定时醒来检查是否停止
+        let filter = REG_NOTIFY_CHANGE_NAME | REG_NOTIFY_CHANGE_LAST_SET;
+        while !stop.load(Ordering::Relaxed) {
+            if RegNotifyChangeKeyValue(key, true, filter, Some(event), true) != ERROR_SUCCESS {
+                break;
+            }
+            loop {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                if WaitForSingleObject(event, 1000) == WAIT_OBJECT_0 {
+                    on_change();
+                    break;
+                }
+            }
+        }
+        let _ = CloseHandle(event);
+        let _ = RegCloseKey(key);
+        true
+    }
+}
@@ -0,0 +1,248 @@
+// 注册表访问模块 - 统一在线注册表、导出的 .reg 文件与离线 hive 文件
+//
+// 来源只通过 Registry trait 读取注册表，因此同一套解析逻辑既能用于本机，
+// 也能用于备份或挂载磁盘中的注册表文件。
+
+pub mod hive; // regf 二进制 hive 文件（SOFTWARE、NTUSER.DAT 等）
+#[cfg(target_os = "windows")]
+pub mod live; // 本机注册表（Win32 API）
+pub mod reg_file; // regedit 导出的 .reg 文本文件
+pub mod uninstall; // 卸载项解析
+
+use std::error::Error;
+use std::path::Path;
+
+/// 注册表根键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RegistryRoot {
+    LocalMachine,
+    CurrentUser,
+    Users,
+    ClassesRoot,
+}
+
+impl RegistryRoot {
+    /// 解析根键名称，支持完整名称与缩写（HKLM/HKCU/HKU/HKCR）
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "HKEY_LOCAL_MACHINE" | "HKLM" => Some(RegistryRoot::LocalMachine),
+            "HKEY_CURRENT_USER" | "HKCU" => Some(RegistryRoot::CurrentUser),
+            "HKEY_USERS" | "HKU" => Some(RegistryRoot::Users),
+            "HKEY_CLASSES_ROOT" | "HKCR" => Some(RegistryRoot::ClassesRoot),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RegistryRoot::LocalMachine => "HKEY_LOCAL_MACHINE",
+            RegistryRoot::CurrentUser => "HKEY_CURRENT_USER",
+            RegistryRoot::Users => "HKEY_USERS",
+            RegistryRoot::ClassesRoot => "HKEY_CLASSES_ROOT",
+        }
+    }
+}
+
+// 值类型（与 REG_* 常量一致）
+pub const REG_SZ: u32 = 1;
+pub const REG_EXPAND_SZ: u32 = 2;
+pub const REG_BINARY: u32 = 3;
+pub const REG_DWORD: u32 = 4;
+pub const REG_DWORD_BIG_ENDIAN: u32 = 5;
+pub const REG_MULTI_SZ: u32 = 7;
+pub const REG_QWORD: u32 = 11;
+
+/// 注册表值
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegValue {
+    String(String),
+    ExpandString(String),
+    MultiString(Vec<String>),
+    Dword(u32),
+    Qword(u64),
+    Binary(Vec<u8>),
+    /// 其它类型保留原始类型与数据
+    Other(u32, Vec<u8>),
+}
+
+impl RegValue {
+    /// 按类型解码原始数据，字符串数据为 UTF-16 LE
+    pub fn from_raw(value_type: u32, data: &[u8]) -> Self {
+        match value_type {
+            REG_SZ => RegValue::String(decode_utf16_string(data)),
+            REG_EXPAND_SZ => RegValue::ExpandString(decode_utf16_string(data)),
+            REG_MULTI_SZ => RegValue::MultiString(
+                decode_utf16(data)
+                    .split('\0')
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            REG_DWORD if data.len() >= 4 => {
+                RegValue::Dword(u32::from_le_bytes([data[0], data[1], data[2], data[3]]))
+            }
+            REG_DWORD_BIG_ENDIAN if data.len() >= 4 => {
+                RegValue::Dword(u32::from_be_bytes([data[0], data[1], data[2], data[3]]))
+            }
+            REG_QWORD if data.len() >= 8 => {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&data[..8]);
+                RegValue::Qword(u64::from_le_bytes(bytes))
+            }
+            REG_BINARY => RegValue::Binary(data.to_vec()),
+            _ => RegValue::Other(value_type, data.to_vec()),
+        }
+    }
+
+    /// 字符串值（REG_SZ / REG_EXPAND_SZ，后者不展开环境变量）
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            RegValue::String(s) | RegValue::ExpandString(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// 数值（REG_DWORD / REG_QWORD，部分程序把数字写成字符串）
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            RegValue::Dword(v) => Some(*v as u64),
+            RegValue::Qword(v) => Some(*v),
+            RegValue::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// 只读的注册表访问接口
+///
+/// 路径使用反斜杠分隔，不区分大小写；值名称为空字符串表示默认值。
+pub trait Registry: Send + Sync {
+    /// 键的直接子键名称，键不存在时返回 None
+    fn subkeys(&self, root: RegistryRoot, path: &str) -> Option<Vec<String>>;
+
+    /// 读取一个值
+    fn value(&self, root: RegistryRoot, path: &str, name: &str) -> Option<RegValue>;
+
+    /// 读取非空字符串值
+    fn string(&self, root: RegistryRoot, path: &str, name: &str) -> Option<String> {
+        self.value(root, path, name)
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|s| !s.is_empty())
+    }
+}
+
+/// 多个注册表来源的组合，按顺序查询，子键合并去重
+///
+/// 用于同时加载多个离线文件，如 SOFTWARE 与 NTUSER.DAT。
+#[derive(Default)]
+pub struct RegistrySet {
+    sources: Vec<Box<dyn Registry>>,
+}
+
+impl RegistrySet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, source: Box<dyn Registry>) {
+        self.sources.push(source);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+impl Registry for RegistrySet {
+    fn subkeys(&self, root: RegistryRoot, path: &str) -> Option<Vec<String>> {
+        let mut result: Option<Vec<String>> = None;
+        for names in self.sources.iter().filter_map(|s| s.subkeys(root, path)) {
+            let merged = result.get_or_insert_with(Vec::new);
+            for name in names {
+                if !merged.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                    merged.push(name);
+                }
+            }
+        }
+        result
+    }
+
+    fn value(&self, root: RegistryRoot, path: &str, name: &str) -> Option<RegValue> {
+        self.sources.iter().find_map(|s| s.value(root, path, name))
+    }
+}
+
+/// 本机注册表
+#[cfg(target_os = "windows")]
+pub fn system() -> &'static dyn Registry {
+    &live::LiveRegistry
+}
+
+/// 打开注册表文件，按文件头识别 .reg 文本或 regf hive
+///
+/// hive 文件按文件名决定挂载位置：NTUSER.DAT 挂载到 HKEY_CURRENT_USER，
+/// UsrClass.dat 挂载到 HKEY_CURRENT_USER\Software\Classes，
+/// 其它（SOFTWARE、SYSTEM 等）挂载到 HKEY_LOCAL_MACHINE\<文件名>。
+pub fn open_file(path: &Path) -> Result<Box<dyn Registry>, Box<dyn Error>> {
+    let data = std::fs::read(path)?;
+    if data.starts_with(hive::REGF_SIGNATURE) {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_uppercase())
+            .unwrap_or_default();
+        let (root, mount) = match file_name.as_str() {
+            "NTUSER.DAT" => (RegistryRoot::CurrentUser, String::new()),
+            "USRCLASS.DAT" => (RegistryRoot::CurrentUser, "Software\\Classes".to_string()),
+            name => (
+                RegistryRoot::LocalMachine,
+                name.split('.').next().unwrap_or(name).to_string(),
+            ),
+        };
+        return Ok(Box::new(hive::HiveFile::from_bytes(data, root, &mount)?));
+    }
+    Ok(Box::new(reg_file::RegFile::parse(&decode_text(&data))?))
+}
+
+/// 路径是否位于挂载点之下，返回相对挂载点的剩余路径
+pub(crate) fn strip_mount<'a>(path: &'a str, mount: &str) -> Option<&'a str> {
+    let path = path.trim_matches('\\');
+    if mount.is_empty() {
+        return Some(path);
+    }
+    let head = path.get(..mount.len())?;
+    if !head.eq_ignore_ascii_case(mount) {
+        return None;
+    }
+    let rest = &path[mount.len()..];
+    if rest.is_empty() {
+        Some("")
+    } else {
+        rest.strip_prefix('\\')
+    }
+}
+
+/// 文本文件解码：UTF-16 LE（带 BOM）或 UTF-8
+fn decode_text(data: &[u8]) -> String {
+    if let Some(rest) = data.strip_prefix(&[0xFF, 0xFE]) {
+        return decode_utf16(rest);
+    }
+    let data = data.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(data);
+    String::from_utf8_lossy(data).to_string()
+}
+
+fn decode_utf16(data: &[u8]) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// UTF-16 字符串，截断到第一个 NUL
+fn decode_utf16_string(data: &[u8]) -> String {
+    let text = decode_utf16(data);
+    match text.find('\0') {
+        Some(end) => text[..end].to_string(),
+        None => text,
+    }
+}
@@ -0,0 +1,387 @@
+// regedit 导出的 .reg 文本文件
+//
+// 支持 "Windows Registry Editor Version 5.00"（UTF-16）与 "REGEDIT4"（ANSI）两种格式：
+//   [HKEY_LOCAL_MACHINE\SOFTWARE\Example]
+//   @="默认值"
+//   "Name"="字符串"
+//   "Size"=dword:00000400
+//   "Path"=hex(2):25,00,50,00,...   (REG_EXPAND_SZ)
+//   "Data"=hex:01,02,\
+//     03,04                         (行尾反斜杠续行)
+//
+// 以及 Wine 前缀中的 system.reg / user.reg（"WINE REGISTRY Version 2"）：
+//   ;; All keys relative to \\Machine   (键相对的根键)
+//   [Software\\Example] 1700000000     (键名中的反斜杠成对出现，后跟修改时间)
+//   "Path"=str(2):"%ProgramFiles%"     (REG_EXPAND_SZ)
+//   "Name"="\x4e2d\x6587"             (非 ASCII 字符以 \x 转义)
+
+use super::{RegValue, Registry, RegistryRoot, REG_BINARY, REG_EXPAND_SZ, REG_MULTI_SZ};
+use std::collections::HashMap;
+use std::error::Error;
+
+#[derive(Default)]
+struct RegKey {
+    /// 子键原始名称（保持导入顺序）
+    subkeys: Vec<String>,
+    values: Vec<(String, RegValue)>,
+}
+
+/// 解析后的 .reg 文件
+#[derive(Default)]
+pub struct RegFile {
+    /// (根键, 小写路径) -> 键
+    keys: HashMap<(RegistryRoot, String), RegKey>,
+}
+
+impl RegFile {
+    pub fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut lines = text.lines();
+        let header = lines
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches('\u{feff}')
+            .trim();
+        let (unicode, wine) = match header {
+            "Windows Registry Editor Version 5.00" => (true, false),
+            "REGEDIT4" => (false, false),
+            "WINE REGISTRY Version 2" => (true, true),
+            _ => return Err("不是有效的 .reg 文件".into()),
+        };
+
+        let mut file = RegFile::default();
+        // Wine 注册表中的键相对于文件头声明的根键（system.reg 为本机，user.reg 为当前用户）
+        let mut wine_root = RegistryRoot::LocalMachine;
+        let mut current: Option<(RegistryRoot, String)> = None;
+        let mut pending = String::new();
+        for line in lines {
+            // 十六进制数据以反斜杠续行
+            let line = line.trim();
+            if let Some(head) = line
+                .strip_suffix('\\')
+                .filter(|_| !pending.is_empty() || is_hex_line(line))
+            {
+                pending.push_str(head);
+                continue;
+            }
+            let line = if pending.is_empty() {
+                line.to_string()
+            } else {
+                std::mem::take(&mut pending) + line
+            };
+            if wine {
+                if let Some(relative) = line.strip_prefix(";; All keys relative to ") {
+                    wine_root = if relative.starts_with("\\\\User") {
+                        RegistryRoot::CurrentUser
+                    } else {
+                        RegistryRoot::LocalMachine
+                    };
+                    continue;
+                }
+                // #time=、#arch= 等元数据
+                if line.starts_with('#') {
+                    continue;
+                }
+                if let Some((key, _)) = line.strip_prefix('[').and_then(|l| l.rsplit_once(']')) {
+                    let key = format!("{}\\{}", wine_root.name(), key.replace("\\\\", "\\"));
+                    current = file.create_key(&key);
+                    continue;
+                }
+            }
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(key) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                current = match key.strip_prefix('-') {
+                    Some(deleted) => {
+                        file.delete_key(deleted);
+                        None
+                    }
+                    None => file.create_key(key),
+                };
+                continue;
+            }
+
+            let Some(key) = &current else {
+                continue;
+            };
+            let Some((name, data)) = split_assignment(&line) else {
+                continue;
+            };
+            let entry = file.keys.get_mut(key).expect("当前键已创建");
+            entry.values.retain(|(n, _)| !n.eq_ignore_ascii_case(&name));
+            if data != "-" {
+                if let Some(value) = parse_value(data, unicode) {
+                    entry.values.push((name, value));
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    /// 创建键及其所有上级键，返回键的索引
+    fn create_key(&mut self, full_path: &str) -> Option<(RegistryRoot, String)> {
+        let (root_name, path) = full_path.split_once('\\').unwrap_or((full_path, ""));
+        let root = RegistryRoot::parse(root_name)?;
+        let mut parent = String::new();
+        self.keys.entry((root, parent.clone())).or_default();
+        for part in path.split('\\').filter(|p| !p.is_empty()) {
+            let child = if parent.is_empty() {
+                part.to_lowercase()
+            } else {
+                format!("{}\\{}", parent, part.to_lowercase())
+            };
+            if !self.keys.contains_key(&(root, child.clone())) {
+                self.keys.entry((root, child.clone())).or_default();
+                if let Some(p) = self.keys.get_mut(&(root, parent.clone())) {
+                    p.subkeys.push(part.to_string());
+                }
+            }
+            parent = child;
+        }
+        Some((root, parent))
+    }
+
+    /// [-键] 删除键及其所有子键
+    fn delete_key(&mut self, full_path: &str) {
+        let (root_name, path) = full_path.split_once('\\').unwrap_or((full_path, ""));
+        let Some(root) = RegistryRoot::parse(root_name) else {
+            return;
+        };
+        let path = path.trim_matches('\\').to_lowercase();
+        let prefix = format!("{}\\", path);
+        self.keys
+            .retain(|(r, p), _| *r != root || (*p != path && !p.starts_with(&prefix)));
+        let (parent, name) = path.rsplit_once('\\').unwrap_or(("", &path));
+        if let Some(p) = self.keys.get_mut(&(root, parent.to_string())) {
+            p.subkeys.retain(|n| !n.eq_ignore_ascii_case(name));
+        }
+    }
+
+    fn key(&self, root: RegistryRoot, path: &str) -> Option<&RegKey> {
+        self.keys
+            .get(&(root, path.trim_matches('\\').to_lowercase()))
+    }
+}
+
+impl Registry for RegFile {
+    fn subkeys(&self, root: RegistryRoot, path: &str) -> Option<Vec<String>> {
+        self.key(root, path).map(|k| k.subkeys.clone())
+    }
+
+    fn value(&self, root: RegistryRoot, path: &str, name: &str) -> Option<RegValue> {
+        self.key(root, path)?
+            .values
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.clone())
+    }
+}
+
+fn is_hex_line(line: &str) -> bool {
+    split_assignment(line).is_some_and(|(_, data)| data.starts_with("hex"))
+}
+
+/// 拆分 "名称"=数据 或 @=数据，返回去掉转义的名称
+fn split_assignment(line: &str) -> Option<(String, &str)> {
+    if let Some(data) = line.strip_prefix("@=") {
+        return Some((String::new(), data));
+    }
+    let (name, rest) = parse_quoted(line)?;
+    Some((name, rest.trim_start().strip_prefix('=')?.trim_start()))
+}
+
+/// 解析以双引号开头的字符串，返回内容与剩余部分
+fn parse_quoted(text: &str) -> Option<(String, &str)> {
+    let body = text.strip_prefix('"')?;
+    let mut result = String::new();
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    match escaped {
+                        'n' => result.push('\n'),
+                        'r' => result.push('\r'),
+                        't' => result.push('\t'),
+                        // Wine：\x 后跟至多 4 位十六进制，\ 后跟至多 3 位八进制（如 \0）
+                        'x' => result.extend(char::from_u32(take_digits(&mut chars, 16, 4, 0))),
+                        d @ '0'..='7' => {
+                            let first = d as u32 - '0' as u32;
+                            result.extend(char::from_u32(take_digits(&mut chars, 8, 2, first)))
+                        }
+                        other => result.push(other),
+                    }
+                }
+            }
+            '"' => return Some((result, &body[i + 1..])),
+            c => result.push(c),
+        }
+    }
+    None
+}
+
+/// 继续读取至多 max 位数字，累加到 initial 上
+fn take_digits(chars: &mut std::str::CharIndices, radix: u32, max: usize, initial: u32) -> u32 {
+    let mut code = initial;
+    for _ in 0..max {
+        match chars.clone().next().and_then(|(_, c)| c.to_digit(radix)) {
+            Some(digit) => {
+                code = code * radix + digit;
+                chars.next();
+            }
+            None => break,
+        }
+    }
+    code
+}
+
+fn parse_value(data: &str, unicode: bool) -> Option<RegValue> {
+    if data.starts_with('"') {
+        return parse_quoted(data).map(|(s, _)| RegValue::String(s));
+    }
+    // Wine：str(2):"..." 与 str(7):"..."（以 \0 分隔）
+    if let Some((type_hex, text)) = data.strip_prefix("str(").and_then(|r| r.split_once("):")) {
+        let (text, _) = parse_quoted(text)?;
+        return Some(match u32::from_str_radix(type_hex.trim(), 16).ok()? {
+            REG_EXPAND_SZ => RegValue::ExpandString(text),
+            REG_MULTI_SZ => RegValue::MultiString(
+                text.split('\0')
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            _ => RegValue::String(text),
+        });
+    }
+    if let Some(hex) = data.strip_prefix("dword:") {
+        return u32::from_str_radix(hex.trim(), 16)
+            .ok()
+            .map(RegValue::Dword);
+    }
+    let (value_type, bytes) = if let Some(bytes) = data.strip_prefix("hex:") {
+        (REG_BINARY, bytes)
+    } else {
+        let rest = data.strip_prefix("hex(")?;
+        let (type_hex, bytes) = rest.split_once("):")?;
+        (u32::from_str_radix(type_hex.trim(), 16).ok()?, bytes)
+    };
+    let bytes: Vec<u8> = bytes
+        .split(',')
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+        .map(|b| u8::from_str_radix(b, 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+
+    // REGEDIT4 中的字符串类型是 ANSI 编码，转成 UTF-16 后统一解码
+    if !unicode && matches!(value_type, REG_EXPAND_SZ | REG_MULTI_SZ) {
+        let text = String::from_utf8_lossy(&bytes).to_string();
+        let wide: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        return Some(RegValue::from_raw(value_type, &wide));
+    }
+    Some(RegValue::from_raw(value_type, &bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HKLM: RegistryRoot = RegistryRoot::LocalMachine;
+
+    #[test]
+    fn reads_regedit_and_wine_files() {
+        let file = RegFile::parse(
+            "Windows Registry Editor Version 5.00\r\n\
+             [HKEY_LOCAL_MACHINE\\SOFTWARE\\App]\r\n\
+             @=\"默认\"\r\n\
+             \"Size\"=dword:00000400\r\n\
+             \"Path\"=hex(2):25,00,41,00,\\\r\n\
+               25,00,00,00\r\n",
+        )
+        .unwrap();
+        assert_eq!(file.string(HKLM, "SOFTWARE\\App", ""), Some("默认".into()));
+        assert_eq!(
+            file.value(HKLM, "software\\app", "Size"),
+            Some(RegValue::Dword(0x400))
+        );
+        assert_eq!(
+            file.value(HKLM, "SOFTWARE\\App", "Path"),
+            Some(RegValue::ExpandString("%A%".into()))
+        );
+
+        let file = RegFile::parse(
+            "WINE REGISTRY Version 2\n\
+             ;; All keys relative to \\\\User\\\\S-1-5-21\n\
+             [Software\\\\App] 1700000000\n\
+             #time=1d9\n\
+             \"Name\"=\"\\x4e2d\\x6587\"\n\
+             \"Path\"=str(2):\"%ProgramFiles%\"\n",
+        )
+        .unwrap();
+        let root = RegistryRoot::CurrentUser;
+        assert_eq!(
+            file.string(root, "Software\\App", "Name"),
+            Some("中文".into())
+        );
+        assert_eq!(
+            file.value(root, "Software\\App", "Path"),
+            Some(RegValue::ExpandString("%ProgramFiles%".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_header() {
+        assert!(RegFile::parse("").is_err());
+        assert!(RegFile::parse("[HKEY_LOCAL_MACHINE\\SOFTWARE]").is_err());
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let file = RegFile::parse(
+            "REGEDIT4\n\
+             \"Orphan\"=\"值在任何键之前\"\n\
+             [HKEY_NOWHERE\\Key]\n\
+             \"Lost\"=\"根键无效\"\n\
+             [HKEY_LOCAL_MACHINE\\SOFTWARE\\App\n\
+             [HKEY_LOCAL_MACHINE\\SOFTWARE\\App]\n\
+             \"Unterminated=\"abc\n\
+             \"NoValue\"\n\
+             \"BadDword\"=dword:xyz\n\
+             \"BadHex\"=hex:zz,01\n\
+             \"BadType\"=hex(zz):01\n\
+             \"BadStr\"=str(2):unquoted\n\
+             \"Escapes\"=\"\\xd800\\777\\\"\n\
+             \"Ok\"=\"yes\"\n\
+             [-HKEY_LOCAL_MACHINE\\SOFTWARE\\Gone]\n\
+             \"AfterDelete\"=\"没有当前键\"\n\
+             \"Tail\"=hex:01,02,\\\n",
+        )
+        .unwrap();
+        assert_eq!(file.subkeys(HKLM, "SOFTWARE"), Some(vec!["App".into()]));
+        for name in [
+            "Unterminated",
+            "NoValue",
+            "BadDword",
+            "BadHex",
+            "BadType",
+            "BadStr",
+        ] {
+            assert_eq!(file.value(HKLM, "SOFTWARE\\App", name), None, "{}", name);
+        }
+        assert_eq!(file.string(HKLM, "SOFTWARE\\App", "Ok"), Some("yes".into()));
+        assert_eq!(file.value(HKLM, "SOFTWARE\\App", "Tail"), None);
+    }
+
+    #[test]
+    fn truncated_escapes_do_not_panic() {
+        // 转义与十六进制续行在任意位置截断
+        let text = "WINE REGISTRY Version 2\n\
+                    [Software\\\\App] 1\n\
+                    \"N\\x4e2d\\101\"=\"\\x4e2d\\0\\\\\\\"\"\n\
+                    \"H\"=hex(7):41,00,\\\n  00,00\n";
+        for end in (0..=text.len()).filter(|&i| text.is_char_boundary(i)) {
+            let _ = RegFile::parse(&text[..end]);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// 卸载信息（Uninstall 键）解析
+
+use super::{Registry, RegistryRoot};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// 读取卸载项的位置：64 位、32 位（WOW6432Node）与当前用户
+pub const UNINSTALL_KEYS: &[(RegistryRoot, &str)] = &[
+    (
+        RegistryRoot::LocalMachine,
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+    ),
+    (
+        RegistryRoot::LocalMachine,
+        "SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+    ),
+    (
+        RegistryRoot::CurrentUser,
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
+    ),
+];
+
+/// 依赖关系的登记位置：每个提供者键的默认值为产品代码，Dependents 下为依赖它的产品
+const DEPENDENCY_KEYS: &[(RegistryRoot, &str)] = &[
+    (
+        RegistryRoot::LocalMachine,
+        "SOFTWARE\\Classes\\Installer\\Dependencies",
+    ),
+    (
+        RegistryRoot::CurrentUser,
+        "SOFTWARE\\Classes\\Installer\\Dependencies",
+    ),
+];
+
+/// 一个卸载项
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UninstallEntry {
+    /// 注册表位置，如 HKEY_LOCAL_MACHINE\SOFTWARE\...\Uninstall\{GUID}
+    pub registry_key: String,
+    pub display_name: String,
+    pub display_icon: Option<String>,
+    pub display_version: Option<String>,
+    pub publisher: Option<String>,
+    /// 安装日期，已规范为 YYYY-MM-DD
+    pub install_date: Option<String>,
+    /// 估计占用空间（KB）
+    pub estimated_size: Option<u64>,
+    pub url_info_about: Option<String>,
+    pub install_location: Option<String>,
+    pub uninstall_string: Option<String>,
+    pub quiet_uninstall_string: Option<String>,
+    /// 由 Windows Installer（MSI）安装
+    pub windows_installer: bool,
+    /// 系统组件，不在"程序和功能"中显示
+    pub system_component: bool,
+    /// 所属的父产品（补丁、语言包等）
+    pub parent_key_name: Option<String>,
+    /// 发布类型，如 Hotfix、Security Update、Update Rollup
+    pub release_type: Option<String>,
+}
+
+impl UninstallEntry {
+    /// 不属于独立程序的原因：系统组件、补丁更新或其它产品的子项
+    pub fn hidden_reason(&self) -> Option<&'static str> {
+        if self.system_component {
+            return Some("SystemComponent");
+        }
+        if self.parent_key_name.is_some() {
+            return Some("ParentKeyName");
+        }
+        let release_type = self.release_type.as_deref().unwrap_or_default();
+        let release_type = release_type.to_lowercase();
+        if release_type.contains("hotfix") || release_type.contains("update") {
+            return Some("ReleaseType");
+        }
+        None
+    }
+
+    /// 卸载项的键名：MSI 为产品代码，WiX Burn 捆绑包为捆绑包 ID
+    fn key_name(&self) -> String {
+        self.registry_key
+            .rsplit('\\')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+}
+
+/// 读取所有卸载项（没有 DisplayName 的项不会出现在"程序和功能"中，直接跳过）
+pub fn read_uninstall_entries(registry: &dyn Registry) -> Vec<UninstallEntry> {
+    let mut entries = Vec::new();
+    for (root, key) in UNINSTALL_KEYS {
+        let Some(names) = registry.subkeys(*root, key) else {
+            println!("无法打开注册表键: {:?}", key);
+            continue;
+        };
+        println!("成功打开注册表键: {:?}", key);
+        entries.extend(
+            names
+                .iter()
+                .filter_map(|name| read_entry(registry, *root, &format!("{}\\{}", key, name))),
+        );
+    }
+    entries
+}
+
+/// 过滤掉系统组件、补丁更新与安装包内部的 MSI 子产品，只保留独立程序
+///
+/// 引导程序（如 WiX Burn）会登记一个可见的捆绑包，再把其中的 MSI 各自登记一次；
+/// 捆绑包在 MSI 的依赖提供者下登记为依赖方，被可见的捆绑包依赖的 MSI 视为子产品。
+pub fn visible_entries(
+    registry: &dyn Registry,
+    entries: Vec<UninstallEntry>,
+) -> Vec<UninstallEntry> {
+    let bundles: HashSet<String> = entries
+        .iter()
+        .filter(|e| !e.windows_installer && e.hidden_reason().is_none())
+        .map(UninstallEntry::key_name)
+        .collect();
+    let bundled = bundled_products(registry, &bundles);
+
+    let total = entries.len();
+    let visible: Vec<UninstallEntry> = entries
+        .into_iter()
+        .filter(|e| {
+            e.hidden_reason().is_none() && !(e.windows_installer && bundled.contains(&e.key_name()))
+        })
+        .collect();
+    println!(
+        "🧹 [过滤阶段] 已安装程序过滤系统组件与更新, 隐藏 {} / {} 个条目",
+        total - visible.len(),
+        total
+    );
+    visible
+}
+
+/// 被捆绑包依赖的产品代码（小写）
+fn bundled_products(registry: &dyn Registry, bundles: &HashSet<String>) -> HashSet<String> {
+    let mut products = HashSet::new();
+    for (root, key) in DEPENDENCY_KEYS {
+        for provider in registry.subkeys(*root, key).unwrap_or_default() {
+            let path = format!("{}\\{}", key, provider);
+            let dependents = registry
+                .subkeys(*root, &format!("{}\\Dependents", path))
+                .unwrap_or_default();
+            if !dependents
+                .iter()
+                .any(|d| bundles.contains(&d.to_lowercase()))
+            {
+                continue;
+            }
+            if let Some(product) = registry.string(*root, &path, "") {
+                products.insert(product.trim().to_lowercase());
+            }
+        }
+    }
+    products
+}
+
+fn read_entry(registry: &dyn Registry, root: RegistryRoot, path: &str) -> Option<UninstallEntry> {
+    let read = |name: &str| registry.string(root, path, name);
+    let number = |name: &str| registry.value(root, path, name).and_then(|v| v.as_u64());
+    Some(UninstallEntry {
+        registry_key: format!("{}\\{}", root.name(), path),
+        display_name: read("DisplayName")?,
+        display_icon: read("DisplayIcon"),
+        display_version: read("DisplayVersion"),
+        publisher: read("Publisher"),
+        install_date: read("InstallDate").and_then(|d| normalize_install_date(&d)),
+        estimated_size: number("EstimatedSize"),
+        url_info_about: read("URLInfoAbout"),
+        install_location: read("InstallLocation"),
+        uninstall_string: read("UninstallString"),
+        quiet_uninstall_string: read("QuietUninstallString"),
+        windows_installer: number("WindowsInstaller") == Some(1),
+        system_component: number("SystemComponent") == Some(1),
+        parent_key_name: read("ParentKeyName"),
+        release_type: read("ReleaseType"),
+    })
+}
+
+/// InstallDate 通常为 YYYYMMDD，少数程序写成 YYYY-MM-DD 或 MM/DD/YYYY
+fn normalize_install_date(date: &str) -> Option<String> {
+    let date = date.trim();
+    let digits: String = date.chars().filter(char::is_ascii_digit).collect();
+    if digits.len() != 8 {
+        return None;
+    }
+    let (year, month, day) = if date.contains('/') {
+        (&digits[4..], &digits[..2], &digits[2..4])
+    } else {
+        (&digits[..4], &digits[4..6], &digits[6..])
+    };
+    Some(format!("{}-{}-{}", year, month, day))
+}
@@ -0,0 +1,387 @@
+// 噪声过滤规则 - 在提取图标之前过滤目录扫描得到的条目
+
+use crate::config::scan_settings;
+use crate::types::DesktopIcon;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+// 命中记录中每条规则返回的示例路径数量
+const MAX_HIT_SAMPLES: usize = 20;
+
+/// 规则命中后的动作
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    /// 隐藏条目
+    #[default]
+    Hide,
+    /// 保留条目，用于覆盖后面的规则
+    Keep,
+}
+
+/// 一条过滤规则，所有已设置的条件都满足才算命中
+///
+/// 文本条件使用不区分大小写的通配符（* 与 ?），多个模式用 | 分隔。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterRule {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub action: RuleAction,
+    /// 文件名，如 "unins*.exe"
+    pub file_name: Option<String>,
+    /// 任一上级目录名，如 "redist|_commonredist"
+    pub path_segment: Option<String>,
+    /// 版本资源中的 FileDescription 或 ProductName
+    pub description: Option<String>,
+    /// 版本资源中的 CompanyName
+    pub company: Option<String>,
+    /// PE 子系统：gui/console/native/dos...
+    pub subsystem: Option<String>,
+    /// 文件大小范围（字节）
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// 同目录下存在匹配的文件或文件夹
+    pub sibling: Option<String>,
+}
+
+impl Default for FilterRule {
+    fn default() -> Self {
+        FilterRule {
+            id: String::new(),
+            name: String::new(),
+            enabled: true,
+            action: RuleAction::Hide,
+            file_name: None,
+            path_segment: None,
+            description: None,
+            company: None,
+            subsystem: None,
+            min_size: None,
+            max_size: None,
+            sibling: None,
+        }
+    }
+}
+
+impl FilterRule {
+    fn has_conditions(&self) -> bool {
+        self.file_name.is_some()
+            || self.path_segment.is_some()
+            || self.description.is_some()
+            || self.company.is_some()
+            || self.subsystem.is_some()
+            || self.min_size.is_some()
+            || self.max_size.is_some()
+            || self.sibling.is_some()
+    }
+
+    /// 判断条目是否命中规则；缺少对应信息的条件视为不满足
+    fn matches(&self, icon: &DesktopIcon, siblings: &mut SiblingCache) -> bool {
+        if !self.enabled || !self.has_conditions() {
+            return false;
+        }
+        let path = Path::new(icon.target_path.trim().trim_matches('"'));
+
+        if let Some(pattern) = &self.file_name {
+            let name = path.file_name().map(|n| n.to_string_lossy());
+            if !name.is_some_and(|n| pattern_matches(pattern, &n)) {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.path_segment {
+            let matched = path.parent().is_some_and(|parent| {
+                parent
+                    .components()
+                    .any(|c| pattern_matches(pattern, &c.as_os_str().to_string_lossy()))
+            });
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.description {
+            let matched = [&icon.file_description, &icon.product_name]
+                .into_iter()
+                .flatten()
+                .any(|d| pattern_matches(pattern, d));
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.company {
+            if !icon
+                .company_name
+                .as_deref()
+                .is_some_and(|c| pattern_matches(pattern, c))
+            {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.subsystem {
+            if !icon
+                .subsystem
+                .as_deref()
+                .is_some_and(|s| pattern_matches(pattern, s))
+            {
+                return false;
+            }
+        }
+        if self.min_size.is_some() || self.max_size.is_some() {
+            let Some(size) = icon.file_size else {
+                return false;
+            };
+            if self.min_size.is_some_and(|min| size < min)
+                || self.max_size.is_some_and(|max| size > max)
+            {
+                return false;
+            }
+        }
+        // 需要读取目录，放在最后判断
+        if let Some(pattern) = &self.sibling {
+            let file_name = path.file_name().map(|n| n.to_string_lossy().to_lowercase());
+            let matched = path.parent().is_some_and(|parent| {
+                siblings
+                    .entries(parent)
+                    .iter()
+                    .filter(|name| Some(*name) != file_name.as_ref())
+                    .any(|name| pattern_matches(pattern, name))
+            });
+            if !matched {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 内置规则
+pub fn default_rules() -> Vec<FilterRule> {
+    let rule = |id: &str, name: &str| FilterRule {
+        id: id.to_string(),
+        name: name.to_string(),
+        ..Default::default()
+    };
+    vec![
+        FilterRule {
+            file_name: Some("unins*.exe|uninst*.exe|*uninstall*|*uninstaller*".into()),
+            ..rule("builtin.uninstaller", "卸载程序")
+        },
+        FilterRule {
+            file_name: Some(
+                "updater.exe|*_updater.exe|*-updater.exe|*autoupdate*.exe|maintenancetool.exe"
+                    .into(),
+            ),
+            ..rule("builtin.updater", "更新程序")
+        },
+        FilterRule {
+            file_name: Some("update.exe".into()),
+            sibling: Some("app-*|.dead|packages".into()),
+            ..rule("builtin.squirrel_update", "Squirrel 更新程序")
+        },
+        FilterRule {
+            file_name: Some(
+                "crashpad_handler.exe|*crashreporter*.exe|*crash_reporter*.exe|*crashhandler*.exe|*bugreport*.exe|breakpad*.exe"
+                    .into(),
+            ),
+            ..rule("builtin.crash_reporter", "崩溃报告程序")
+        },
+        FilterRule {
+            file_name: Some(
+                "vc_redist*.exe|vcredist*.exe|dxsetup.exe|dotnetfx*.exe|ndp*.exe|*redist*.exe"
+                    .into(),
+            ),
+            ..rule("builtin.redistributable", "运行库安装包")
+        },
+        FilterRule {
+            path_segment: Some("redist|_commonredist|redistributables|directx|vcredist".into()),
+            ..rule("builtin.redist_folder", "运行库目录中的程序")
+        },
+        FilterRule {
+            file_name: Some(
+                "*helper.exe|elevation_service.exe|*-service.exe".into(),
+            ),
+            ..rule("builtin.helper", "辅助进程")
+        },
+        FilterRule {
+            file_name: Some("setup.exe|installer.exe|*_setup.exe|*-setup.exe|*_installer.exe".into()),
+            ..rule("builtin.installer", "安装程序")
+        },
+        FilterRule {
+            path_segment: Some("node_modules|site-packages|__pycache__|locales|swiftshader".into()),
+            ..rule("builtin.dependency_folder", "依赖目录中的程序")
+        },
+        FilterRule {
+            file_name: Some("*.exe".into()),
+            max_size: Some(8 * 1024),
+            ..rule("builtin.stub", "体积过小的存根程序")
+        },
+    ]
+}
+
+/// 当前生效的规则：用户规则在前（可以用 keep 覆盖内置规则），其后是内置规则
+pub fn active_rules() -> Vec<FilterRule> {
+    let settings = scan_settings();
+    let mut rules = settings.filter_rules;
+    if settings.use_default_rules {
+        rules.extend(default_rules());
+    }
+    rules
+}
+
+/// 按规则过滤条目，返回保留的条目并记录命中
+pub fn filter_entries(icons: Vec<DesktopIcon>, source_name: &str) -> Vec<DesktopIcon> {
+    let rules = active_rules();
+    if rules.is_empty() {
+        return icons;
+    }
+
+    let start = std::time::Instant::now();
+    let total = icons.len();
+    let mut siblings = SiblingCache::default();
+    let mut hits: Vec<(&FilterRule, String)> = Vec::new();
+    let kept: Vec<DesktopIcon> = icons
+        .into_iter()
+        .filter(
+            |icon| match rules.iter().find(|r| r.matches(icon, &mut siblings)) {
+                Some(rule) if rule.action == RuleAction::Hide => {
+                    hits.push((rule, icon.target_path.clone()));
+                    false
+                }
+                _ => true,
+            },
+        )
+        .collect();
+
+    record_hits(&hits);
+    println!(
+        "🧹 [过滤阶段] {} 规则过滤完成, 隐藏 {} / {} 个条目, 耗时: {:.3}s",
+        source_name,
+        total - kept.len(),
+        total,
+        start.elapsed().as_secs_f64()
+    );
+    kept
+}
+
+/// 同一次过滤中按目录缓存的文件名列表（小写）
+#[derive(Default)]
+struct SiblingCache {
+    dirs: HashMap<PathBuf, Vec<String>>,
+}
+
+impl SiblingCache {
+    fn entries(&mut self, dir: &Path) -> &[String] {
+        self.dirs.entry(dir.to_path_buf()).or_insert_with(|| {
+            std::fs::read_dir(dir)
+                .map(|entries| {
+                    entries
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.file_name().to_string_lossy().to_lowercase())
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+}
+
+// ========== 命中统计 ==========
+
+/// 单条规则的命中统计
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleHitSummary {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub count: usize,
+    /// 部分被隐藏的路径
+    pub samples: Vec<String>,
+}
+
+struct RuleHits {
+    rule_name: String,
+    paths: BTreeSet<String>,
+}
+
+static RULE_HITS: OnceLock<Mutex<BTreeMap<String, RuleHits>>> = OnceLock::new();
+
+fn hits_lock() -> &'static Mutex<BTreeMap<String, RuleHits>> {
+    RULE_HITS.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+/// 按路径去重记录，重复扫描不会重复计数
+fn record_hits(hits: &[(&FilterRule, String)]) {
+    if hits.is_empty() {
+        return;
+    }
+    if let Ok(mut map) = hits_lock().lock() {
+        for (rule, path) in hits {
+            map.entry(rule.id.clone())
+                .or_insert_with(|| RuleHits {
+                    rule_name: rule.name.clone(),
+                    paths: BTreeSet::new(),
+                })
+                .paths
+                .insert(path.clone());
+        }
+    }
+}
+
+/// 各规则的命中统计，按命中数降序
+pub fn rule_hits() -> Vec<RuleHitSummary> {
+    let Ok(map) = hits_lock().lock() else {
+        return Vec::new();
+    };
+    let mut summaries: Vec<RuleHitSummary> = map
+        .iter()
+        .map(|(id, hits)| RuleHitSummary {
+            rule_id: id.clone(),
+            rule_name: hits.rule_name.clone(),
+            count: hits.paths.len(),
+            samples: hits.paths.iter().take(MAX_HIT_SAMPLES).cloned().collect(),
+        })
+        .collect();
+    summaries.sort_by_key(|s| std::cmp::Reverse(s.count));
+    summaries
+}
+
+/// 清空命中统计（规则变化后旧的统计不再准确）
+pub fn clear_rule_hits() {
+    if let Ok(mut map) = hits_lock().lock() {
+        map.clear();
+    }
+}
+
+// ========== 通配符匹配 ==========
+
+/// 以 | 分隔的多个通配符模式，任一匹配即可
+pub(crate) fn pattern_matches(patterns: &str, text: &str) -> bool {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    patterns.split('|').map(str::trim).any(|p| {
+        let p: Vec<char> = p.to_lowercase().chars().collect();
+        wildcard_match(&p, &text)
+    })
+}
+
+fn wildcard_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // 让上一个 * 多匹配一个字符
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -1,16 +1,23 @@
 // 快捷方式解析模块
+//
+// 快捷方式解析依赖 Shell API，只在 Windows 上可用；
+// 文件元数据等辅助函数也用于离线扫描。
 
+#[cfg(target_os = "windows")]
 use crate::extractors::utils::ComInit;
+#[cfg(target_os = "windows")]
+use crate::path::normalize_path;
+#[cfg(target_os = "windows")]
+use crate::sources::squirrel::resolve_squirrel_target;
 use std::fs;
 use std::path::Path;
 use std::time::SystemTime;
+#[cfg(target_os = "windows")]
 use windows::{
     core::{Interface, PCWSTR},
-    Win32::Storage::FileSystem::{FILE_FLAGS_AND_ATTRIBUTES, WIN32_FIND_DATAW},
+    Win32::Storage::FileSystem::WIN32_FIND_DATAW,
     Win32::System::Com::{CoCreateInstance, IPersistFile, CLSCTX_INPROC_SERVER, STGM_READ},
-    Win32::UI::Shell::{
-        IShellLinkW, SHGetFileInfoW, ShellLink, SHFILEINFOW, SHGFI_DISPLAYNAME, SLGP_UNCPRIORITY,
-    },
+    Win32::UI::Shell::{IShellLinkW, ShellLink, SLGP_UNCPRIORITY},
 };
 
 /// 文件元数据信息
@@ -23,6 +30,7 @@ pub struct FileMetadata {
 }
 
 /// 快捷方式完整信息结构
+#[cfg(target_os = "windows")]
 pub struct ShortcutInfo {
     pub target_path: String,
     pub icon_path: String,
@@ -32,9 +40,11 @@ pub struct ShortcutInfo {
     pub working_directory: String,
     pub hotkey: u16,
     pub show_command: i32,
+    pub app_user_model_id: Option<String>,
 }
 
 /// 获取快捷方式的完整信息
+#[cfg(target_os = "windows")]
 pub fn get_shortcut_full_info(lnk_path: &Path) -> Result<ShortcutInfo, Box<dyn std::error::Error>> {
     unsafe {
         let _com = ComInit::new(windows::Win32::System::Com::COINIT_MULTITHREADED);
@@ -77,13 +87,9 @@ pub fn get_shortcut_full_info(lnk_path: &Path) -> Result<ShortcutInfo, Box<dyn s
         // 获取显示命令
         let show_cmd = shell_link.GetShowCmd().map(|cmd| cmd.0).unwrap_or(1);
 
-        let target = String::from_utf16_lossy(&target_path)
-            .trim_end_matches('\0')
-            .to_string();
-
-        let icon = String::from_utf16_lossy(&icon_path)
-            .trim_end_matches('\0')
-            .to_string();
+        // 目标可能以已知文件夹 GUID 开头，图标位置常含 %SystemRoot% 等环境变量
+        let target = normalize_path(String::from_utf16_lossy(&target_path).trim_end_matches('\0'));
+        let icon = normalize_path(String::from_utf16_lossy(&icon_path).trim_end_matches('\0'));
         let desc = String::from_utf16_lossy(&description)
             .trim_end_matches('\0')
             .to_string();
@@ -94,12 +100,21 @@ pub fn get_shortcut_full_info(lnk_path: &Path) -> Result<ShortcutInfo, Box<dyn s
             .trim_end_matches('\0')
             .to_string();
 
-        let final_icon_path = if icon.is_empty() {
+        let mut final_icon_path = if icon.is_empty() {
             target.clone()
         } else {
             icon
         };
 
+        // Squirrel 应用的快捷方式指向通用的 Update.exe，目标与图标改为实际程序，
+        // 这样桌面与开始菜单中的同一应用能按目标合并
+        let mut target = target;
+        if let Some(resolved) = resolve_squirrel_target(&target, &args) {
+            target = resolved.to_string_lossy().to_string();
+            final_icon_path = target.clone();
+            icon_index = 0;
+        }
+
         Ok(ShortcutInfo {
             target_path: target,
             icon_path: final_icon_path,
@@ -109,6 +124,10 @@ pub fn get_shortcut_full_info(lnk_path: &Path) -> Result<ShortcutInfo, Box<dyn s
             working_directory: work_dir,
             hotkey,
             show_command: show_cmd,
+            // IShellLink 不提供属性存储中的 AppUserModelID，直接解析文件
+            app_user_model_id: crate::lnk::ShellLink::read(lnk_path)
+                .ok()
+                .and_then(|link| link.app_user_model_id),
         })
     }
 }
@@ -135,13 +154,13 @@ pub fn hotkey_to_string(hotkey: u16) -> Option<String> {
     }
 
     // 添加按键
-    if key >= 0x41 && key <= 0x5A {
+    if (0x41..=0x5A).contains(&key) {
         // A-Z
         parts.push(format!("{}", key as char));
-    } else if key >= 0x30 && key <= 0x39 {
+    } else if (0x30..=0x39).contains(&key) {
         // 0-9
         parts.push(format!("{}", key as char));
-    } else if key >= 0x70 && key <= 0x87 {
+    } else if (0x70..=0x87).contains(&key) {
         // F1-F24
         parts.push(format!("F{}", key - 0x6F));
     }
@@ -210,6 +229,7 @@ fn system_time_to_string(time: SystemTime) -> String {
     datetime.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
+#[cfg(target_os = "windows")]
 pub fn resolve_shortcut(
     lnk_path: &Path,
 ) -> Result<(String, String, i32), Box<dyn std::error::Error>> {
@@ -234,12 +254,8 @@ pub fn resolve_shortcut(
         let mut icon_index = 0i32;
         shell_link.GetIconLocation(&mut icon_path, &mut icon_index)?;
 
-        let target = String::from_utf16_lossy(&target_path)
-            .trim_end_matches('\0')
-            .to_string();
-        let icon = String::from_utf16_lossy(&icon_path)
-            .trim_end_matches('\0')
-            .to_string();
+        let target = normalize_path(String::from_utf16_lossy(&target_path).trim_end_matches('\0'));
+        let icon = normalize_path(String::from_utf16_lossy(&icon_path).trim_end_matches('\0'));
 
         let final_icon_path = if icon.is_empty() {
             target.clone()
@@ -250,38 +266,3 @@ pub fn resolve_shortcut(
         Ok((target, final_icon_path, icon_index))
     }
 }
-
-/// 获取文件的本地化显示名称
-pub fn get_localized_name(file_path: &Path) -> Option<String> {
-    unsafe {
-        // 初始化 COM
-        let _com = ComInit::new(windows::Win32::System::Com::COINIT_MULTITHREADED);
-
-        let wide_path: Vec<u16> = file_path
-            .to_string_lossy()
-            .encode_utf16()
-            .chain(std::iter::once(0))
-            .collect();
-
-        let mut shfi = SHFILEINFOW::default();
-        let result = SHGetFileInfoW(
-            PCWSTR(wide_path.as_ptr()),
-            FILE_FLAGS_AND_ATTRIBUTES::default(),
-            Some(&mut shfi),
-            size_of::<SHFILEINFOW>() as u32,
-            SHGFI_DISPLAYNAME,
-        );
-
-        if result != 0 {
-            let display_name = String::from_utf16_lossy(&shfi.szDisplayName)
-                .trim_end_matches('\0')
-                .to_string();
-
-            if !display_name.is_empty() {
-                return Some(display_name);
-            }
-        }
-
-        None
-    }
-}
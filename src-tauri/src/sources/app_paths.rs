@@ -0,0 +1,216 @@
+// App Paths 软件来源（从注册表读取）
+//
+// 程序在 SOFTWARE\Microsoft\Windows\CurrentVersion\App Paths\<名称>.exe 下登记自身，
+// 默认值为程序完整路径，Path 值为启动时加在 PATH 前面的目录。
+// 运行对话框与 ShellExecute 按登记的名称查找程序，因此名称同时作为搜索别名。
+
+use crate::cancel::ScanToken;
+#[cfg(target_os = "windows")]
+use crate::path::LocalPaths;
+use crate::path::PathResolver;
+use crate::pe::read_image_summary;
+use crate::pe::version::read_version_info;
+#[cfg(target_os = "windows")]
+use crate::registry;
+use crate::registry::{Registry, RegistryRoot};
+use crate::shortcut::get_file_metadata;
+#[cfg(target_os = "windows")]
+use crate::sources::scanner::IconScanner;
+use crate::sources::{apply_image_summary, apply_version_info, version_display_name};
+use crate::types::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+#[cfg(target_os = "windows")]
+use std::error::Error;
+use std::path::Path;
+
+const SOURCE_NAME: &str = "App Paths";
+
+// 当前用户的登记优先于本机
+const APP_PATHS_KEYS: &[(RegistryRoot, &str)] = &[
+    (
+        RegistryRoot::CurrentUser,
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\App Paths",
+    ),
+    (
+        RegistryRoot::LocalMachine,
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\App Paths",
+    ),
+    (
+        RegistryRoot::LocalMachine,
+        "SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\App Paths",
+    ),
+];
+
+#[cfg(target_os = "windows")]
+pub struct AppPathsScanner;
+
+#[cfg(target_os = "windows")]
+impl IconScanner for AppPathsScanner {
+    fn id(&self) -> &str {
+        "app_paths"
+    }
+    fn name(&self) -> &str {
+        "App Paths"
+    }
+    fn description(&self) -> &str {
+        "注册表 App Paths 中登记的程序"
+    }
+    fn icon(&self) -> &str {
+        "🗂️"
+    }
+    fn default_priority(&self) -> i32 {
+        45
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_app_paths_icons(registry::system(), &LocalPaths, method, token)
+    }
+}
+
+/// 一个程序在 App Paths 中的登记（同一程序可能以多个名称登记）
+struct AppPathEntry {
+    exe_path: String,
+    path_dirs: Option<String>,
+    names: Vec<String>,
+}
+
+/// 读取 App Paths 中登记的程序，登记的路径经 paths 转换
+pub fn get_app_paths_icons(
+    registry: &dyn Registry,
+    paths: &dyn PathResolver,
+    _method: Option<&str>,
+    token: &ScanToken,
+) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
+    let scan_start = std::time::Instant::now();
+    let entries = read_app_paths(registry, paths);
+    println!(
+        "🔍 [扫描阶段] {} 扫描完成, 找到 {} 个程序, 耗时: {:.3}s",
+        SOURCE_NAME,
+        entries.len(),
+        scan_start.elapsed().as_secs_f64()
+    );
+
+    let prepare_start = std::time::Instant::now();
+    let results: Vec<DesktopIcon> = entries
+        .par_iter()
+        .filter(|_| !token.should_stop())
+        .filter_map(create_app_path_icon)
+        .collect();
+    println!(
+        "🧩 [准备阶段] {} 已准备 {} 个条目, 耗时: {:.3}s",
+        SOURCE_NAME,
+        results.len(),
+        prepare_start.elapsed().as_secs_f64()
+    );
+    Ok(results)
+}
+
+/// 按程序路径合并各位置的登记
+fn read_app_paths(registry: &dyn Registry, paths: &dyn PathResolver) -> Vec<AppPathEntry> {
+    let mut entries: Vec<AppPathEntry> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (root, key) in APP_PATHS_KEYS {
+        let Some(names) = registry.subkeys(*root, key) else {
+            continue;
+        };
+        for name in names {
+            let subkey = format!("{}\\{}", key, name);
+            let Some(exe_path) = registry
+                .string(*root, &subkey, "")
+                .map(|p| paths.resolve(p.trim().trim_matches('"')))
+            else {
+                continue;
+            };
+            let path_dirs = registry.string(*root, &subkey, "Path").map(|p| {
+                p.split(';')
+                    .map(|dir| paths.resolve(dir))
+                    .collect::<Vec<_>>()
+                    .join(";")
+            });
+
+            let key = exe_path.to_lowercase();
+            match index.get(&key) {
+                Some(&i) => {
+                    let entry = &mut entries[i];
+                    if !entry.names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                        entry.names.push(name);
+                    }
+                    entry.path_dirs = entry.path_dirs.take().or(path_dirs);
+                }
+                None => {
+                    index.insert(key, entries.len());
+                    entries.push(AppPathEntry {
+                        exe_path,
+                        path_dirs,
+                        names: vec![name],
+                    });
+                }
+            }
+        }
+    }
+    entries
+}
+
+fn create_app_path_icon(entry: &AppPathEntry) -> Option<DesktopIcon> {
+    let path = Path::new(&entry.exe_path);
+    // 卸载后残留的登记很常见，程序不存在时跳过
+    if !path.is_file() {
+        return None;
+    }
+
+    let summary = read_image_summary(path);
+    let version_info = read_version_info(path);
+    let display_name = version_info
+        .as_ref()
+        .and_then(version_display_name)
+        .or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string()))?;
+
+    // 登记名 chrome.exe 同时提供 chrome 作为别名
+    let mut aliases = Vec::new();
+    for name in &entry.names {
+        let stem = Path::new(name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        for alias in [name.as_str(), stem.as_str()] {
+            if !aliases
+                .iter()
+                .any(|a: &String| a.eq_ignore_ascii_case(alias))
+            {
+                aliases.push(alias.to_string());
+            }
+        }
+    }
+
+    let file_meta = get_file_metadata(path);
+    let mut icon = DesktopIcon {
+        name: display_name,
+        icon_base64: String::new(),
+        target_path: entry.exe_path.clone(),
+        file_path: entry.exe_path.clone(),
+        icon_width: 32,
+        icon_height: 32,
+        icon_source_path: Some(entry.exe_path.clone()),
+        icon_source_index: Some(0),
+        created_time: file_meta.created_time,
+        modified_time: file_meta.modified_time,
+        accessed_time: file_meta.accessed_time,
+        file_size: file_meta.file_size,
+        file_type: file_meta.file_type,
+        source_name: Some(SOURCE_NAME.to_string()),
+        aliases: Some(aliases),
+        app_path_dirs: entry.path_dirs.clone(),
+        ..Default::default()
+    };
+    if let Some(summary) = &summary {
+        apply_image_summary(&mut icon, summary);
+    }
+    if let Some(info) = &version_info {
+        apply_version_info(&mut icon, info);
+    }
+    Some(icon)
+}
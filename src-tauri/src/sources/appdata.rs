@@ -1,5 +1,12 @@
+use crate::cancel::ScanToken;
+use crate::config::{scan_settings, scanner_setting};
+use crate::pe::read_image_summary;
+use crate::pe::version::read_version_info;
+use crate::rules::filter_entries;
 use crate::shortcut::get_file_metadata;
-use crate::sources::scanner::IconScanner;
+use crate::sources::scanner::{IconScanner, ScanCost, SettingField, SettingKind};
+use crate::sources::squirrel::is_stale_app_dir;
+use crate::sources::{apply_image_summary, apply_version_info, version_display_name};
 use crate::types::*;
 use rayon::prelude::*;
 use std::env;
@@ -21,13 +28,55 @@ impl IconScanner for AppDataScanner {
     fn icon(&self) -> &str {
         "👤"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_appdata_icons(method)
+    fn cost(&self) -> ScanCost {
+        ScanCost::Moderate
+    }
+    fn default_priority(&self) -> i32 {
+        60
+    }
+    fn settings(&self) -> Vec<SettingField> {
+        vec![
+            SettingField {
+                key: "max_depth",
+                label: "Programs 扫描深度",
+                description: "%LOCALAPPDATA%\\Programs 向下遍历的目录层数",
+                kind: SettingKind::Depth {
+                    default: PROGRAMS_DEPTH,
+                    min: 1,
+                    max: 8,
+                },
+            },
+            SettingField {
+                key: "root_depth",
+                label: "根目录扫描深度",
+                description: "AppData\\Local 与 AppData\\Roaming 根目录向下遍历的目录层数",
+                kind: SettingKind::Depth {
+                    default: ROOT_DEPTH,
+                    min: 1,
+                    max: 4,
+                },
+            },
+        ]
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_appdata_icons(method, token)
     }
 }
 
-pub fn get_appdata_icons(method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+// 默认的目录遍历深度：Programs 目录与 Local、Roaming 根目录
+const PROGRAMS_DEPTH: usize = 4;
+const ROOT_DEPTH: usize = 2;
+
+pub fn get_appdata_icons(
+    method: Option<&str>,
+    token: &ScanToken,
+) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
     let mut all_icons = Vec::new();
+    let root_depth = scanner_setting("appdata_programs", "root_depth").unwrap_or(ROOT_DEPTH);
 
     // 1. %LOCALAPPDATA%\Programs
     if let Ok(local_appdata) = env::var("LOCALAPPDATA") {
@@ -40,16 +89,18 @@ pub fn get_appdata_icons(method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<d
                 &programs_path,
                 method,
                 "用户程序 (AppData/Local)",
+                token,
             )?);
         }
 
         // 扫描 Local 根目录（有些应用直接装在这里，比如 Telegram）
-        // 限制深度为 2，避免扫描太多
+        // 限制深度，避免扫描太多
         all_icons.extend(scan_appdata_folder_with_depth(
             &local_path,
             method,
-            2,
+            root_depth,
             "用户程序 (AppData/Local)",
+            token,
         )?);
     }
 
@@ -61,8 +112,9 @@ pub fn get_appdata_icons(method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<d
             all_icons.extend(scan_appdata_folder_with_depth(
                 &roaming_path,
                 method,
-                2,
+                root_depth,
                 "用户程序 (AppData/Roaming)",
+                token,
             )?);
         }
     }
@@ -74,8 +126,10 @@ fn scan_appdata_folder(
     folder_path: &Path,
     method: Option<&str>,
     source_name: &str,
+    token: &ScanToken,
 ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-    scan_appdata_folder_with_depth(folder_path, method, 4, source_name)
+    let depth = scanner_setting("appdata_programs", "max_depth").unwrap_or(PROGRAMS_DEPTH);
+    scan_appdata_folder_with_depth(folder_path, method, depth, source_name, token)
 }
 
 fn scan_appdata_folder_with_depth(
@@ -83,6 +137,7 @@ fn scan_appdata_folder_with_depth(
     _method: Option<&str>,
     depth: usize,
     source_name: &str,
+    token: &ScanToken,
 ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
     println!(
         "扫描 {} 文件夹: {:?} (深度: {})",
@@ -91,7 +146,16 @@ fn scan_appdata_folder_with_depth(
 
     let scan_start = std::time::Instant::now();
     let mut exe_files = Vec::new();
-    collect_exe_files(folder_path, &mut exe_files, depth)?;
+    collect_exe_files(folder_path, &mut exe_files, depth, token)?;
+    // Squirrel 应用更新后旧的 app-<版本> 目录会保留一段时间，只保留最新版本
+    let mut stale_dirs = std::collections::HashMap::<PathBuf, bool>::new();
+    exe_files.retain(|path| {
+        !path.ancestors().skip(1).any(|dir| {
+            *stale_dirs
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| is_stale_app_dir(dir))
+        })
+    });
     let scan_duration = scan_start.elapsed();
 
     println!(
@@ -103,16 +167,19 @@ fn scan_appdata_folder_with_depth(
 
     // 并行处理所有 EXE 文件
     let prepare_start = std::time::Instant::now();
+    let gui_only = scan_settings().gui_only;
     let results: Vec<_> = exe_files
         .par_iter()
-        .filter_map(|path| match process_exe_file(path, source_name) {
-            Ok(icon) => Some(icon),
+        .filter(|_| !token.should_stop())
+        .filter_map(|path| match process_exe_file(path, source_name, gui_only) {
+            Ok(icon) => icon,
             Err(e) => {
                 eprintln!("{} 处理失败 {:?}: {}", source_name, path, e);
                 None
             }
         })
         .collect();
+    let results = filter_entries(results, source_name);
     let prepare_duration = prepare_start.elapsed();
     println!(
         "🧩 [准备阶段] {} 扫描结束, 等待后续统一提取图标, 已准备 {} 个条目, 耗时: {:.3}s",
@@ -128,15 +195,16 @@ fn collect_exe_files(
     dir: &Path,
     files: &mut Vec<PathBuf>,
     max_depth: usize,
+    token: &ScanToken,
 ) -> Result<(), Box<dyn Error>> {
-    if max_depth == 0 {
+    if max_depth == 0 || token.should_stop() {
         return Ok(());
     }
     if let Ok(entries) = std::fs::read_dir(dir) {
         for entry in entries.filter_map(|e| e.ok()) {
             let path = entry.path();
             if path.is_dir() {
-                collect_exe_files(&path, files, max_depth - 1)?;
+                collect_exe_files(&path, files, max_depth - 1, token)?;
             } else if path.is_file() {
                 // 收集所有文件，统一由前端过滤
                 files.push(path);
@@ -146,13 +214,29 @@ fn collect_exe_files(
     Ok(())
 }
 
-fn process_exe_file(path: &Path, source_name: &str) -> Result<DesktopIcon, Box<dyn Error>> {
+/// 处理单个可执行文件，gui_only 时跳过非 GUI 程序
+fn process_exe_file(
+    path: &Path,
+    source_name: &str,
+    gui_only: bool,
+) -> Result<Option<DesktopIcon>, Box<dyn Error>> {
     let file_name = path.file_stem().unwrap().to_string_lossy().to_string();
+    // 控制台程序、16 位程序和 DLL 不是 GUI 程序
+    let summary = read_image_summary(path);
+    if gui_only && !summary.as_ref().is_some_and(|s| s.is_gui()) {
+        return Ok(None);
+    }
+    // 读取版本资源，用产品信息替代文件名作为显示名称
+    let version_info = read_version_info(path);
+    let display_name = version_info
+        .as_ref()
+        .and_then(version_display_name)
+        .unwrap_or(file_name);
     let file_path = path.to_string_lossy().to_string();
     let file_meta = get_file_metadata(path);
 
-    Ok(DesktopIcon {
-        name: file_name,
+    let mut icon = DesktopIcon {
+        name: display_name,
         icon_base64: String::new(),
         target_path: file_path.clone(),
         file_path: file_path.clone(),
@@ -171,5 +255,14 @@ fn process_exe_file(path: &Path, source_name: &str) -> Result<DesktopIcon, Box<d
         hotkey: None,
         show_command: None,
         source_name: Some(source_name.to_string()),
-    })
+        ..Default::default()
+    };
+    if let Some(summary) = &summary {
+        apply_image_summary(&mut icon, summary);
+    }
+    if let Some(info) = &version_info {
+        apply_version_info(&mut icon, info);
+    }
+
+    Ok(Some(icon))
 }
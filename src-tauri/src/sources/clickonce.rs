@@ -0,0 +1,200 @@
+// ClickOnce 应用（.appref-ms 部署引用）
+//
+// .appref-ms 是 UTF-16 文本，内容形如：
+//   https://example.com/App.application#App.application, Culture=neutral, PublicKeyToken=0123456789abcdef, processorArchitecture=msil
+// 启动时交给系统打开 .appref-ms，由 ClickOnce 负责检查更新与运行。
+
+use crate::registry::{self, RegistryRoot};
+use crate::shortcut::get_file_metadata;
+use crate::types::DesktopIcon;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+const UNINSTALL_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall";
+
+/// 解析后的部署引用
+#[derive(Debug, Clone, Default)]
+pub struct AppReference {
+    /// 部署清单地址
+    pub deployment_url: String,
+    /// 部署标识名称，如 "App.application"
+    pub identity_name: String,
+    pub public_key_token: Option<String>,
+    pub processor_architecture: Option<String>,
+    /// 原始内容，与卸载项中的 ShortcutAppId 一致
+    pub raw: String,
+}
+
+impl AppReference {
+    /// 应用名称（去掉 .application 后缀）
+    pub fn app_name(&self) -> &str {
+        let name = self.identity_name.as_str();
+        name.get(..name.len().saturating_sub(12))
+            .filter(|_| name.to_lowercase().ends_with(".application"))
+            .unwrap_or(name)
+    }
+}
+
+/// 是否为 ClickOnce 部署引用文件
+pub fn is_appref(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("appref-ms"))
+}
+
+/// 解析 .appref-ms 内容
+pub fn parse_appref(content: &str) -> Option<AppReference> {
+    let raw = content.trim_matches(|c: char| c == '\u{feff}' || c == '\0' || c.is_whitespace());
+    let (url, identity) = raw.split_once('#')?;
+    let mut parts = identity.split(',').map(str::trim);
+    let identity_name = parts.next()?.to_string();
+
+    let mut reference = AppReference {
+        deployment_url: url.trim().to_string(),
+        identity_name,
+        raw: raw.to_string(),
+        ..Default::default()
+    };
+    for part in parts {
+        let Some((key, value)) = part.split_once('=') else {
+            continue;
+        };
+        match key.trim().to_lowercase().as_str() {
+            "publickeytoken" => reference.public_key_token = Some(value.trim().to_lowercase()),
+            "processorarchitecture" => {
+                reference.processor_architecture = Some(value.trim().to_string())
+            }
+            _ => {}
+        }
+    }
+    Some(reference)
+}
+
+/// 读取 .appref-ms 文件（UTF-16 LE，带 BOM）
+pub fn read_appref(path: &Path) -> Option<AppReference> {
+    let data = std::fs::read(path).ok()?;
+    let content = if data.starts_with(&[0xFF, 0xFE]) || data.get(1) == Some(&0) {
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(&data).to_string()
+    };
+    parse_appref(&content)
+}
+
+/// 从 .appref-ms 创建条目
+pub fn create_appref_icon(path: &Path, source_name: &str) -> Result<DesktopIcon, Box<dyn Error>> {
+    let reference = read_appref(path).ok_or("无法解析 ClickOnce 部署引用")?;
+    let file_path = path.to_string_lossy().to_string();
+    let file_meta = get_file_metadata(path);
+    let registration = find_uninstall_entry(&reference.raw);
+
+    // 开始菜单中的文件名即为产品名称；发布者取卸载项中登记的
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| reference.app_name().to_string());
+    let publisher = registration.as_ref().and_then(|r| r.publisher.clone());
+
+    let (icon_path, icon_index) = registration
+        .as_ref()
+        .and_then(|r| r.display_icon.clone())
+        .or_else(|| {
+            find_cached_executable(&reference).map(|p| (p.to_string_lossy().to_string(), 0))
+        })
+        .unwrap_or_else(|| (file_path.clone(), 0));
+
+    Ok(DesktopIcon {
+        name,
+        icon_base64: String::new(),
+        target_path: file_path.clone(),
+        file_path,
+        icon_width: 32,
+        icon_height: 32,
+        icon_source_path: Some(icon_path),
+        icon_source_index: Some(icon_index),
+        created_time: file_meta.created_time,
+        modified_time: file_meta.modified_time,
+        accessed_time: file_meta.accessed_time,
+        file_size: file_meta.file_size,
+        file_type: Some("ClickOnce App".to_string()),
+        description: registration.and_then(|r| r.display_name),
+        source_name: Some(source_name.to_string()),
+        publisher,
+        deployment_url: Some(reference.deployment_url),
+        ..Default::default()
+    })
+}
+
+/// ClickOnce 在 HKCU 卸载项中登记的信息
+struct Registration {
+    display_name: Option<String>,
+    publisher: Option<String>,
+    display_icon: Option<(String, i32)>,
+}
+
+/// 按 ShortcutAppId 查找对应的卸载项
+fn find_uninstall_entry(app_id: &str) -> Option<Registration> {
+    let registry = registry::system();
+    let root = RegistryRoot::CurrentUser;
+    let subkey = registry
+        .subkeys(root, UNINSTALL_KEY)?
+        .into_iter()
+        .map(|name| format!("{}\\{}", UNINSTALL_KEY, name))
+        .find(|path| {
+            registry
+                .string(root, path, "ShortcutAppId")
+                .is_some_and(|id| id.trim().eq_ignore_ascii_case(app_id))
+        })?;
+
+    let read = |value: &str| registry.string(root, &subkey, value);
+    Some(Registration {
+        display_name: read("DisplayName"),
+        publisher: read("Publisher"),
+        display_icon: read("DisplayIcon").and_then(|icon| {
+            let (path, index) = match icon.rsplit_once(',') {
+                Some((p, i)) if i.trim().parse::<i32>().is_ok() => {
+                    (p.to_string(), i.trim().parse().unwrap_or(0))
+                }
+                _ => (icon, 0),
+            };
+            let path = path.trim().trim_matches('"').to_string();
+            // dfshim.dll 是 ClickOnce 的通用图标，不如缓存中的程序图标
+            (Path::new(&path).is_file() && !path.to_lowercase().ends_with("dfshim.dll"))
+                .then_some((path, index))
+        }),
+    })
+}
+
+/// 在本地 ClickOnce 缓存（%LOCALAPPDATA%\Apps\2.0）中查找应用程序
+///
+/// 缓存目录名形如 "myap..exe_0123456789abcdef_0001.0002_0a1b2c3d4e5f6a7b"，
+/// 由名称缩写、公钥标记、版本和哈希组成；同一应用的多个版本取最近修改的一个。
+fn find_cached_executable(reference: &AppReference) -> Option<PathBuf> {
+    let cache = dirs::data_local_dir()?.join("Apps").join("2.0");
+    let token = reference.public_key_token.as_deref()?;
+    let app_name = reference.app_name().to_lowercase();
+    let exe_name = format!("{}.exe", app_name);
+
+    walkdir::WalkDir::new(&cache)
+        .min_depth(3)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .filter(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .to_lowercase()
+                .contains(token)
+        })
+        .filter_map(|e| {
+            let exe = e.path().join(&exe_name);
+            let modified = std::fs::metadata(&exe).and_then(|m| m.modified()).ok()?;
+            Some((modified, exe))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, exe)| exe)
+}
@@ -1,11 +1,13 @@
 // 桌面软件来源
 
+use crate::cancel::ScanToken;
 use crate::path::*;
 use crate::shortcut::{
     get_file_metadata, get_shortcut_full_info, hotkey_to_string, resolve_shortcut,
     show_command_to_string,
 };
-use crate::sources::scanner::IconScanner;
+use crate::sources::clickonce::{create_appref_icon, is_appref};
+use crate::sources::scanner::{IconScanner, WatchTarget};
 use crate::types::*;
 use rayon::prelude::*;
 use std::error::Error;
@@ -26,8 +28,21 @@ impl IconScanner for DesktopScanner {
     fn icon(&self) -> &str {
         "🖥️"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_desktop_icons(method)
+    fn default_priority(&self) -> i32 {
+        75
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        get_desktop_path()
+            .into_iter()
+            .map(WatchTarget::Path)
+            .collect()
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_desktop_icons(method, token)
     }
 }
 
@@ -46,25 +61,40 @@ impl IconScanner for PublicDesktopScanner {
     fn icon(&self) -> &str {
         "💼"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_public_desktop_icons(method)
+    fn default_priority(&self) -> i32 {
+        70
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        get_public_desktop_path()
+            .into_iter()
+            .map(WatchTarget::Path)
+            .collect()
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_public_desktop_icons(method, token)
     }
 }
 
 /// 获取用户桌面图标
 pub fn get_desktop_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let desktop_path = get_desktop_path()?;
-    scan_folder(&desktop_path, method, "用户桌面")
+    scan_folder(&desktop_path, method, "用户桌面", token)
 }
 
 /// 获取公共桌面图标
 pub fn get_public_desktop_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let desktop_path = get_public_desktop_path()?;
-    scan_folder(&desktop_path, method, "公共桌面")
+    scan_folder(&desktop_path, method, "公共桌面", token)
 }
 
 /// 扫描文件夹获取图标
@@ -72,6 +102,7 @@ fn scan_folder(
     folder_path: &Path,
     _method: Option<&str>,
     source_name: &str,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     println!("扫描 {} 文件夹: {:?}", source_name, folder_path);
 
@@ -102,6 +133,7 @@ fn scan_folder(
         let extract_start = std::time::Instant::now();
         let results: Vec<_> = file_paths
             .par_iter()
+            .filter(|_| !token.should_stop())
             .filter_map(|path| match process_item(path, source_name) {
                 Ok(icon) => Some(icon),
                 Err(e) => {
@@ -136,6 +168,10 @@ fn process_item(
         .unwrap_or("Unknown")
         .to_string();
 
+    if is_appref(path) {
+        return create_appref_icon(path, source_name);
+    }
+
     let file_path = path.to_string_lossy().to_string();
     let file_meta = get_file_metadata(path);
     let is_lnk = path.extension().and_then(|s| s.to_str()) == Some("lnk");
@@ -168,6 +204,7 @@ fn process_item(
                     hotkey: None,
                     show_command: None,
                     source_name: Some(source_name.to_string()),
+                    ..Default::default()
                 });
             }
         };
@@ -203,7 +240,9 @@ fn process_item(
             },
             hotkey: hotkey_to_string(shortcut_info.hotkey),
             show_command: Some(show_command_to_string(shortcut_info.show_command)),
+            app_user_model_id: shortcut_info.app_user_model_id,
             source_name: Some(source_name.to_string()),
+            ..Default::default()
         });
     }
 
@@ -227,5 +266,6 @@ fn process_item(
         hotkey: None,
         show_command: None,
         source_name: Some(source_name.to_string()),
+        ..Default::default()
     })
 }
@@ -0,0 +1,280 @@
+// 自定义目录来源 - 扫描用户在设置中添加的目录（如 D:\Tools、~/opt）
+//
+// 每个来源有根目录、遍历深度、包含与排除通配符、文件类型，扫描器 ID 为 folder:<id>。
+// 程序读取版本信息，快捷方式由 lnk 模块解析，.url 读取 [InternetShortcut]，
+// .desktop 读取 [Desktop Entry]（只在能启动它的 Linux 上收集），.app 包（macOS）读取 Info.plist，
+// 脚本按文件本身登记。
+
+use crate::cancel::ScanToken;
+use crate::config::{scan_settings, FolderSource};
+use crate::desktop_entry::DesktopEntry;
+use crate::localized::{decode_ini, ini_value};
+use crate::path::{normalize_path, LocalPaths, PathResolver};
+use crate::rules::{filter_entries, pattern_matches};
+use crate::shortcut::get_file_metadata;
+use crate::sources::offline::process_item;
+use crate::sources::program_files::process_exe_file;
+use crate::sources::scanner::{IconScanner, ScanCost, WatchTarget};
+use crate::types::*;
+use rayon::prelude::*;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+// 可收集的文件类型
+#[cfg(target_os = "linux")]
+const FILE_TYPES: &[&str] = &["exe", "lnk", "url", "desktop", "app", "script"];
+#[cfg(not(target_os = "linux"))]
+const FILE_TYPES: &[&str] = &["exe", "lnk", "url", "app", "script"];
+
+// 文件类型 script 包含的扩展名
+const SCRIPT_EXTENSIONS: &[&str] = &["bat", "cmd", "ps1", "vbs", "sh", "py", "command"];
+
+pub struct FolderScanner {
+    id: String,
+    name: String,
+    source: FolderSource,
+}
+
+/// 设置中的全部目录来源
+pub fn get_folder_scanners() -> Vec<Box<dyn IconScanner>> {
+    scan_settings()
+        .folder_sources
+        .into_iter()
+        .map(|source| {
+            // 没有标识的来源（前端保存前的旧设置）按根目录标识，顺序变化时保持不变
+            let id = match source.id.trim() {
+                "" => format!("folder:{}", source.root.trim()),
+                id => format!("folder:{}", id),
+            };
+            let name = match source.name.trim() {
+                "" => source.root.trim().to_string(),
+                name => name.to_string(),
+            };
+            Box::new(FolderScanner { id, name, source }) as Box<dyn IconScanner>
+        })
+        .collect()
+}
+
+impl IconScanner for FolderScanner {
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn description(&self) -> &str {
+        &self.source.root
+    }
+    fn icon(&self) -> &str {
+        "🗃️"
+    }
+    fn available(&self) -> bool {
+        resolve_root(&self.source.root).is_some_and(|root| root.is_dir())
+    }
+    fn cost(&self) -> ScanCost {
+        ScanCost::Moderate
+    }
+    fn default_priority(&self) -> i32 {
+        55
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        resolve_root(&self.source.root)
+            .into_iter()
+            .map(WatchTarget::Path)
+            .collect()
+    }
+    fn scan(
+        &self,
+        _method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let Some(root) = resolve_root(&self.source.root).filter(|r| r.is_dir()) else {
+            println!("{} 路径不存在", self.name);
+            return Ok(Vec::new());
+        };
+        println!(
+            "扫描 {} 文件夹: {:?} (深度: {})",
+            self.name, root, self.source.max_depth
+        );
+
+        let scan_start = std::time::Instant::now();
+        let files = self.collect_files(&root, token);
+        println!(
+            "🔍 [扫描阶段] {} 扫描完成, 找到 {} 个文件, 耗时: {:.3}s",
+            self.name,
+            files.len(),
+            scan_start.elapsed().as_secs_f64()
+        );
+
+        let prepare_start = std::time::Instant::now();
+        let results: Vec<DesktopIcon> = files
+            .par_iter()
+            .filter(|_| !token.should_stop())
+            .filter_map(
+                |(path, file_type)| match process_file(path, file_type, &self.name) {
+                    Ok(icon) => icon,
+                    Err(e) => {
+                        eprintln!("{} 处理失败 {:?}: {}", self.name, path, e);
+                        None
+                    }
+                },
+            )
+            .collect();
+        let results = filter_entries(results, &self.name);
+        println!(
+            "🧩 [准备阶段] {} 已准备 {} 个条目, 耗时: {:.3}s",
+            self.name,
+            results.len(),
+            prepare_start.elapsed().as_secs_f64()
+        );
+        Ok(results)
+    }
+}
+
+impl FolderScanner {
+    /// 收集匹配的文件及其类型，.app 包作为一个条目且不再进入
+    fn collect_files(&self, root: &Path, token: &ScanToken) -> Vec<(PathBuf, &'static str)> {
+        let source = &self.source;
+        let matches = |patterns: &[String], name: &str| {
+            patterns
+                .iter()
+                .any(|p| !p.trim().is_empty() && pattern_matches(p, name))
+        };
+
+        let mut files = Vec::new();
+        let mut walker = WalkDir::new(root)
+            .min_depth(1)
+            .max_depth(source.max_depth.max(1))
+            .into_iter();
+        while let Some(entry) = walker.next() {
+            if token.should_stop() {
+                break;
+            }
+            let Ok(entry) = entry else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().is_dir();
+            if matches(&source.exclude, &name) {
+                if is_dir {
+                    walker.skip_current_dir();
+                }
+                continue;
+            }
+
+            let Some(file_type) = file_type(entry.path()) else {
+                continue;
+            };
+            if is_dir {
+                if file_type != "app" {
+                    continue;
+                }
+                walker.skip_current_dir();
+            }
+            let wanted = source.file_types.is_empty()
+                || source
+                    .file_types
+                    .iter()
+                    .any(|t| t.eq_ignore_ascii_case(file_type));
+            if wanted && (source.include.is_empty() || matches(&source.include, &name)) {
+                files.push((entry.into_path(), file_type));
+            }
+        }
+        files
+    }
+}
+
+/// 根目录：~ 开头的按主目录展开，再展开已知文件夹与环境变量
+fn resolve_root(root: &str) -> Option<PathBuf> {
+    let root = root.trim();
+    if root.is_empty() {
+        return None;
+    }
+    if let Some(rest) = root.strip_prefix('~') {
+        let rest = rest.trim_start_matches(['/', '\\']);
+        return Some(dirs::home_dir()?.join(normalize_path(rest)));
+    }
+    Some(PathBuf::from(normalize_path(root)))
+}
+
+/// 按扩展名判断文件类型
+fn file_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    if SCRIPT_EXTENSIONS.contains(&extension.as_str()) {
+        return Some("script");
+    }
+    FILE_TYPES.iter().find(|t| **t == extension).copied()
+}
+
+/// 按类型处理单个文件
+fn process_file(
+    path: &Path,
+    file_type: &str,
+    source_name: &str,
+) -> Result<Option<DesktopIcon>, Box<dyn Error>> {
+    match file_type {
+        "exe" => process_exe_file(path, source_name, false),
+        "url" => Ok(Some(process_url_file(path, source_name)?)),
+        "desktop" => Ok(process_desktop_file(path, source_name)),
+        #[cfg(target_os = "macos")]
+        "app" => Ok(
+            crate::sources::macos::build_desktop_icon_from_app(path, None).map(|mut icon| {
+                icon.source_name = Some(source_name.to_string());
+                icon
+            }),
+        ),
+        #[cfg(not(target_os = "macos"))]
+        "app" => Ok(None),
+        _ => process_item(path, &LocalPaths, source_name).map(Some),
+    }
+}
+
+/// Internet 快捷方式：目标为 URL，图标取 IconFile 与 IconIndex
+fn process_url_file(path: &Path, source_name: &str) -> Result<DesktopIcon, Box<dyn Error>> {
+    let mut icon = process_item(path, &LocalPaths, source_name)?;
+    let text = decode_ini(&std::fs::read(path)?);
+    if let Some(url) = ini_value(&text, "InternetShortcut", "URL") {
+        icon.target_path = url;
+    }
+    if let Some(icon_file) = ini_value(&text, "InternetShortcut", "IconFile") {
+        icon.icon_source_path = Some(LocalPaths.resolve(&icon_file));
+        icon.icon_source_index = ini_value(&text, "InternetShortcut", "IconIndex")
+            .and_then(|i| i.parse().ok())
+            .or(Some(0));
+    }
+    Ok(icon)
+}
+
+/// .desktop 文件：名称、命令与说明取自 [Desktop Entry]，图标只识别绝对路径
+fn process_desktop_file(path: &Path, source_name: &str) -> Option<DesktopIcon> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let entry = DesktopEntry::parse(&text)?;
+    let file_path = path.to_string_lossy().to_string();
+    let file_meta = get_file_metadata(path);
+    let icon_source_path = entry
+        .icon
+        .filter(|icon| Path::new(icon).is_absolute() && Path::new(icon).is_file());
+    Some(DesktopIcon {
+        name: entry.name,
+        icon_base64: String::new(),
+        target_path: file_path.clone(),
+        file_path,
+        icon_width: 32,
+        icon_height: 32,
+        icon_source_index: icon_source_path.as_ref().map(|_| 0),
+        icon_source_path,
+        created_time: file_meta.created_time,
+        modified_time: file_meta.modified_time,
+        accessed_time: file_meta.accessed_time,
+        file_size: file_meta.file_size,
+        file_type: file_meta.file_type,
+        description: entry.comment,
+        arguments: Some(entry.exec),
+        working_directory: entry.path,
+        source_name: Some(source_name.to_string()),
+        run_in_terminal: entry.terminal,
+        aliases: (!entry.keywords.is_empty()).then_some(entry.keywords),
+        ..Default::default()
+    })
+}
@@ -1,17 +1,30 @@
 // 已安装程序软件来源（从注册表读取）
 
+use crate::cancel::ScanToken;
+use crate::config::scanner_setting;
+#[cfg(target_os = "windows")]
+use crate::path::LocalPaths;
+use crate::path::PathResolver;
+#[cfg(target_os = "windows")]
+use crate::registry;
+#[cfg(target_os = "windows")]
+use crate::registry::uninstall::UNINSTALL_KEYS;
+use crate::registry::uninstall::{read_uninstall_entries, visible_entries, UninstallEntry};
+use crate::registry::Registry;
 use crate::shortcut::get_file_metadata;
-use crate::sources::scanner::IconScanner;
+#[cfg(target_os = "windows")]
+use crate::sources::scanner::{IconScanner, WatchTarget};
+use crate::sources::scanner::{SettingField, SettingKind};
 use crate::types::*;
 use rayon::prelude::*;
+#[cfg(target_os = "windows")]
 use std::error::Error;
 use std::path::Path;
-use windows::core::PCWSTR;
-use windows::Win32::Foundation::ERROR_SUCCESS;
-use windows::Win32::System::Registry::*;
 
+#[cfg(target_os = "windows")]
 pub struct InstalledProgramsScanner;
 
+#[cfg(target_os = "windows")]
 impl IconScanner for InstalledProgramsScanner {
     fn id(&self) -> &str {
         "installed_programs"
@@ -25,39 +38,63 @@ impl IconScanner for InstalledProgramsScanner {
     fn icon(&self) -> &str {
         "📦"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_installed_programs_icons(method)
+    fn default_priority(&self) -> i32 {
+        50
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        UNINSTALL_KEYS
+            .iter()
+            .map(|(root, key)| WatchTarget::Registry(*root, key.to_string()))
+            .collect()
+    }
+    fn settings(&self) -> Vec<SettingField> {
+        installed_programs_settings()
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_installed_programs_icons(method, token)
     }
 }
 
 /// 获取已安装程序图标
+#[cfg(target_os = "windows")]
 pub fn get_installed_programs_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
-    let scan_start = std::time::Instant::now();
-
-    let mut programs = Vec::new();
-
-    // 读取 64 位程序
-    read_uninstall_keys(
-        HKEY_LOCAL_MACHINE,
-        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
-        &mut programs,
-    )?;
+    get_installed_programs_from(registry::system(), &LocalPaths, method, token)
+}
 
-    // 读取 32 位程序（在 64 位系统上）
-    read_uninstall_keys(
-        HKEY_LOCAL_MACHINE,
-        "SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
-        &mut programs,
-    )?;
+// 是否保留 visible_entries 隐藏的条目
+const SHOW_HIDDEN: &str = "show_hidden";
+
+/// 已安装程序扫描器的设置项
+pub(crate) fn installed_programs_settings() -> Vec<SettingField> {
+    vec![SettingField {
+        key: SHOW_HIDDEN,
+        label: "显示隐藏条目",
+        description: "保留系统组件、更新补丁与被安装包捆绑的 MSI 条目",
+        kind: SettingKind::Bool { default: false },
+    }]
+}
 
-    // 读取当前用户安装的程序
-    read_uninstall_keys(
-        HKEY_CURRENT_USER,
-        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Uninstall",
-        &mut programs,
-    )?;
+/// 从指定的注册表（本机或离线文件）读取已安装程序，登记的路径经 paths 转换
+pub fn get_installed_programs_from(
+    registry: &dyn Registry,
+    paths: &dyn PathResolver,
+    method: Option<&str>,
+    token: &ScanToken,
+) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
+    let scan_start = std::time::Instant::now();
+    let entries = read_uninstall_entries(registry);
+    let programs = if scanner_setting("installed_programs", SHOW_HIDDEN).unwrap_or(false) {
+        entries
+    } else {
+        visible_entries(registry, entries)
+    };
 
     let scan_duration = scan_start.elapsed();
     println!(
@@ -70,15 +107,16 @@ pub fn get_installed_programs_icons(
     let prepare_start = std::time::Instant::now();
     let results: Vec<_> = programs
         .par_iter()
-        .filter_map(|(name, icon_path, exe_path)| {
-            match create_icon_from_program(name, icon_path, exe_path, method) {
+        .filter(|_| !token.should_stop())
+        .filter_map(
+            |entry| match create_icon_from_program(entry, paths, method) {
                 Ok(icon) => Some(icon),
                 Err(e) => {
-                    eprintln!("处理程序 {} 失败: {}", name, e);
+                    eprintln!("处理程序 {} 失败: {}", entry.display_name, e);
                     None
                 }
-            }
-        })
+            },
+        )
         .collect();
     let prepare_duration = prepare_start.elapsed();
     println!(
@@ -89,147 +127,27 @@ pub fn get_installed_programs_icons(
     Ok(results)
 }
 
-/// 读取注册表卸载项
-fn read_uninstall_keys(
-    hkey: HKEY,
-    subkey: &str,
-    programs: &mut Vec<(String, String, String)>,
-) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    unsafe {
-        let subkey_wide: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
-        let mut key_handle = HKEY::default();
-
-        if RegOpenKeyExW(
-            hkey,
-            PCWSTR(subkey_wide.as_ptr()),
-            Some(0),
-            KEY_READ,
-            &mut key_handle,
-        ) == ERROR_SUCCESS
-        {
-            let mut index = 0;
-            println!("成功打开注册表键: {:?}", subkey);
-            loop {
-                let mut name_buffer = [0u16; 256];
-                let mut name_len = name_buffer.len() as u32;
-
-                let result = RegEnumKeyExW(
-                    key_handle,
-                    index,
-                    Some(windows::core::PWSTR(name_buffer.as_mut_ptr())),
-                    &mut name_len,
-                    None,
-                    None,
-                    None,
-                    None,
-                );
-
-                if result != ERROR_SUCCESS {
-                    if result != windows::Win32::Foundation::WIN32_ERROR(259) {
-                        // ERROR_NO_MORE_ITEMS
-                        println!("枚举注册表子键 {} 失败: {:?}", subkey, result);
-                    }
-                    break;
-                }
-
-                let name = String::from_utf16_lossy(&name_buffer[..name_len as usize]);
-                let full_subkey = format!("{}\\{}", subkey, name);
-
-                if let Ok((display_name, icon_path, exe_path)) =
-                    read_program_info(hkey, &full_subkey)
-                {
-                    if !display_name.is_empty() {
-                        programs.push((display_name, icon_path, exe_path));
-                    }
-                }
-
-                index += 1;
-            }
-            let _ = RegCloseKey(key_handle);
-        } else {
-            println!("无法打开注册表键: {:?}", subkey);
-        }
-    }
-
-    Ok(())
-}
-
-/// 读取程序信息
-fn read_program_info(
-    hkey: HKEY,
-    subkey: &str,
-) -> std::result::Result<(String, String, String), Box<dyn std::error::Error>> {
-    unsafe {
-        let subkey_wide: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
-        let mut key_handle = HKEY::default();
-
-        if RegOpenKeyExW(
-            hkey,
-            PCWSTR(subkey_wide.as_ptr()),
-            Some(0),
-            KEY_READ,
-            &mut key_handle,
-        ) != ERROR_SUCCESS
-        {
-            return Err("无法打开注册表键".into());
-        }
-
-        let display_name = read_registry_string(key_handle, "DisplayName").unwrap_or_default();
-        let icon_path = read_registry_string(key_handle, "DisplayIcon").unwrap_or_default();
-        let exe_path = read_registry_string(key_handle, "InstallLocation")
-            .or_else(|| read_registry_string(key_handle, "UninstallString"))
-            .unwrap_or_default();
-
-        let _ = RegCloseKey(key_handle);
-
-        Ok((display_name, icon_path, exe_path))
-    }
-}
-
-/// 读取注册表字符串值
-fn read_registry_string(key: HKEY, value_name: &str) -> Option<String> {
-    unsafe {
-        let value_wide: Vec<u16> = value_name
-            .encode_utf16()
-            .chain(std::iter::once(0))
-            .collect();
-        let mut buffer = [0u16; 512];
-        let mut buffer_size = (buffer.len() * 2) as u32;
-        let mut value_type = REG_NONE;
-
-        if RegQueryValueExW(
-            key,
-            PCWSTR(value_wide.as_ptr()),
-            None,
-            Some(&mut value_type),
-            Some(buffer.as_mut_ptr() as *mut u8),
-            Some(&mut buffer_size),
-        ) == ERROR_SUCCESS
-            && value_type == REG_SZ
-        {
-            let len = buffer_size as usize / 2;
-            let result = String::from_utf16_lossy(&buffer[..len.saturating_sub(1)]);
-            if !result.is_empty() {
-                return Some(result);
-            }
-        }
-    }
-    None
-}
-
 /// 从程序信息创建图标
 fn create_icon_from_program(
-    name: &str,
-    icon_path: &str,
-    exe_path: &str,
+    entry: &UninstallEntry,
+    paths: &dyn PathResolver,
     _method: Option<&str>,
 ) -> std::result::Result<DesktopIcon, Box<dyn std::error::Error>> {
+    let name = entry.display_name.as_str();
+    // 没有 DisplayIcon 时用安装目录或卸载命令推测程序路径
+    let icon_path = entry.display_icon.as_deref().unwrap_or_default();
+    let exe_path = entry
+        .install_location
+        .as_deref()
+        .or(entry.uninstall_string.as_deref())
+        .unwrap_or_default();
+
     // 解析图标路径和索引
-    let (mut actual_icon_path, icon_index) = parse_icon_path(icon_path, exe_path);
+    let (mut actual_icon_path, icon_index) = parse_icon_path(icon_path, exe_path, paths);
 
     if actual_icon_path.is_empty() && !name.is_empty() {
         // 如果没有图标路径，但有程序名，我们也保留它
-        actual_icon_path = exe_path.to_string();
+        actual_icon_path = paths.resolve(exe_path);
     }
 
     if name.is_empty() {
@@ -259,22 +177,30 @@ fn create_icon_from_program(
         hotkey: None,
         show_command: None,
         source_name: Some("已安装程序".to_string()),
+        publisher: entry.publisher.clone(),
+        display_version: entry.display_version.clone(),
+        install_date: entry.install_date.clone(),
+        estimated_size: entry.estimated_size,
+        url_info_about: entry.url_info_about.clone(),
+        uninstall_string: entry.uninstall_string.clone(),
+        quiet_uninstall_string: entry.quiet_uninstall_string.clone(),
+        ..Default::default()
     })
 }
 
 /// 解析图标路径（可能包含索引，如 "path.exe,0"）
-fn parse_icon_path(icon_path: &str, exe_path: &str) -> (String, i32) {
+fn parse_icon_path(icon_path: &str, exe_path: &str, paths: &dyn PathResolver) -> (String, i32) {
     if !icon_path.is_empty() {
         // DisplayIcon 可能的格式: "C:\path\file.exe", "C:\path\file.exe,0", "C:\path\icon.ico"
         if let Some(comma_pos) = icon_path.rfind(',') {
             let path_part = icon_path[..comma_pos].trim().trim_matches('"');
             let index_part = icon_path[comma_pos + 1..].trim();
             if let Ok(index) = index_part.parse::<i32>() {
-                return (path_part.to_string(), index);
+                return (paths.resolve(path_part), index);
             }
         }
         // 没有索引，直接返回路径
-        let cleaned_path = icon_path.trim().trim_matches('"').to_string();
+        let cleaned_path = paths.resolve(icon_path.trim().trim_matches('"'));
         if Path::new(&cleaned_path).exists() {
             return (cleaned_path, 0);
         }
@@ -285,12 +211,12 @@ fn parse_icon_path(icon_path: &str, exe_path: &str) -> (String, i32) {
         let cleaned_exe = exe_path.trim().trim_matches('"').to_string();
         // 从 UninstallString 中提取 exe 路径
         if cleaned_exe.to_lowercase().ends_with(".exe") {
-            return (cleaned_exe, 0);
+            return (paths.resolve(&cleaned_exe), 0);
         }
         // 可能包含参数，提取第一个 .exe
         if let Some(exe_pos) = cleaned_exe.to_lowercase().find(".exe") {
             let exe_part = &cleaned_exe[..exe_pos + 4];
-            return (exe_part.trim_matches('"').to_string(), 0);
+            return (paths.resolve(exe_part.trim_matches('"')), 0);
         }
     }
 
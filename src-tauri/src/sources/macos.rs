@@ -1,6 +1,7 @@
 // macOS 应用扫描与图标提取
 
-use crate::sources::scanner::IconScanner;
+use crate::cancel::{run_command, ScanToken};
+use crate::sources::scanner::{IconScanner, ScanCost, WatchTarget};
 use crate::types::{DesktopIcon, IconData};
 use base64::prelude::*;
 use icns::{IconFamily, IconType};
@@ -16,7 +17,7 @@ use walkdir::WalkDir;
 use objc2::runtime::AnyObject;
 use objc2::{msg_send, ClassType};
 use objc2_app_kit::{NSBitmapImageRep, NSImage, NSWorkspace};
-use objc2_foundation::{NSData, NSString, NSPoint, NSRect, NSSize};
+use objc2_foundation::{NSData, NSPoint, NSRect, NSSize, NSString};
 
 use std::sync::{Mutex, OnceLock};
 
@@ -172,8 +173,18 @@ impl IconScanner for ApplicationsScanner {
     fn icon(&self) -> &str {
         "🍎"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        let mut icons = get_applications_icons(method)?;
+    fn default_priority(&self) -> i32 {
+        90
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        vec![WatchTarget::Path(PathBuf::from("/Applications"))]
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let mut icons = get_applications_icons(method, token)?;
         for icon in &mut icons {
             icon.source_name = Some("系统应用程序".to_string());
         }
@@ -196,8 +207,18 @@ impl IconScanner for SystemApplicationsScanner {
     fn icon(&self) -> &str {
         "⚙️"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        let mut icons = get_system_applications_icons(method)?;
+    fn default_priority(&self) -> i32 {
+        80
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        vec![WatchTarget::Path(PathBuf::from("/System/Applications"))]
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let mut icons = get_system_applications_icons(method, token)?;
         for icon in &mut icons {
             icon.source_name = Some("核心应用程序".to_string());
         }
@@ -220,8 +241,21 @@ impl IconScanner for UserApplicationsScanner {
     fn icon(&self) -> &str {
         "👤"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        let mut icons = get_user_applications_icons(method)?;
+    fn default_priority(&self) -> i32 {
+        85
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        dirs::home_dir()
+            .map(|home| WatchTarget::Path(home.join("Applications")))
+            .into_iter()
+            .collect()
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let mut icons = get_user_applications_icons(method, token)?;
         for icon in &mut icons {
             icon.source_name = Some("用户应用程序".to_string());
         }
@@ -244,8 +278,15 @@ impl IconScanner for SpotlightScanner {
     fn icon(&self) -> &str {
         "🔍"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        let mut icons = get_spotlight_applications_icons(method)?;
+    fn cost(&self) -> ScanCost {
+        ScanCost::Moderate
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let mut icons = get_spotlight_applications_icons(method, token)?;
         for icon in &mut icons {
             icon.source_name = Some("Spotlight 搜索".to_string());
         }
@@ -268,8 +309,15 @@ impl IconScanner for SystemProfilerScanner {
     fn icon(&self) -> &str {
         "📋"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        let mut icons = get_system_profiler_icons(method)?;
+    fn cost(&self) -> ScanCost {
+        ScanCost::Slow
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let mut icons = get_system_profiler_icons(method, token)?;
         for icon in &mut icons {
             icon.source_name = Some("系统信息报告".to_string());
         }
@@ -292,8 +340,12 @@ impl IconScanner for CoreServicesScanner {
     fn icon(&self) -> &str {
         "🛠️"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        let mut icons = get_core_services_icons(method)?;
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let mut icons = get_core_services_icons(method, token)?;
         for icon in &mut icons {
             icon.source_name = Some("系统核心服务".to_string());
         }
@@ -304,45 +356,51 @@ impl IconScanner for CoreServicesScanner {
 /// 从系统 /Applications 扫描应用
 pub fn get_applications_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let dir = PathBuf::from("/Applications");
-    scan_applications_dir(&dir, method)
+    scan_applications_dir(&dir, method, token)
 }
 
 /// 从核心系统 /System/Applications 扫描应用
 pub fn get_system_applications_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let dir = PathBuf::from("/System/Applications");
-    scan_applications_dir(&dir, method)
+    scan_applications_dir(&dir, method, token)
 }
 
 /// 从用户 ~/Applications 扫描应用
 pub fn get_user_applications_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let mut dir = dirs::home_dir().ok_or("无法获取用户主目录")?;
     dir.push("Applications");
-    scan_applications_dir(&dir, method)
+    scan_applications_dir(&dir, method, token)
 }
 
 /// 从系统核心服务目录扫描
 pub fn get_core_services_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let dir = PathBuf::from("/System/Library/CoreServices");
-    scan_applications_dir(&dir, method)
+    scan_applications_dir(&dir, method, token)
 }
 
 /// 使用 system_profiler 获取所有应用
 pub fn get_system_profiler_icons(
     _method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     use std::process::Command;
     // 使用 xml 格式解析会更准，但这里先用简单文本解析路径
-    let output = Command::new("system_profiler")
-        .args(["SPApplicationsDataType", "-detailLevel", "mini"])
-        .output()?;
+    let output = run_command(
+        Command::new("system_profiler").args(["SPApplicationsDataType", "-detailLevel", "mini"]),
+        token,
+    )?;
 
     if !output.status.success() {
         return Err("system_profiler 执行失败".into());
@@ -352,7 +410,7 @@ pub fn get_system_profiler_icons(
     let mut results = Vec::new();
     let mut seen = std::collections::HashSet::new();
 
-    for line in stdout.lines() {
+    for line in stdout.lines().take_while(|_| !token.should_stop()) {
         let line = line.trim();
         if line.starts_with("Location:") {
             let path_str = line.replace("Location:", "").trim().to_string();
@@ -374,11 +432,13 @@ pub fn get_system_profiler_icons(
 /// 使用 mdfind (Spotlight) 扫描所有应用
 pub fn get_spotlight_applications_icons(
     _method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     use std::process::Command;
-    let output = Command::new("mdfind")
-        .arg("kMDItemContentType == 'com.apple.application-bundle'")
-        .output()?;
+    let output = run_command(
+        Command::new("mdfind").arg("kMDItemContentType == 'com.apple.application-bundle'"),
+        token,
+    )?;
 
     if !output.status.success() {
         return Err("mdfind 命令执行失败".into());
@@ -391,7 +451,7 @@ pub fn get_spotlight_applications_icons(
     // 排除已知的标准路径，避免重复扫描（虽然上层可能有去重，但这里可以先做一层）
     let skip_prefixes = ["/Applications/", "/System/Applications/"];
 
-    for line in stdout.lines() {
+    for line in stdout.lines().take_while(|_| !token.should_stop()) {
         let path_str = line.trim();
         if path_str.is_empty() || !path_str.ends_with(".app") {
             continue;
@@ -428,6 +488,7 @@ pub fn get_spotlight_applications_icons(
 fn scan_applications_dir(
     dir: &Path,
     _method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     if !dir.exists() {
         return Ok(Vec::new());
@@ -437,6 +498,7 @@ fn scan_applications_dir(
     let paths: Vec<PathBuf> = WalkDir::new(dir)
         .max_depth(3)
         .into_iter()
+        .take_while(|_| !token.should_stop())
         .filter_map(|e| e.ok())
         .map(|e| e.path().to_path_buf())
         .filter(|path| {
@@ -447,6 +509,7 @@ fn scan_applications_dir(
     // 并行处理每个 .app 路径提取图标
     let results: Vec<DesktopIcon> = paths
         .into_par_iter()
+        .filter(|_| !token.should_stop())
         .filter_map(|path| build_desktop_icon_from_app(&path, _method))
         .collect();
 
@@ -499,11 +562,22 @@ pub(crate) fn extract_icon_for_app(app_path: &Path, method: Option<&str>) -> Opt
     Some(icon_data)
 }
 
-fn build_desktop_icon_from_app(app_path: &Path, _method: Option<&str>) -> Option<DesktopIcon> {
+pub(crate) fn build_desktop_icon_from_app(
+    app_path: &Path,
+    _method: Option<&str>,
+) -> Option<DesktopIcon> {
     let plist_path = app_path.join("Contents").join("Info.plist");
 
     // 1. 获取名称
     let name = read_app_name(&plist_path, app_path)?;
+    let bundle_id = PlistValue::from_file(&plist_path)
+        .ok()
+        .and_then(|v| v.into_dictionary())
+        .and_then(|dict| {
+            dict.get("CFBundleIdentifier")
+                .and_then(|v| v.as_string())
+                .map(str::to_string)
+        });
 
     let icon_source_path = app_path.to_string_lossy().to_string();
     let metadata = fs::metadata(app_path).ok();
@@ -543,6 +617,8 @@ fn build_desktop_icon_from_app(app_path: &Path, _method: Option<&str>) -> Option
         hotkey: None,
         show_command: None,
         source_name: None,
+        bundle_id,
+        ..Default::default()
     })
 }
 
@@ -0,0 +1,171 @@
+// 合并引擎 - 各命令共用的去重与合并
+//
+// 同一应用（见 identity）只保留一个条目：优先级高的来源胜出，优先级相同时有图标的胜出；
+// 其余重复条目补充胜出者缺失的字段（说明、参数、快捷键等），并记录在 source_names 中。
+// 应用商店条目与同名的普通条目合并，保留普通条目。
+// 来源优先级取自各扫描器的默认优先级，可在设置中按来源 ID 覆盖。
+
+use crate::config::scan_settings;
+use crate::identity::{canonical_target, identity_keys, normalize_name, IdentityIndex};
+use crate::sources::get_all_scanners;
+use crate::types::DesktopIcon;
+use std::collections::HashMap;
+
+pub struct MergeEngine {
+    entries: Vec<(DesktopIcon, i32)>,
+    index: IdentityIndex,
+    /// 各来源的优先级（扫描器默认值叠加设置中的覆盖）
+    priorities: HashMap<String, i32>,
+    source_names: HashMap<String, String>,
+}
+
+impl MergeEngine {
+    /// 按当前设置中的来源优先级创建
+    pub fn from_settings() -> Self {
+        let scanners = get_all_scanners();
+        let mut priorities: HashMap<String, i32> = scanners
+            .iter()
+            .map(|s| (s.id().to_string(), s.default_priority()))
+            .collect();
+        priorities.extend(scan_settings().source_priorities);
+        MergeEngine {
+            entries: Vec::new(),
+            index: IdentityIndex::default(),
+            priorities,
+            source_names: scanners
+                .iter()
+                .map(|s| (s.id().to_string(), s.name().to_string()))
+                .collect(),
+        }
+    }
+
+    /// 来源的优先级，未登记的来源为 50
+    fn priority(&self, source_id: &str) -> i32 {
+        self.priorities.get(source_id).copied().unwrap_or(50)
+    }
+
+    /// 加入一个来源的条目
+    pub fn add(&mut self, source_id: &str, icons: Vec<DesktopIcon>) {
+        let priority = self.priority(source_id);
+        let fallback_name = self
+            .source_names
+            .get(source_id)
+            .cloned()
+            .unwrap_or_else(|| source_id.to_string());
+        for mut icon in icons {
+            if icon.source_names.is_empty() {
+                let name = icon
+                    .source_name
+                    .clone()
+                    .unwrap_or_else(|| fallback_name.clone());
+                icon.source_names.push(name);
+            }
+            let keys = identity_keys(&icon);
+            match self.index.find(&keys) {
+                Some(slot) => {
+                    self.index.insert(keys, slot);
+                    merge_entry(&mut self.entries[slot], icon, priority);
+                }
+                None => {
+                    self.index.insert(keys, self.entries.len());
+                    self.entries.push((icon, priority));
+                }
+            }
+        }
+    }
+
+    /// 合并同名的应用商店条目，按名称排序后返回
+    pub fn finish(self) -> Vec<DesktopIcon> {
+        let is_uwp = |icon: &DesktopIcon| icon.file_type.as_deref() == Some("UWP App");
+        let (uwp, mut icons): (Vec<DesktopIcon>, Vec<DesktopIcon>) = self
+            .entries
+            .into_iter()
+            .map(|(icon, _)| icon)
+            .partition(|icon| is_uwp(icon));
+
+        let by_name: HashMap<String, usize> = icons
+            .iter()
+            .enumerate()
+            .map(|(i, icon)| (normalize_name(&icon.name), i))
+            .collect();
+        for icon in uwp {
+            match by_name.get(&normalize_name(&icon.name)) {
+                Some(&i) => fill_missing(&mut icons[i], &icon),
+                None => icons.push(icon),
+            }
+        }
+
+        icons.sort_by(|a, b| a.name.cmp(&b.name));
+        icons
+    }
+}
+
+/// 合并重复条目：优先级更高，或优先级相同但新条目有图标时替换，另一方补充缺失字段
+fn merge_entry(existing: &mut (DesktopIcon, i32), icon: DesktopIcon, priority: i32) {
+    let (existing_icon, existing_priority) = existing;
+    let replace = priority > *existing_priority
+        || (priority == *existing_priority
+            && existing_icon.icon_base64.is_empty()
+            && !icon.icon_base64.is_empty());
+    if replace {
+        let other = std::mem::replace(existing_icon, icon);
+        fill_missing(existing_icon, &other);
+        *existing_priority = priority;
+    } else {
+        fill_missing(existing_icon, &icon);
+    }
+}
+
+/// 用重复条目补充缺失的字段，合并搜索别名与来源列表
+fn fill_missing(icon: &mut DesktopIcon, other: &DesktopIcon) {
+    fn fill<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+        if field.is_none() {
+            field.clone_from(other);
+        }
+    }
+
+    if icon.icon_base64.is_empty() && !other.icon_base64.is_empty() {
+        icon.icon_base64.clone_from(&other.icon_base64);
+        icon.icon_width = other.icon_width;
+        icon.icon_height = other.icon_height;
+        icon.icon_source_path.clone_from(&other.icon_source_path);
+        icon.icon_source_index = other.icon_source_index;
+    }
+    fill(&mut icon.icon_source_path, &other.icon_source_path);
+    fill(&mut icon.description, &other.description);
+    // 参数与工作目录只对同一个目标程序有意义（名称相同的条目可能指向不同程序）
+    if canonical_target(&icon.target_path) == canonical_target(&other.target_path) {
+        fill(&mut icon.arguments, &other.arguments);
+        fill(&mut icon.working_directory, &other.working_directory);
+    }
+    fill(&mut icon.hotkey, &other.hotkey);
+    fill(&mut icon.show_command, &other.show_command);
+    fill(&mut icon.publisher, &other.publisher);
+    fill(&mut icon.signer, &other.signer);
+    fill(&mut icon.display_version, &other.display_version);
+    fill(&mut icon.install_date, &other.install_date);
+    fill(&mut icon.estimated_size, &other.estimated_size);
+    fill(&mut icon.url_info_about, &other.url_info_about);
+    fill(&mut icon.uninstall_string, &other.uninstall_string);
+    fill(
+        &mut icon.quiet_uninstall_string,
+        &other.quiet_uninstall_string,
+    );
+    fill(&mut icon.app_path_dirs, &other.app_path_dirs);
+    fill(&mut icon.app_user_model_id, &other.app_user_model_id);
+    fill(&mut icon.bundle_id, &other.bundle_id);
+
+    if let Some(other_aliases) = &other.aliases {
+        let aliases = icon.aliases.get_or_insert_with(Vec::new);
+        for alias in other_aliases {
+            if !aliases.iter().any(|a| a.eq_ignore_ascii_case(alias)) {
+                aliases.push(alias.clone());
+            }
+        }
+    }
+    for name in &other.source_names {
+        if !icon.source_names.contains(name) {
+            icon.source_names.push(name.clone());
+        }
+    }
+}
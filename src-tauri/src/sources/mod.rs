@@ -2,35 +2,102 @@
 
 pub mod scanner;
 
-// Windows 专用来源
+// Windows 来源（注册表与目录扫描部分也用于离线分区）
+pub mod app_paths;
 #[cfg(target_os = "windows")]
 pub mod appdata;
 #[cfg(target_os = "windows")]
-pub mod desktop;
+pub mod clickonce;
 #[cfg(target_os = "windows")]
+pub mod desktop;
 pub mod installed_programs;
-#[cfg(target_os = "windows")]
+pub mod merge;
+pub mod offline;
 pub mod program_files;
 #[cfg(target_os = "windows")]
 pub mod quick_launch;
+pub mod squirrel;
 #[cfg(target_os = "windows")]
 pub mod start_menu;
 #[cfg(target_os = "windows")]
 pub mod taskbar;
 #[cfg(target_os = "windows")]
 pub mod uwp;
+#[cfg(target_os = "windows")]
+pub mod wsl;
+
+// 用户添加的目录来源
+pub mod folder;
 
 // macOS 专用来源
 #[cfg(target_os = "macos")]
 pub mod macos;
 
+// Linux 专用来源
+#[cfg(target_os = "linux")]
+pub mod wine;
+#[cfg(target_os = "linux")]
+pub mod xdg;
+
+use crate::cancel::{run_guarded, run_guarded_all, Guarded, ScanToken};
+use crate::catalog::build_catalog;
+use crate::config::scan_settings;
+use crate::offline::offline_root;
 use crate::path::normalize_path;
-use crate::sources::scanner::IconScanner;
+use crate::sources::scanner::{IconScanner, ScanCost, ScanStatus};
 use crate::types::{DesktopIcon, IconData};
 use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// 各扫描器的可用性；WSL、Wine 等扫描器判断可用性时需要查找发行版或前缀，
+// 结果保留到设置变化或下一次扫描开始
+static AVAILABILITY: Mutex<Option<HashMap<String, bool>>> = Mutex::new(None);
+
+/// 在独立线程中提取一个图标源，超过图标提取超时或扫描取消后不再等待
+fn guard_extract<T: Send + 'static>(
+    job: impl FnOnce() -> T + Send + 'static,
+    timeout: Option<Duration>,
+    token: &ScanToken,
+    source: &str,
+) -> Guarded<T> {
+    guard_file_job(job, timeout, token, "图标提取", source)
+}
 
+/// 在独立线程中处理一个文件，超时或扫描取消后不再等待；stage 用于日志
+fn guard_file_job<T: Send + 'static>(
+    job: impl FnOnce() -> T + Send + 'static,
+    timeout: Option<Duration>,
+    token: &ScanToken,
+    stage: &str,
+    source: &str,
+) -> Guarded<T> {
+    if token.is_cancelled() {
+        return Guarded::Cancelled;
+    }
+    // 不限制时间时直接在当前线程提取
+    if timeout.is_none() {
+        return Guarded::Done(job());
+    }
+    let outcome = run_guarded(job, timeout, token);
+    match &outcome {
+        Guarded::TimedOut => eprintln!(
+            "!!! {}超时, 跳过 {}: {:?}",
+            stage,
+            source,
+            timeout.unwrap_or_default()
+        ),
+        Guarded::Panicked => eprintln!("!!! {}异常退出, 跳过 {}", stage, source),
+        _ => {}
+    }
+    outcome
+}
+
+/// 提取没有图标的条目的图标，同一图标源只提取一次，每个图标源受图标提取超时限制；
+/// 返回因超时没有图标的条目数
 #[cfg(target_os = "windows")]
-pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>) {
+pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>, token: &ScanToken) -> usize {
     use crate::extractor::{extract_icon_as_base64, extract_icon_with_method};
     use crate::extractors::shell_icon::{extract_icon_via_shell, extract_thumbnail_icon};
     use rayon::prelude::*;
@@ -125,50 +192,63 @@ pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>) {
     );
 
     let plans: Vec<(String, ExtractPlan)> = plans.into_iter().collect();
-    let extracted: Vec<(String, IconData)> = plans
+    let timeout = scan_settings().extract_timeout();
+    let extracted: Vec<(String, Option<IconData>)> = plans
         .par_iter()
-        .map(|(key, plan)| {
-            let icon_data = if plan.kind == 1 {
-                let shell_path = plan.path.as_str();
-                let mut data = extract_thumbnail_icon(shell_path, 256).unwrap_or(IconData {
-                    base64: String::new(),
-                    width: 32,
-                    height: 32,
-                });
-                if data.base64.is_empty() || data.width < 48 {
-                    if let Ok(better) = extract_icon_via_shell(shell_path, 256) {
-                        if !better.base64.is_empty() {
-                            data = better;
-                        }
-                    }
-                }
-                data
-            } else {
-                let icon_index = plan.icon_index;
-                let source_path = plan.path.as_str();
-                if let Some(m) = method {
-                    extract_icon_with_method(source_path, icon_index, m).unwrap_or(IconData {
+        .filter_map(|(key, plan)| {
+            let job_plan = plan.clone();
+            let job_method = method.map(str::to_string);
+            let job = move || {
+                let plan = job_plan;
+                let method = job_method.as_deref();
+                if plan.kind == 1 {
+                    let shell_path = plan.path.as_str();
+                    let mut data = extract_thumbnail_icon(shell_path, 256).unwrap_or(IconData {
                         base64: String::new(),
                         width: 32,
                         height: 32,
-                    })
+                    });
+                    if data.base64.is_empty() || data.width < 48 {
+                        if let Ok(better) = extract_icon_via_shell(shell_path, 256) {
+                            if !better.base64.is_empty() {
+                                data = better;
+                            }
+                        }
+                    }
+                    data
                 } else {
-                    extract_icon_as_base64(source_path, icon_index).unwrap_or(IconData {
-                        base64: String::new(),
-                        width: 32,
-                        height: 32,
-                    })
+                    let icon_index = plan.icon_index;
+                    let source_path = plan.path.as_str();
+                    if let Some(m) = method {
+                        extract_icon_with_method(source_path, icon_index, m).unwrap_or(IconData {
+                            base64: String::new(),
+                            width: 32,
+                            height: 32,
+                        })
+                    } else {
+                        extract_icon_as_base64(source_path, icon_index).unwrap_or(IconData {
+                            base64: String::new(),
+                            width: 32,
+                            height: 32,
+                        })
+                    }
                 }
             };
-            (key.clone(), icon_data)
+            match guard_extract(job, timeout, token, &plan.path) {
+                Guarded::Done(icon_data) => Some((key.clone(), Some(icon_data))),
+                Guarded::TimedOut => Some((key.clone(), None)),
+                Guarded::Cancelled | Guarded::Panicked => None,
+            }
         })
         .collect();
 
-    let mut extracted_map = std::collections::HashMap::<String, IconData>::new();
+    // 值为 None 表示该图标源提取超时
+    let mut extracted_map = std::collections::HashMap::<String, Option<IconData>>::new();
     extracted_map.reserve(extracted.len());
     for (key, data) in extracted {
         extracted_map.insert(key, data);
     }
+    let mut timed_out = 0usize;
 
     for icon in icons.iter_mut() {
         if !icon.icon_base64.is_empty() {
@@ -184,10 +264,14 @@ pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>) {
             let key_path = normalize_key_path(source_path_raw);
             format!("file{KEY_SEP}{icon_index}{KEY_SEP}{key_path}")
         };
-        if let Some(icon_data) = extracted_map.get(&key) {
-            icon.icon_base64 = icon_data.base64.clone();
-            icon.icon_width = icon_data.width;
-            icon.icon_height = icon_data.height;
+        match extracted_map.get(&key) {
+            Some(Some(icon_data)) => {
+                icon.icon_base64 = icon_data.base64.clone();
+                icon.icon_width = icon_data.width;
+                icon.icon_height = icon_data.height;
+            }
+            Some(None) => timed_out += 1,
+            None => {}
         }
     }
 
@@ -203,16 +287,22 @@ pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>) {
         filled,
         duration.as_secs_f64()
     );
+    timed_out
 }
 
 #[cfg(target_os = "macos")]
-pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>) {
+pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>, token: &ScanToken) -> usize {
     use crate::sources::macos::extract_icon_for_app;
     use rayon::prelude::*;
     use std::path::Path;
 
     const KEY_SEP: char = '\u{1f}';
 
+    // 离线分区中是 Windows 程序，没有 .app 包
+    if offline_root().is_some() {
+        return fill_pe_icons(icons, token);
+    }
+
     let start = std::time::Instant::now();
     let total = icons.len();
     let already_has_icon = icons.iter().filter(|i| !i.icon_base64.is_empty()).count();
@@ -260,43 +350,39 @@ pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>) {
     );
 
     let keys: Vec<String> = unique_keys.into_iter().collect();
-    let extracted: Vec<(String, IconData)> = if method == Some("icns") {
-        keys.par_iter()
-            .map(|key| {
-                let mut parts = key.split(KEY_SEP);
-                let _kind = parts.next().unwrap_or_default();
-                let app_path = parts.next().unwrap_or_default();
-                let icon_data =
-                    extract_icon_for_app(Path::new(app_path), method).unwrap_or(IconData {
-                        base64: String::new(),
-                        width: 32,
-                        height: 32,
-                    });
-                (key.clone(), icon_data)
+    let timeout = scan_settings().extract_timeout();
+    let extract = |key: &String| {
+        let mut parts = key.split(KEY_SEP);
+        let _kind = parts.next().unwrap_or_default();
+        let app_path = parts.next().unwrap_or_default().to_string();
+        let job_path = app_path.clone();
+        let job_method = method.map(str::to_string);
+        let job = move || {
+            extract_icon_for_app(Path::new(&job_path), job_method.as_deref()).unwrap_or(IconData {
+                base64: String::new(),
+                width: 32,
+                height: 32,
             })
-            .collect()
+        };
+        match guard_extract(job, timeout, token, &app_path) {
+            Guarded::Done(icon_data) => Some((key.clone(), Some(icon_data))),
+            Guarded::TimedOut => Some((key.clone(), None)),
+            Guarded::Cancelled | Guarded::Panicked => None,
+        }
+    };
+    let extracted: Vec<(String, Option<IconData>)> = if method == Some("icns") {
+        keys.par_iter().filter_map(extract).collect()
     } else {
-        keys.iter()
-            .map(|key| {
-                let mut parts = key.split(KEY_SEP);
-                let _kind = parts.next().unwrap_or_default();
-                let app_path = parts.next().unwrap_or_default();
-                let icon_data =
-                    extract_icon_for_app(Path::new(app_path), method).unwrap_or(IconData {
-                        base64: String::new(),
-                        width: 32,
-                        height: 32,
-                    });
-                (key.clone(), icon_data)
-            })
-            .collect()
+        keys.iter().filter_map(extract).collect()
     };
 
-    let mut extracted_map = std::collections::HashMap::<String, IconData>::new();
+    // 值为 None 表示该图标源提取超时
+    let mut extracted_map = std::collections::HashMap::<String, Option<IconData>>::new();
     extracted_map.reserve(extracted.len());
     for (key, data) in extracted {
         extracted_map.insert(key, data);
     }
+    let mut timed_out = 0usize;
 
     for icon in icons.iter_mut() {
         if !icon.icon_base64.is_empty() {
@@ -304,10 +390,14 @@ pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>) {
         }
         let app_path = icon.icon_source_path.as_deref().unwrap_or(&icon.file_path);
         let key = format!("app{KEY_SEP}{app_path}");
-        if let Some(icon_data) = extracted_map.get(&key) {
-            icon.icon_base64 = icon_data.base64.clone();
-            icon.icon_width = icon_data.width;
-            icon.icon_height = icon_data.height;
+        match extracted_map.get(&key) {
+            Some(Some(icon_data)) => {
+                icon.icon_base64 = icon_data.base64.clone();
+                icon.icon_width = icon_data.width;
+                icon.icon_height = icon_data.height;
+            }
+            Some(None) => timed_out += 1,
+            None => {}
         }
     }
 
@@ -323,13 +413,254 @@ pub fn fill_icons(icons: &mut [DesktopIcon], method: Option<&str>) {
         filled,
         duration.as_secs_f64()
     );
+    timed_out
+}
+
+/// 将版本资源信息写入条目
+pub fn apply_version_info(icon: &mut DesktopIcon, info: &crate::pe::version::VersionInfo) {
+    let lang = crate::pe::ui_language();
+    icon.file_description = info.file_description(lang);
+    icon.product_name = info.product_name(lang);
+    icon.company_name = info.company_name(lang);
+    icon.file_version = info.file_version(lang);
+    icon.product_version = info.product_version(lang);
+    icon.version_strings = Some(info.all_tables());
+}
+
+/// 裸 exe 条目的显示名称：优先 FileDescription，其次 ProductName
+///
+/// 套件中的各个程序共用同一个 ProductName（如 Office 的 WINWORD.EXE/EXCEL.EXE），
+/// 用 FileDescription 才能区分开。
+pub fn version_display_name(info: &crate::pe::version::VersionInfo) -> Option<String> {
+    let lang = crate::pe::ui_language();
+    info.file_description(lang)
+        .or_else(|| info.product_name(lang))
+}
+
+/// 将 PE 头部的架构与子系统写入条目
+pub fn apply_image_summary(icon: &mut DesktopIcon, summary: &crate::pe::ImageSummary) {
+    icon.architecture = summary.architecture.map(str::to_string);
+    icon.subsystem = Some(summary.subsystem.to_string());
+    icon.run_in_terminal = summary.subsystem == "console";
+}
+
+/// 将应用程序清单信息写入条目
+pub fn apply_manifest_info(icon: &mut DesktopIcon, info: &crate::pe::manifest::ManifestInfo) {
+    icon.execution_level = info.execution_level.clone();
+    icon.requires_elevation = info.requires_elevation();
+    icon.dpi_awareness = info.dpi_awareness.clone();
+    icon.supported_os = (!info.supported_os.is_empty()).then(|| info.supported_os.clone());
+}
+
+/// 将 Authenticode 签名信息写入条目
+pub fn apply_signature_info(icon: &mut DesktopIcon, info: &crate::pe::signature::SignatureInfo) {
+    icon.signature_status = Some(info.status.as_str().to_string());
+    icon.signer = info.signer.clone();
+    icon.signer_subject = info.subject.clone();
+    icon.signer_issuer = info.issuer.clone();
+}
+
+/// 为目标是可执行文件的条目补充头部、版本资源、清单与签名信息（同一目标只解析一次），
+/// 每个目标受图标提取超时限制，扫描取消或超时后跳过其余目标；
+/// 每处理完一批目标以（已处理, 总数）调用 on_progress，返回读取超时的目标数
+pub fn fill_executable_info(
+    icons: &mut [DesktopIcon],
+    token: &ScanToken,
+    mut on_progress: impl FnMut(usize, usize),
+) -> usize {
+    use crate::pe::manifest::{read_manifest_info, ManifestInfo};
+    use crate::pe::signature::{read_signature_info, SignatureInfo};
+    use crate::pe::version::{read_version_info, VersionInfo};
+    use crate::pe::{read_image_summary, ImageSummary};
+
+    // 每批目标并行读取，批次之间报告进度
+    const INFO_BATCH: usize = 64;
+
+    let start = std::time::Instant::now();
+    let exe_path = |icon: &DesktopIcon| -> Option<String> {
+        if icon.signature_status.is_some() || icon.file_type.as_deref() == Some("UWP App") {
+            return None;
+        }
+        let target = normalize_path(icon.target_path.trim().trim_matches('"'));
+        target.to_lowercase().ends_with(".exe").then_some(target)
+    };
+    let targets: Vec<String> = icons
+        .iter()
+        .filter_map(exe_path)
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+    type ExecutableInfo = (
+        Option<ImageSummary>,
+        Option<VersionInfo>,
+        Option<ManifestInfo>,
+        Option<SignatureInfo>,
+    );
+    let timeout = scan_settings().extract_timeout();
+    // 值为 None 表示该目标读取超时
+    let mut infos: std::collections::HashMap<String, Option<ExecutableInfo>> =
+        std::collections::HashMap::new();
+    on_progress(0, targets.len());
+    for (index, batch) in targets.chunks(INFO_BATCH).enumerate() {
+        if token.should_stop() {
+            break;
+        }
+        infos.par_extend(batch.par_iter().filter_map(|target| {
+            if token.should_stop() {
+                return None;
+            }
+            let job_target = target.clone();
+            let job = move || {
+                let path = std::path::Path::new(&job_target);
+                (
+                    read_image_summary(path),
+                    read_version_info(path),
+                    read_manifest_info(path),
+                    read_signature_info(path),
+                )
+            };
+            match guard_file_job(job, timeout, token, "读取可执行信息", target) {
+                Guarded::Done(info) => Some((target.clone(), Some(info))),
+                Guarded::TimedOut => Some((target.clone(), None)),
+                Guarded::Cancelled | Guarded::Panicked => None,
+            }
+        }));
+        on_progress(index * INFO_BATCH + batch.len(), targets.len());
+    }
+    let timed_out = infos.values().filter(|info| info.is_none()).count();
+
+    let mut filled = 0usize;
+    for icon in icons.iter_mut() {
+        let Some(Some((summary, version, manifest, signature))) =
+            exe_path(icon).and_then(|t| infos.get(&t))
+        else {
+            continue;
+        };
+        // 扫描阶段可能已经读取过头部与版本资源
+        if let (None, Some(summary)) = (&icon.subsystem, summary) {
+            apply_image_summary(icon, summary);
+        }
+        if let (None, Some(version)) = (&icon.version_strings, version) {
+            apply_version_info(icon, version);
+        }
+        if let Some(manifest) = manifest {
+            apply_manifest_info(icon, manifest);
+        }
+        if let Some(signature) = signature {
+            apply_signature_info(icon, signature);
+        }
+        filled += 1;
+    }
+
+    println!(
+        "📄 [版本信息] 补充头部、版本资源、清单与签名完成, 条目: {}, 超时目标: {}, 耗时: {:.3}s",
+        filled,
+        timed_out,
+        start.elapsed().as_secs_f64()
+    );
+    timed_out
 }
 
 #[cfg(not(any(target_os = "windows", target_os = "macos")))]
-pub fn fill_icons(_icons: &mut [DesktopIcon], _method: Option<&str>) {}
+pub fn fill_icons(icons: &mut [DesktopIcon], _method: Option<&str>, token: &ScanToken) -> usize {
+    fill_pe_icons(icons, token)
+}
+
+/// 离线分区的图标：直接从 PE 资源或 .ico 文件读取（同一图标源只读取一次）；
+/// 返回因超时没有图标的条目数
+#[cfg(not(target_os = "windows"))]
+fn fill_pe_icons(icons: &mut [DesktopIcon], token: &ScanToken) -> usize {
+    use crate::pe::icon::read_icon;
+
+    let start = std::time::Instant::now();
+    let source = |icon: &DesktopIcon| -> (String, i32) {
+        let path = icon.icon_source_path.as_deref().unwrap_or(&icon.file_path);
+        (path.to_string(), icon.icon_source_index.unwrap_or(0))
+    };
+    let sources: std::collections::HashSet<(String, i32)> = icons
+        .iter()
+        .filter(|i| i.icon_base64.is_empty())
+        .map(source)
+        .collect();
+    let unique_count = sources.len();
+    let timeout = scan_settings().extract_timeout();
+    // 值为 None 表示该图标源提取超时
+    let extracted: std::collections::HashMap<(String, i32), Option<IconData>> = sources
+        .into_par_iter()
+        .filter_map(|(path, index)| {
+            let job_path = path.clone();
+            let job = move || read_icon(std::path::Path::new(&job_path), index);
+            match guard_extract(job, timeout, token, &path) {
+                Guarded::Done(data) => Some(((path, index), Some(data?))),
+                Guarded::TimedOut => Some(((path, index), None)),
+                Guarded::Cancelled | Guarded::Panicked => None,
+            }
+        })
+        .collect();
+
+    let mut filled = 0usize;
+    let mut timed_out = 0usize;
+    for icon in icons.iter_mut().filter(|i| i.icon_base64.is_empty()) {
+        match extracted.get(&source(icon)) {
+            Some(Some(data)) => {
+                icon.icon_base64 = data.base64.clone();
+                icon.icon_width = data.width;
+                icon.icon_height = data.height;
+                filled += 1;
+            }
+            Some(None) => timed_out += 1,
+            None => {}
+        }
+    }
+
+    println!(
+        "🖼️ [提取阶段] 离线图标提取完成, 唯一图标源: {}, 本次新增: {}, 耗时: {:.3}s",
+        unique_count,
+        filled,
+        start.elapsed().as_secs_f64()
+    );
+    timed_out
+}
 
 /// 获取所有可用的扫描器
 pub fn get_all_scanners() -> Vec<Box<dyn IconScanner>> {
+    // 设置了离线 Windows 根目录时扫描该分区而不是本机，用户添加的目录来源始终保留
+    let mut scanners = match offline_root() {
+        Some(root) => offline::get_offline_scanners(&root).unwrap_or_else(|e| {
+            eprintln!("!!! 打开离线 Windows 分区 {} 失败: {}", root, e);
+            Vec::new()
+        }),
+        None => local_scanners(),
+    };
+    scanners.extend(folder::get_folder_scanners());
+    let mut availability = AVAILABILITY
+        .lock()
+        .ok()
+        .and_then(|cache| cache.clone())
+        .unwrap_or_default();
+    let known = availability.len();
+    scanners.retain(|s| {
+        *availability
+            .entry(s.id().to_string())
+            .or_insert_with(|| s.available())
+    });
+    if availability.len() != known {
+        if let Ok(mut cache) = AVAILABILITY.lock() {
+            *cache = Some(availability);
+        }
+    }
+    scanners
+}
+
+/// 清除扫描器可用性的缓存，设置变化或开始扫描时重新判断
+pub fn reset_scanner_availability() {
+    if let Ok(mut cache) = AVAILABILITY.lock() {
+        *cache = None;
+    }
+}
+
+/// 本机的内置扫描器
+fn local_scanners() -> Vec<Box<dyn IconScanner>> {
     let mut scanners: Vec<Box<dyn IconScanner>> = Vec::new();
     #[cfg(target_os = "windows")]
     {
@@ -342,8 +673,10 @@ pub fn get_all_scanners() -> Vec<Box<dyn IconScanner>> {
         scanners.push(Box::new(appdata::AppDataScanner));
         scanners.push(Box::new(quick_launch::QuickLaunchScanner));
         scanners.push(Box::new(installed_programs::InstalledProgramsScanner));
+        scanners.push(Box::new(app_paths::AppPathsScanner));
         scanners.push(Box::new(program_files::ProgramFilesScanner));
         scanners.push(Box::new(program_files::ProgramFilesX86Scanner));
+        scanners.push(Box::new(wsl::WslScanner));
     }
     #[cfg(target_os = "macos")]
     {
@@ -354,180 +687,126 @@ pub fn get_all_scanners() -> Vec<Box<dyn IconScanner>> {
         scanners.push(Box::new(macos::SpotlightScanner));
         scanners.push(Box::new(macos::SystemProfilerScanner));
     }
-    scanners
-}
-
-/// 软件来源枚举
-#[derive(Debug, Clone, PartialEq)]
-pub enum IconSource {
-    Desktop,
-    PublicDesktop,
-    StartMenu,
-    CommonStartMenu,
-    InstalledPrograms,
-    ProgramFiles,
-    ProgramFilesX86,
-    QuickLaunch,
-    TaskbarPinned,
-    AppDataPrograms,
-    UWPApps,
-    // macOS
-    Applications,
-    SystemApplications,
-    UserApplications,
-    CoreServices,
-    Spotlight,
-    SystemProfiler,
-}
-
-impl IconSource {
-    pub fn from_str(s: &str) -> Option<Self> {
-        match s {
-            "desktop" => Some(Self::Desktop),
-            "public_desktop" => Some(Self::PublicDesktop),
-            "start_menu" => Some(Self::StartMenu),
-            "common_start_menu" => Some(Self::CommonStartMenu),
-            "installed_programs" => Some(Self::InstalledPrograms),
-            "program_files" => Some(Self::ProgramFiles),
-            "program_files_x86" => Some(Self::ProgramFilesX86),
-            "quick_launch" => Some(Self::QuickLaunch),
-            "taskbar_pinned" => Some(Self::TaskbarPinned),
-            "appdata_programs" => Some(Self::AppDataPrograms),
-            "uwp_apps" => Some(Self::UWPApps),
-            // macOS
-            "applications" => Some(Self::Applications),
-            "system_applications" => Some(Self::SystemApplications),
-            "user_applications" => Some(Self::UserApplications),
-            "core_services" => Some(Self::CoreServices),
-            "spotlight" => Some(Self::Spotlight),
-            "system_profiler" => Some(Self::SystemProfiler),
-            _ => None,
-        }
+    #[cfg(target_os = "linux")]
+    {
+        scanners.push(Box::new(xdg::XdgScanner));
+        scanners.push(Box::new(wine::WineScanner));
     }
+    scanners
 }
 
-/// 获取指定来源的图标
-pub fn get_icons_from_source(
-    source: IconSource,
+/// 并行执行扫描器，按扫描器顺序返回各来源的条目，失败或超时的来源返回空列表
+pub fn run_scanners(
+    scanners: Vec<Box<dyn IconScanner>>,
     method: Option<&str>,
-) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
-    let scanners = get_all_scanners();
-    let source_id = match source {
-        IconSource::Desktop => "desktop",
-        IconSource::PublicDesktop => "public_desktop",
-        IconSource::StartMenu => "start_menu",
-        IconSource::CommonStartMenu => "common_start_menu",
-        IconSource::InstalledPrograms => "installed_programs",
-        IconSource::ProgramFiles => "program_files",
-        IconSource::ProgramFilesX86 => "program_files_x86",
-        IconSource::QuickLaunch => "quick_launch",
-        IconSource::TaskbarPinned => "taskbar_pinned",
-        IconSource::AppDataPrograms => "appdata_programs",
-        IconSource::UWPApps => "uwp_apps",
-        IconSource::Applications => "applications",
-        IconSource::SystemApplications => "system_applications",
-        IconSource::UserApplications => "user_applications",
-        IconSource::CoreServices => "core_services",
-        IconSource::Spotlight => "spotlight",
-        IconSource::SystemProfiler => "system_profiler",
-    };
-
-    if let Some(scanner) = scanners.iter().find(|s| s.id() == source_id) {
-        scanner.scan(method)
-    } else {
-        Ok(vec![])
-    }
+) -> Vec<(String, Vec<DesktopIcon>)> {
+    run_scanners_with(scanners, method, &ScanToken::uncancellable(), |_, _, _| {})
+        .into_iter()
+        .map(|(id, icons, _)| (id, icons))
+        .collect()
 }
 
-/// 获取所有来源的图标（并行去重）
-pub fn get_all_icons(
+/// 各扫描器在独立线程中执行，收到受扫描器超时限制的句柄，超时或扫描取消后尽快返回；
+/// 每个来源结束时调用 on_scanned
+pub fn run_scanners_with(
+    scanners: Vec<Box<dyn IconScanner>>,
     method: Option<&str>,
-) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
-    let start_all = std::time::Instant::now();
-    let scanners = get_all_scanners();
-
-    // 并行执行所有扫描器
-    let mut icon_map: std::collections::HashMap<String, (DesktopIcon, i32)> =
-        std::collections::HashMap::new();
-
-    // 定义来源优先级函数
-    let get_priority = |source_id: &str| -> i32 {
-        match source_id {
-            "uwp_apps" => 100,
-            "taskbar_pinned" => 90,
-            "start_menu" => 85,
-            "common_start_menu" => 80,
-            "desktop" => 75,
-            "public_desktop" => 70,
-            "quick_launch" => 65,
-            "appdata_programs" => 60,
-            "installed_programs" => 50,
-            "program_files" => 40,
-            "program_files_x86" => 35,
-            "applications" => 90,        // macOS
-            "system_applications" => 80, // macOS
-            "user_applications" => 85,   // macOS
-            _ => 50,
-        }
-    };
-
-    // 并行执行所有扫描器
-    let all_results: Vec<(Vec<DesktopIcon>, String)> = scanners
-        .par_iter()
-        .filter(|s| s.id() != "system_profiler") // 排除极慢的扫描器，避免全量扫描时卡死
+    token: &ScanToken,
+    mut on_scanned: impl FnMut(&str, &[DesktopIcon], &ScanStatus),
+) -> Vec<(String, Vec<DesktopIcon>, ScanStatus)> {
+    let names: Vec<(String, String)> = scanners
+        .iter()
+        .map(|s| (s.id().to_string(), s.name().to_string()))
+        .collect();
+    let jobs = scanners
+        .into_iter()
         .map(|scanner| {
-            let id = scanner.id().to_string();
-            println!(">>> 开始并行扫描来源: {}", scanner.name());
-            let result = scanner.scan(method).unwrap_or_else(|e| {
-                eprintln!("!!! 扫描器 {} 失败: {}", scanner.name(), e);
-                vec![]
-            });
-            println!(
-                "<<< 扫描器 {} 完成，找到 {} 个图标",
-                scanner.name(),
-                result.len()
-            );
-            (result, id)
+            let method = method.map(str::to_string);
+            Box::new(move |token: ScanToken| {
+                println!(">>> 开始并行扫描来源: {}", scanner.name());
+                scanner
+                    .scan(method.as_deref(), &token)
+                    .map_err(|e| e.to_string())
+            }) as Box<dyn FnOnce(ScanToken) -> Result<Vec<DesktopIcon>, String> + Send>
         })
         .collect();
 
-    // 按扫描器顺序汇总（保留优先级）
-    for (icons, source_id) in all_results {
-        let priority = get_priority(&source_id);
-        for icon in icons {
-            // 生成唯一指纹：名称 + 规范化后的 target_path
-            // 不再包含 arguments，以解决带不同追踪参数的同名应用重复问题
-            let name = icon.name.trim().to_lowercase();
-            let target = if cfg!(target_os = "windows") {
-                normalize_path(&icon.target_path).to_lowercase()
-            } else {
-                icon.target_path.to_lowercase()
-            };
-            let fingerprint = format!("{}:{}", name, target);
-
-            if let Some((existing_icon, existing_priority)) = icon_map.get_mut(&fingerprint) {
-                // 如果新来源优先级更高，或者优先级相同但新图标数据更完整，则替换
-                let should_replace = if priority > *existing_priority {
-                    true
-                } else if priority == *existing_priority {
-                    existing_icon.icon_base64.is_empty() && !icon.icon_base64.is_empty()
-                } else {
-                    false
-                };
+    let mut results: Vec<Option<(Vec<DesktopIcon>, ScanStatus)>> =
+        names.iter().map(|_| None).collect();
+    let timeout = scan_settings().scanner_timeout();
+    run_guarded_all(jobs, timeout, token, |index, outcome| {
+        let (id, name) = &names[index];
+        let (icons, status) = match outcome {
+            Guarded::Done(Ok(icons)) => {
+                println!("<<< 扫描器 {} 完成，找到 {} 个图标", name, icons.len());
+                (icons, ScanStatus::Ok)
+            }
+            Guarded::Done(Err(e)) => {
+                eprintln!("!!! 扫描器 {} 失败: {}", name, e);
+                (Vec::new(), ScanStatus::Failed(e))
+            }
+            Guarded::TimedOut => {
+                eprintln!(
+                    "!!! 扫描器 {} 超时: {:?}",
+                    name,
+                    timeout.unwrap_or_default()
+                );
+                (Vec::new(), ScanStatus::TimedOut)
+            }
+            Guarded::Cancelled => (Vec::new(), ScanStatus::Cancelled),
+            Guarded::Panicked => {
+                eprintln!("!!! 扫描器 {} 异常退出", name);
+                (
+                    Vec::new(),
+                    ScanStatus::Failed("扫描线程异常退出".to_string()),
+                )
+            }
+        };
+        on_scanned(id, &icons, &status);
+        results[index] = Some((icons, status));
+    });
+
+    names
+        .into_iter()
+        .zip(results)
+        .map(|((id, _), result)| {
+            let (icons, status) = result.unwrap_or((Vec::new(), ScanStatus::Cancelled));
+            (id, icons, status)
+        })
+        .collect()
+}
 
-                if should_replace {
-                    *existing_icon = icon;
-                    *existing_priority = priority;
-                }
-            } else {
-                icon_map.insert(fingerprint, (icon, priority));
+/// 按来源 ID 选择扫描器；未指定时选择全部非慢速来源，指定的来源都不可用时同样扫描全部来源
+pub fn select_scanners(source_ids: Option<&[String]>) -> Vec<Box<dyn IconScanner>> {
+    let mut scanners = get_all_scanners();
+    if let Some(ids) = source_ids {
+        for id in ids {
+            if !scanners.iter().any(|s| s.id() == id) {
+                eprintln!("[Backend] 来源 {} 不可用，已跳过", id);
             }
         }
+        if scanners.iter().any(|s| ids.iter().any(|id| id == s.id())) {
+            scanners.retain(|s| ids.iter().any(|id| id == s.id()));
+            return scanners;
+        }
+        println!("[Backend] 指定的来源都不可用，执行全量扫描");
     }
+    // 排除极慢的扫描器，避免全量扫描时卡死
+    scanners.retain(|s| s.cost() != ScanCost::Slow);
+    scanners
+}
 
-    // 将 HashMap 转换为 Vec，并按名称排序
-    let mut all_icons: Vec<DesktopIcon> = icon_map.into_iter().map(|(_, (icon, _))| icon).collect();
-    all_icons.sort_by(|a, b| a.name.cmp(&b.name));
+/// 获取所有来源的图标（并行去重）
+pub fn get_all_icons(
+    method: Option<&str>,
+) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
+    let start_all = std::time::Instant::now();
+    reset_scanner_availability();
+    crate::offline::reset_shared_root();
+    let scanners = select_scanners(None);
+
+    // 按扫描器顺序汇总，由合并引擎按来源优先级去重
+    let all_icons = build_catalog(run_scanners(scanners, method), true, method);
 
     let duration_all = start_all.elapsed();
     println!(
@@ -535,8 +814,5 @@ pub fn get_all_icons(
         all_icons.len(),
         duration_all.as_secs_f64()
     );
-
-    fill_icons(&mut all_icons, method);
-
     Ok(all_icons)
 }
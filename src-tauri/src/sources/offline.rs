@@ -0,0 +1,395 @@
+// 离线 Windows 分区软件来源 - 扫描设置中指定的 Windows 根目录（如 /mnt/c）
+//
+// 快捷方式由 lnk 模块解析，注册表读取分区中的 hive 文件，不调用任何 Win32 API。
+// 来源 ID、优先级与设置项和本机 Windows 来源一致，因此去重规则相同。
+
+use crate::cancel::ScanToken;
+use crate::lnk::ShellLink;
+use crate::localized::localized_file_name;
+use crate::offline::{shared_root, OfflinePaths, WindowsRoot};
+use crate::path::PathResolver;
+use crate::registry::Registry;
+use crate::shortcut::{get_file_metadata, hotkey_to_string, show_command_to_string};
+use crate::sources::app_paths::get_app_paths_icons;
+use crate::sources::installed_programs::{
+    get_installed_programs_from, installed_programs_settings,
+};
+use crate::sources::program_files::{program_folder_settings, scan_program_folder};
+use crate::sources::scanner::{IconScanner, ScanCost, SettingField};
+use crate::sources::squirrel::resolve_squirrel_target;
+use crate::types::*;
+use rayon::prelude::*;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use walkdir::WalkDir;
+
+// 开始菜单的默认位置，User Shell Folders 中未登记时使用
+pub(crate) const USER_PROGRAMS: &str = "%APPDATA%\\Microsoft\\Windows\\Start Menu\\Programs";
+pub(crate) const COMMON_PROGRAMS: &str = "%ProgramData%\\Microsoft\\Windows\\Start Menu\\Programs";
+
+#[derive(Clone, Copy)]
+enum OfflineSource {
+    Desktop,
+    PublicDesktop,
+    StartMenu,
+    CommonStartMenu,
+    QuickLaunch,
+    InstalledPrograms,
+    AppPaths,
+    ProgramFiles,
+    ProgramFilesX86,
+}
+
+const ALL_SOURCES: &[OfflineSource] = &[
+    OfflineSource::Desktop,
+    OfflineSource::PublicDesktop,
+    OfflineSource::StartMenu,
+    OfflineSource::CommonStartMenu,
+    OfflineSource::QuickLaunch,
+    OfflineSource::InstalledPrograms,
+    OfflineSource::AppPaths,
+    OfflineSource::ProgramFiles,
+    OfflineSource::ProgramFilesX86,
+];
+
+pub struct OfflineScanner {
+    root: Arc<WindowsRoot>,
+    source: OfflineSource,
+}
+
+/// 离线分区的全部扫描器
+pub fn get_offline_scanners(root: &str) -> Result<Vec<Box<dyn IconScanner>>, Box<dyn Error>> {
+    let root = shared_root(root)?;
+    Ok(ALL_SOURCES
+        .iter()
+        .map(|&source| {
+            Box::new(OfflineScanner {
+                root: root.clone(),
+                source,
+            }) as Box<dyn IconScanner>
+        })
+        .collect())
+}
+
+impl IconScanner for OfflineScanner {
+    fn id(&self) -> &str {
+        match self.source {
+            OfflineSource::Desktop => "desktop",
+            OfflineSource::PublicDesktop => "public_desktop",
+            OfflineSource::StartMenu => "start_menu",
+            OfflineSource::CommonStartMenu => "common_start_menu",
+            OfflineSource::QuickLaunch => "quick_launch",
+            OfflineSource::InstalledPrograms => "installed_programs",
+            OfflineSource::AppPaths => "app_paths",
+            OfflineSource::ProgramFiles => "program_files",
+            OfflineSource::ProgramFilesX86 => "program_files_x86",
+        }
+    }
+    fn name(&self) -> &str {
+        match self.source {
+            OfflineSource::Desktop => "用户桌面",
+            OfflineSource::PublicDesktop => "公共桌面",
+            OfflineSource::StartMenu => "用户开始菜单",
+            OfflineSource::CommonStartMenu => "公共开始菜单",
+            OfflineSource::QuickLaunch => "快速启动",
+            OfflineSource::InstalledPrograms => "已安装程序",
+            OfflineSource::AppPaths => "App Paths",
+            OfflineSource::ProgramFiles => "Program Files",
+            OfflineSource::ProgramFilesX86 => "Program Files (x86)",
+        }
+    }
+    fn description(&self) -> &str {
+        match self.source {
+            OfflineSource::Desktop => "离线分区中各用户桌面上的应用",
+            OfflineSource::PublicDesktop => "离线分区中所有用户共享的桌面应用",
+            OfflineSource::StartMenu => "离线分区中各用户开始菜单中的应用",
+            OfflineSource::CommonStartMenu => "离线分区中所有用户共享的开始菜单应用",
+            OfflineSource::QuickLaunch => "离线分区中各用户快速启动栏中的应用",
+            OfflineSource::InstalledPrograms => "从离线注册表文件读取的已安装程序",
+            OfflineSource::AppPaths => "离线注册表 App Paths 中登记的程序",
+            OfflineSource::ProgramFiles => "离线分区 Program Files 目录中的程序",
+            OfflineSource::ProgramFilesX86 => "离线分区 Program Files (x86) 目录中的程序",
+        }
+    }
+    fn icon(&self) -> &str {
+        match self.source {
+            OfflineSource::Desktop => "🖥️",
+            OfflineSource::PublicDesktop => "💼",
+            OfflineSource::StartMenu => "📋",
+            OfflineSource::CommonStartMenu => "🗂️",
+            OfflineSource::QuickLaunch => "⚡",
+            OfflineSource::InstalledPrograms => "📦",
+            OfflineSource::AppPaths => "🗂️",
+            OfflineSource::ProgramFiles => "📁",
+            OfflineSource::ProgramFilesX86 => "📂",
+        }
+    }
+    fn cost(&self) -> ScanCost {
+        match self.source {
+            OfflineSource::ProgramFiles | OfflineSource::ProgramFilesX86 => ScanCost::Moderate,
+            _ => ScanCost::Fast,
+        }
+    }
+    fn default_priority(&self) -> i32 {
+        match self.source {
+            OfflineSource::Desktop => 75,
+            OfflineSource::PublicDesktop => 70,
+            OfflineSource::StartMenu => 85,
+            OfflineSource::CommonStartMenu => 80,
+            OfflineSource::QuickLaunch => 65,
+            OfflineSource::InstalledPrograms => 50,
+            OfflineSource::AppPaths => 45,
+            OfflineSource::ProgramFiles => 40,
+            OfflineSource::ProgramFilesX86 => 35,
+        }
+    }
+    fn settings(&self) -> Vec<SettingField> {
+        match self.source {
+            OfflineSource::ProgramFiles | OfflineSource::ProgramFilesX86 => {
+                program_folder_settings()
+            }
+            OfflineSource::InstalledPrograms => installed_programs_settings(),
+            _ => Vec::new(),
+        }
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let root = self.root.as_ref();
+        let machine = OfflinePaths { root, user: None };
+        match self.source {
+            OfflineSource::Desktop => {
+                self.scan_user_folders(Some("Desktop"), "%USERPROFILE%\\Desktop", false, token)
+            }
+            OfflineSource::PublicDesktop => scan_shortcut_folder(
+                root.common_folder("Common Desktop", "%PUBLIC%\\Desktop"),
+                false,
+                &machine,
+                self.name(),
+                token,
+            ),
+            OfflineSource::StartMenu => {
+                self.scan_user_folders(Some("Programs"), USER_PROGRAMS, true, token)
+            }
+            OfflineSource::CommonStartMenu => scan_shortcut_folder(
+                root.common_folder("Common Programs", COMMON_PROGRAMS),
+                true,
+                &machine,
+                self.name(),
+                token,
+            ),
+            OfflineSource::QuickLaunch => self.scan_user_folders(
+                None,
+                "%APPDATA%\\Microsoft\\Internet Explorer\\Quick Launch",
+                false,
+                token,
+            ),
+            OfflineSource::InstalledPrograms => self.scan_registries(|registry, paths| {
+                get_installed_programs_from(registry, paths, method, token)
+            }),
+            OfflineSource::AppPaths => self.scan_registries(|registry, paths| {
+                get_app_paths_icons(registry, paths, method, token)
+            }),
+            OfflineSource::ProgramFiles => match root.folder("%ProgramFiles%", None) {
+                Some(dir) => scan_program_folder(&dir, method, self.id(), self.name(), token),
+                None => Ok(Vec::new()),
+            },
+            OfflineSource::ProgramFilesX86 => match root.folder("%ProgramFiles(x86)%", None) {
+                Some(dir) => scan_program_folder(&dir, method, self.id(), self.name(), token),
+                None => Ok(Vec::new()),
+            },
+        }
+    }
+}
+
+impl OfflineScanner {
+    /// 逐个用户扫描同一个用户文件夹，来源名称中带上用户名
+    fn scan_user_folders(
+        &self,
+        shell_folder: Option<&str>,
+        default: &str,
+        recursive: bool,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let root = self.root.as_ref();
+        let mut icons = Vec::new();
+        for user in root.users() {
+            let paths = OfflinePaths {
+                root,
+                user: Some(user),
+            };
+            let source_name = format!("{} ({})", self.name(), user.name);
+            icons.extend(scan_shortcut_folder(
+                root.user_folder(user, shell_folder, default),
+                recursive,
+                &paths,
+                &source_name,
+                token,
+            )?);
+        }
+        Ok(icons)
+    }
+
+    /// 依次读取 SOFTWARE 与各用户的 NTUSER.DAT，用户注册表中的路径按该用户展开
+    fn scan_registries(
+        &self,
+        scan: impl Fn(&dyn Registry, &OfflinePaths) -> Result<Vec<DesktopIcon>, Box<dyn Error>>,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let root = self.root.as_ref();
+        let mut icons = Vec::new();
+        if let Some(software) = root.software() {
+            icons.extend(scan(software, &OfflinePaths { root, user: None })?);
+        }
+        for user in root.users() {
+            let Some(hive) = user.hive() else {
+                continue;
+            };
+            let paths = OfflinePaths {
+                root,
+                user: Some(user),
+            };
+            icons.extend(scan(hive, &paths)?);
+        }
+        Ok(icons)
+    }
+}
+
+/// 扫描快捷方式文件夹（开始菜单需要递归）
+pub(crate) fn scan_shortcut_folder(
+    folder: Option<PathBuf>,
+    recursive: bool,
+    paths: &OfflinePaths,
+    source_name: &str,
+    token: &ScanToken,
+) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+    let Some(folder) = folder.filter(|f| f.is_dir()) else {
+        println!("{} 路径不存在", source_name);
+        return Ok(Vec::new());
+    };
+    println!("扫描 {} 文件夹: {:?}", source_name, folder);
+
+    let scan_start = std::time::Instant::now();
+    let files: Vec<PathBuf> = WalkDir::new(&folder)
+        .min_depth(1)
+        .max_depth(if recursive { usize::MAX } else { 1 })
+        .into_iter()
+        .take_while(|_| !token.should_stop())
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect();
+    println!(
+        "🔍 [扫描阶段] {} 扫描完成, 找到 {} 个文件, 耗时: {:.3}s",
+        source_name,
+        files.len(),
+        scan_start.elapsed().as_secs_f64()
+    );
+
+    let prepare_start = std::time::Instant::now();
+    let results: Vec<DesktopIcon> = files
+        .par_iter()
+        .filter(|_| !token.should_stop())
+        .filter_map(|path| match process_item(path, paths, source_name) {
+            Ok(icon) => Some(icon),
+            Err(e) => {
+                eprintln!("{} 处理失败 {:?}: {}", source_name, path, e);
+                None
+            }
+        })
+        .collect();
+    println!(
+        "🧩 [准备阶段] {} 已准备 {} 个条目, 耗时: {:.3}s",
+        source_name,
+        results.len(),
+        prepare_start.elapsed().as_secs_f64()
+    );
+    Ok(results)
+}
+
+/// 处理单个文件项，快捷方式的目标与图标经 paths 转换为本地路径
+pub(crate) fn process_item(
+    path: &Path,
+    paths: &dyn PathResolver,
+    source_name: &str,
+) -> Result<DesktopIcon, Box<dyn Error>> {
+    let file_path = path.to_string_lossy().to_string();
+    let file_meta = get_file_metadata(path);
+    let mut icon = DesktopIcon {
+        // desktop.ini 中登记的本地化显示名称，没有登记时使用文件名
+        name: localized_file_name(path, paths).unwrap_or_else(|| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Unknown".to_string())
+        }),
+        icon_base64: String::new(),
+        target_path: file_path.clone(),
+        file_path: file_path.clone(),
+        icon_width: 32,
+        icon_height: 32,
+        icon_source_path: Some(file_path),
+        icon_source_index: Some(0),
+        created_time: file_meta.created_time,
+        modified_time: file_meta.modified_time,
+        accessed_time: file_meta.accessed_time,
+        file_size: file_meta.file_size,
+        file_type: file_meta.file_type,
+        source_name: Some(source_name.to_string()),
+        ..Default::default()
+    };
+    if !path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("lnk"))
+    {
+        return Ok(icon);
+    }
+
+    let link = ShellLink::read(path)?;
+    let mut target = link
+        .target_path
+        .as_deref()
+        .map(|t| paths.resolve(t))
+        .or_else(|| {
+            let relative = link.relative_path.as_deref()?;
+            Some(join_relative(path.parent()?, relative))
+        })
+        .unwrap_or_default();
+    let arguments = link.arguments.unwrap_or_default();
+    let mut icon_path = link.icon_location.as_deref().map(|p| paths.resolve(p));
+    let mut icon_index = link.icon_index;
+
+    // Squirrel 应用的 Update.exe 改为实际程序，与其它来源按目标合并
+    if let Some(resolved) = resolve_squirrel_target(&target, &arguments) {
+        target = resolved.to_string_lossy().to_string();
+        icon_path = None;
+        icon_index = 0;
+    }
+
+    icon.icon_source_path = Some(icon_path.unwrap_or_else(|| target.clone()));
+    icon.icon_source_index = Some(icon_index);
+    icon.target_path = target;
+    icon.description = link.description;
+    icon.arguments = (!arguments.is_empty()).then_some(arguments);
+    icon.working_directory = link.working_directory.map(|d| paths.resolve(&d));
+    icon.hotkey = hotkey_to_string(link.hotkey);
+    icon.show_command = Some(show_command_to_string(link.show_command));
+    icon.app_user_model_id = link.app_user_model_id;
+    Ok(icon)
+}
+
+/// 快捷方式中的相对路径（如 ..\..\App\app.exe）相对于快捷方式所在目录
+fn join_relative(base: &Path, relative: &str) -> String {
+    let mut path = base.to_path_buf();
+    for component in relative.split(['\\', '/']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                path.pop();
+            }
+            name => path.push(name),
+        }
+    }
+    path.to_string_lossy().to_string()
+}
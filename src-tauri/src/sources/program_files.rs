@@ -1,15 +1,27 @@
 // Program Files 软件来源
 
+use crate::cancel::ScanToken;
+use crate::config::{scan_settings, scanner_setting};
+#[cfg(target_os = "windows")]
 use crate::path::*;
+use crate::pe::read_image_summary;
+use crate::pe::version::read_version_info;
+use crate::rules::{filter_entries, pattern_matches};
 use crate::shortcut::get_file_metadata;
-use crate::sources::scanner::IconScanner;
+#[cfg(target_os = "windows")]
+use crate::sources::scanner::{IconScanner, ScanCost};
+use crate::sources::scanner::{SettingField, SettingKind};
+use crate::sources::{apply_image_summary, apply_version_info, version_display_name};
 use crate::types::*;
 use rayon::prelude::*;
+#[cfg(target_os = "windows")]
 use std::error::Error;
 use std::path::Path;
 
+#[cfg(target_os = "windows")]
 pub struct ProgramFilesScanner;
 
+#[cfg(target_os = "windows")]
 impl IconScanner for ProgramFilesScanner {
     fn id(&self) -> &str {
         "program_files"
@@ -23,13 +35,28 @@ impl IconScanner for ProgramFilesScanner {
     fn icon(&self) -> &str {
         "📁"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_program_files_icons(method)
+    fn cost(&self) -> ScanCost {
+        ScanCost::Moderate
+    }
+    fn default_priority(&self) -> i32 {
+        40
+    }
+    fn settings(&self) -> Vec<SettingField> {
+        program_folder_settings()
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_program_files_icons(method, token)
     }
 }
 
+#[cfg(target_os = "windows")]
 pub struct ProgramFilesX86Scanner;
 
+#[cfg(target_os = "windows")]
 impl IconScanner for ProgramFilesX86Scanner {
     fn id(&self) -> &str {
         "program_files_x86"
@@ -43,32 +70,90 @@ impl IconScanner for ProgramFilesX86Scanner {
     fn icon(&self) -> &str {
         "📂"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_program_files_x86_icons(method)
+    fn cost(&self) -> ScanCost {
+        ScanCost::Moderate
+    }
+    fn default_priority(&self) -> i32 {
+        35
+    }
+    fn settings(&self) -> Vec<SettingField> {
+        program_folder_settings()
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_program_files_x86_icons(method, token)
     }
 }
 
 /// 获取 Program Files 中的程序图标
+#[cfg(target_os = "windows")]
 pub fn get_program_files_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let program_files_path = get_program_files_path()?;
-    scan_program_folder(&program_files_path, method, "Program Files")
+    scan_program_folder(
+        &program_files_path,
+        method,
+        "program_files",
+        "Program Files",
+        token,
+    )
 }
 
 /// 获取 Program Files (x86) 中的程序图标
+#[cfg(target_os = "windows")]
 pub fn get_program_files_x86_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let program_files_x86_path = get_program_files_x86_path()?;
-    scan_program_folder(&program_files_x86_path, method, "Program Files (x86)")
+    scan_program_folder(
+        &program_files_x86_path,
+        method,
+        "program_files_x86",
+        "Program Files (x86)",
+        token,
+    )
 }
 
-/// 扫描 Program Files 文件夹
-fn scan_program_folder(
+// 默认的目录遍历深度
+const DEFAULT_MAX_DEPTH: usize = 4;
+
+/// Program Files 扫描器的设置项
+pub(crate) fn program_folder_settings() -> Vec<SettingField> {
+    vec![
+        SettingField {
+            key: "max_depth",
+            label: "扫描深度",
+            description: "向下遍历的目录层数，越深越慢",
+            kind: SettingKind::Depth {
+                default: DEFAULT_MAX_DEPTH,
+                min: 1,
+                max: 8,
+            },
+        },
+        SettingField {
+            key: "exclude_dirs",
+            label: "排除目录",
+            description: "跳过名称匹配的目录，支持 * 与 ?",
+            kind: SettingKind::Filters {
+                default: Vec::new(),
+            },
+        },
+    ]
+}
+
+/// 扫描 Program Files 文件夹，按扫描器 scanner_id 的设置限制深度与排除目录
+pub(crate) fn scan_program_folder(
     folder_path: &Path,
     _method: Option<&str>,
+    scanner_id: &str,
     source_name: &str,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     println!("扫描 {} 文件夹: {:?}", source_name, folder_path);
 
@@ -78,8 +163,10 @@ fn scan_program_folder(
     }
 
     let scan_start = std::time::Instant::now();
+    let max_depth = scanner_setting(scanner_id, "max_depth").unwrap_or(DEFAULT_MAX_DEPTH);
+    let exclude_dirs: Vec<String> = scanner_setting(scanner_id, "exclude_dirs").unwrap_or_default();
     let mut exe_files = Vec::new();
-    collect_exe_files(folder_path, &mut exe_files, 4)?; // 增加深度到4
+    collect_exe_files(folder_path, &mut exe_files, max_depth, &exclude_dirs, token)?;
     let scan_duration = scan_start.elapsed();
 
     println!(
@@ -91,13 +178,13 @@ fn scan_program_folder(
 
     // 并行处理所有 EXE 文件
     let prepare_start = std::time::Instant::now();
+    let gui_only = scan_settings().gui_only;
     let results: Vec<_> = exe_files
         .par_iter()
-        .filter_map(|path| match process_exe_file(path, source_name) {
-            Ok(icon) => Some(icon),
-            Err(_) => None,
-        })
+        .filter(|_| !token.should_stop())
+        .filter_map(|path| process_exe_file(path, source_name, gui_only).ok().flatten())
         .collect();
+    let results = filter_entries(results, source_name);
     let prepare_duration = prepare_start.elapsed();
     println!(
         "🧩 [准备阶段] {} 扫描结束, 等待后续统一提取图标, 已准备 {} 个条目, 耗时: {:.3}s",
@@ -113,8 +200,10 @@ fn collect_exe_files(
     dir: &Path,
     files: &mut Vec<std::path::PathBuf>,
     max_depth: usize,
+    exclude_dirs: &[String],
+    token: &ScanToken,
 ) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    if max_depth == 0 {
+    if max_depth == 0 || token.should_stop() {
         return Ok(());
     }
 
@@ -135,8 +224,12 @@ fn collect_exe_files(
             }
 
             if path.is_dir() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if exclude_dirs.iter().any(|p| pattern_matches(p, &name)) {
+                    continue;
+                }
                 // 递归处理子目录
-                collect_exe_files(&path, files, max_depth - 1)?;
+                collect_exe_files(&path, files, max_depth - 1, exclude_dirs, token)?;
             } else if path.is_file() {
                 // 收集所有文件，统一由前端过滤
                 files.push(path);
@@ -146,22 +239,34 @@ fn collect_exe_files(
     Ok(())
 }
 
-/// 处理单个可执行文件
-fn process_exe_file(
+/// 处理单个可执行文件，gui_only 时跳过非 GUI 程序
+pub(crate) fn process_exe_file(
     path: &Path,
     source_name: &str,
-) -> Result<DesktopIcon, Box<dyn std::error::Error>> {
+    gui_only: bool,
+) -> Result<Option<DesktopIcon>, Box<dyn std::error::Error>> {
     let file_name = path
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("Unknown")
         .to_string();
+    // 控制台程序、16 位程序和 DLL 不是 GUI 程序
+    let summary = read_image_summary(path);
+    if gui_only && !summary.as_ref().is_some_and(|s| s.is_gui()) {
+        return Ok(None);
+    }
+    // 读取版本资源，用产品信息替代文件名作为显示名称
+    let version_info = read_version_info(path);
+    let display_name = version_info
+        .as_ref()
+        .and_then(version_display_name)
+        .unwrap_or(file_name);
 
     let file_path = path.to_string_lossy().to_string();
     let file_meta = get_file_metadata(path);
 
-    Ok(DesktopIcon {
-        name: file_name,
+    let mut icon = DesktopIcon {
+        name: display_name,
         icon_base64: String::new(),
         target_path: file_path.clone(),
         file_path: file_path.clone(),
@@ -180,5 +285,14 @@ fn process_exe_file(
         hotkey: None,
         show_command: None,
         source_name: Some(source_name.to_string()),
-    })
+        ..Default::default()
+    };
+    if let Some(summary) = &summary {
+        apply_image_summary(&mut icon, summary);
+    }
+    if let Some(info) = &version_info {
+        apply_version_info(&mut icon, info);
+    }
+
+    Ok(Some(icon))
 }
@@ -1,10 +1,11 @@
 // 快速启动软件来源
 
+use crate::cancel::ScanToken;
 use crate::shortcut::{
     get_file_metadata, get_shortcut_full_info, hotkey_to_string, resolve_shortcut,
     show_command_to_string,
 };
-use crate::sources::scanner::IconScanner;
+use crate::sources::scanner::{IconScanner, WatchTarget};
 use crate::types::*;
 use rayon::prelude::*;
 use std::env;
@@ -26,17 +27,31 @@ impl IconScanner for QuickLaunchScanner {
     fn icon(&self) -> &str {
         "⚡"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_quick_launch_icons(method)
+    fn default_priority(&self) -> i32 {
+        65
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        get_quick_launch_path()
+            .into_iter()
+            .map(WatchTarget::Path)
+            .collect()
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_quick_launch_icons(method, token)
     }
 }
 
 /// 获取快速启动栏图标
 pub fn get_quick_launch_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let quick_launch_path = get_quick_launch_path()?;
-    scan_quick_launch_folder(&quick_launch_path, method)
+    scan_quick_launch_folder(&quick_launch_path, method, token)
 }
 
 /// 获取快速启动路径
@@ -60,6 +75,7 @@ fn get_quick_launch_path() -> std::result::Result<PathBuf, Box<dyn std::error::E
 fn scan_quick_launch_folder(
     folder_path: &Path,
     _method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     println!("扫描快速启动文件夹: {:?}", folder_path);
 
@@ -91,6 +107,7 @@ fn scan_quick_launch_folder(
         let prepare_start = std::time::Instant::now();
         let results: Vec<_> = file_paths
             .par_iter()
+            .filter(|_| !token.should_stop())
             .filter_map(|path| match process_item(path) {
                 Ok(icon) => Some(icon),
                 Err(e) => {
@@ -161,6 +178,7 @@ fn process_item(path: &Path) -> std::result::Result<DesktopIcon, Box<dyn std::er
                 hotkey: None,
                 show_command: None,
                 source_name: Some("快速启动".to_string()),
+                ..Default::default()
             });
         }
     };
@@ -196,6 +214,8 @@ fn process_item(path: &Path) -> std::result::Result<DesktopIcon, Box<dyn std::er
         },
         hotkey: hotkey_to_string(shortcut_info.hotkey),
         show_command: Some(show_command_to_string(shortcut_info.show_command)),
+        app_user_model_id: shortcut_info.app_user_model_id,
         source_name: Some("快速启动".to_string()),
+        ..Default::default()
     })
 }
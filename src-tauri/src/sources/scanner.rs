@@ -1,5 +1,73 @@
+use crate::cancel::ScanToken;
+use crate::registry::RegistryRoot;
 use crate::types::DesktopIcon;
+use serde::Serialize;
 use std::error::Error;
+use std::path::PathBuf;
+
+/// 扫描耗时的预估，全量扫描会跳过 Slow 的扫描器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanCost {
+    /// 读取少量文件或注册表
+    Fast,
+    /// 遍历目录树或启动外部进程
+    Moderate,
+    /// 耗时数秒以上
+    Slow,
+}
+
+/// 一个来源的扫描结果状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum ScanStatus {
+    Ok,
+    /// 扫描出错，附带错误信息
+    Failed(String),
+    /// 超过扫描器超时时间
+    TimedOut,
+    /// 扫描被取消
+    Cancelled,
+}
+
+/// 扫描器设置项的类型与默认值，前端按 kind 渲染输入控件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SettingKind {
+    /// 目录路径
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    Path { default: Option<String> },
+    /// 目录遍历深度
+    Depth {
+        default: usize,
+        min: usize,
+        max: usize,
+    },
+    /// 开关
+    Bool { default: bool },
+    /// 通配符模式列表（* 与 ?，不区分大小写）
+    Filters { default: Vec<String> },
+}
+
+/// 扫描器的一个设置项，值保存在扫描设置的 scanner_settings.<扫描器 ID>.<key> 中
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingField {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
+    #[serde(flatten)]
+    pub kind: SettingKind,
+}
+
+/// 变化监视的目标
+#[derive(Debug, Clone)]
+pub enum WatchTarget {
+    /// 目录及其子目录
+    Path(PathBuf),
+    /// 注册表键及其子键（Windows）
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Registry(RegistryRoot, String),
+}
 
 /// 图标扫描器 Trait
 pub trait IconScanner: Send + Sync {
@@ -15,6 +83,40 @@ pub trait IconScanner: Send + Sync {
     /// 扫描器的图标（Emoji）
     fn icon(&self) -> &str;
 
-    /// 执行扫描
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>>;
+    /// 当前环境能否使用（如未安装 WSL 时不可用），不可用的扫描器不会注册
+    fn available(&self) -> bool {
+        true
+    }
+
+    /// 扫描耗时的预估
+    fn cost(&self) -> ScanCost {
+        ScanCost::Fast
+    }
+
+    /// 去重时的默认优先级，数值越大越优先，可在设置中覆盖
+    fn default_priority(&self) -> i32 {
+        50
+    }
+
+    /// 需要监视变化的目录与注册表键，变化时重新扫描该来源
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        Vec::new()
+    }
+
+    /// 扫描的内容能否通过监视文件或注册表变化增量更新
+    fn supports_watching(&self) -> bool {
+        !self.watch_targets().is_empty()
+    }
+
+    /// 扫描器自己的设置项
+    fn settings(&self) -> Vec<SettingField> {
+        Vec::new()
+    }
+
+    /// 执行扫描，在遍历与逐项处理时检查 token，取消或超时后尽快返回
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>>;
 }
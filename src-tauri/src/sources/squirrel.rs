@@ -0,0 +1,144 @@
+// Squirrel 安装的应用（Discord、Slack、GitHub Desktop 等）
+//
+// 目录结构：
+//   %LOCALAPPDATA%\Discord\Update.exe
+//   %LOCALAPPDATA%\Discord\app-1.0.9001\Discord.exe
+//   %LOCALAPPDATA%\Discord\app-1.0.9002\Discord.exe
+// 快捷方式指向 Update.exe --processStart Discord.exe，由 Update.exe 启动最新版本。
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// 从启动参数中取出 --processStart 指定的程序名
+pub fn parse_process_start(arguments: &str) -> Option<String> {
+    let tokens = split_arguments(arguments);
+    let mut iter = tokens.iter();
+    while let Some(token) = iter.next() {
+        for flag in ["--processStartAndWait", "--processStart"] {
+            let Some(rest) = token
+                .get(..flag.len())
+                .filter(|p| p.eq_ignore_ascii_case(flag))
+                .map(|_| &token[flag.len()..])
+            else {
+                continue;
+            };
+            // 支持 --processStart=Discord.exe 与 --processStart Discord.exe 两种写法
+            let exe = if let Some(inline) = rest.strip_prefix('=') {
+                inline.to_string()
+            } else if rest.is_empty() {
+                iter.next()?.clone()
+            } else {
+                continue;
+            };
+            return (!exe.is_empty()).then_some(exe);
+        }
+    }
+    None
+}
+
+/// 将 Update.exe --processStart xxx.exe 解析为最新 app-<版本> 目录中的实际程序
+pub fn resolve_squirrel_target(target: &str, arguments: &str) -> Option<PathBuf> {
+    let update = Path::new(target.trim().trim_matches('"'));
+    if !update
+        .file_name()
+        .is_some_and(|n| n.eq_ignore_ascii_case("update.exe"))
+    {
+        return None;
+    }
+    let exe = parse_process_start(arguments)?;
+    let app_dir = latest_app_dir(update.parent()?)?;
+    let resolved = app_dir.join(exe);
+    resolved.is_file().then_some(resolved)
+}
+
+/// 是否为非最新的 app-<版本> 目录（旧版本在更新后会保留一段时间）
+#[cfg(target_os = "windows")]
+pub fn is_stale_app_dir(dir: &Path) -> bool {
+    let Some(version) = dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .and_then(app_dir_version)
+    else {
+        return false;
+    };
+    let Some(root) = dir.parent() else {
+        return false;
+    };
+    root.join("Update.exe").is_file()
+        && latest_app_dir(root)
+            .and_then(|latest| {
+                latest
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(app_dir_version)
+            })
+            .is_some_and(|latest| compare_versions(&version, &latest) == Ordering::Less)
+}
+
+/// 根目录下版本号最高的 app-<版本> 目录
+pub fn latest_app_dir(root: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(root)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| {
+            let version = app_dir_version(&e.file_name().to_string_lossy())?;
+            Some((version, e.path()))
+        })
+        .max_by(|(a, _), (b, _)| compare_versions(a, b))
+        .map(|(_, path)| path)
+}
+
+/// 解析 app-1.2.3 或 app-1.2.3-beta1 形式的目录名
+fn app_dir_version(name: &str) -> Option<String> {
+    let version = name
+        .get(..4)
+        .filter(|p| p.eq_ignore_ascii_case("app-"))
+        .map(|_| &name[4..])?;
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| version.to_string())
+}
+
+/// 先按数字段比较，数字段相同时不带预发布后缀的版本更新
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> (Vec<u64>, String) {
+        let (numbers, suffix) = v.split_once('-').unwrap_or((v, ""));
+        let parts = numbers
+            .split('.')
+            .map(|p| p.parse::<u64>().unwrap_or(0))
+            .collect();
+        (parts, suffix.to_lowercase())
+    };
+    let (a_parts, a_suffix) = split(a);
+    let (b_parts, b_suffix) = split(b);
+    a_parts
+        .cmp(&b_parts)
+        .then_with(|| match (a_suffix.is_empty(), b_suffix.is_empty()) {
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            _ => a_suffix.cmp(&b_suffix),
+        })
+}
+
+/// 按空白拆分命令行参数，支持双引号
+fn split_arguments(arguments: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in arguments.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
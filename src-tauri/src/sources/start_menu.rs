@@ -1,11 +1,13 @@
 // 开始菜单软件来源
 
+use crate::cancel::ScanToken;
+use crate::localized::localized_file_name;
 use crate::path::*;
 use crate::shortcut::{
-    get_file_metadata, get_localized_name, get_shortcut_full_info, hotkey_to_string,
-    show_command_to_string,
+    get_file_metadata, get_shortcut_full_info, hotkey_to_string, show_command_to_string,
 };
-use crate::sources::scanner::IconScanner;
+use crate::sources::clickonce::{create_appref_icon, is_appref};
+use crate::sources::scanner::{IconScanner, WatchTarget};
 use crate::types::*;
 use rayon::prelude::*;
 use std::error::Error;
@@ -26,8 +28,21 @@ impl IconScanner for StartMenuScanner {
     fn icon(&self) -> &str {
         "📋"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_start_menu_icons(method)
+    fn default_priority(&self) -> i32 {
+        85
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        get_start_menu_programs_path()
+            .into_iter()
+            .map(WatchTarget::Path)
+            .collect()
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_start_menu_icons(method, token)
     }
 }
 
@@ -46,25 +61,40 @@ impl IconScanner for CommonStartMenuScanner {
     fn icon(&self) -> &str {
         "🗂️"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_common_start_menu_icons(method)
+    fn default_priority(&self) -> i32 {
+        80
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        get_common_start_menu_programs_path()
+            .into_iter()
+            .map(WatchTarget::Path)
+            .collect()
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_common_start_menu_icons(method, token)
     }
 }
 
 /// 获取用户开始菜单图标
 pub fn get_start_menu_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let programs_path = get_start_menu_programs_path()?;
-    scan_folder_recursive(&programs_path, method, "用户开始菜单")
+    scan_folder_recursive(&programs_path, method, "用户开始菜单", token)
 }
 
 /// 获取公共开始菜单图标
 pub fn get_common_start_menu_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     let programs_path = get_common_start_menu_programs_path()?;
-    scan_folder_recursive(&programs_path, method, "公共开始菜单")
+    scan_folder_recursive(&programs_path, method, "公共开始菜单", token)
 }
 
 /// 递归扫描文件夹（开始菜单有子文件夹）
@@ -72,6 +102,7 @@ fn scan_folder_recursive(
     folder_path: &Path,
     _method: Option<&str>,
     source_name: &str,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     println!("递归扫描 {} 文件夹: {:?}", source_name, folder_path);
 
@@ -82,7 +113,7 @@ fn scan_folder_recursive(
 
     let scan_start = std::time::Instant::now();
     let mut all_files = Vec::new();
-    collect_files_recursive(folder_path, &mut all_files)?;
+    collect_files_recursive(folder_path, &mut all_files, token)?;
     let scan_duration = scan_start.elapsed();
 
     println!(
@@ -96,6 +127,7 @@ fn scan_folder_recursive(
     let extract_start = std::time::Instant::now();
     let results: Vec<_> = all_files
         .par_iter()
+        .filter(|_| !token.should_stop())
         .filter_map(|path| match process_item(path, source_name) {
             Ok(icon) => Some(icon),
             Err(e) => {
@@ -119,13 +151,17 @@ fn scan_folder_recursive(
 fn collect_files_recursive(
     dir: &Path,
     files: &mut Vec<std::path::PathBuf>,
+    token: &ScanToken,
 ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    if token.should_stop() {
+        return Ok(());
+    }
     if let Ok(entries) = std::fs::read_dir(dir) {
         for entry in entries.filter_map(|e| e.ok()) {
             let path = entry.path();
             if path.is_dir() {
                 // 递归处理子目录
-                collect_files_recursive(&path, files)?;
+                collect_files_recursive(&path, files, token)?;
             } else if path.is_file() {
                 // 收集所有文件，不再仅限于快捷方式，统一由前端过滤
                 files.push(path);
@@ -137,6 +173,11 @@ fn collect_files_recursive(
 
 /// 处理单个文件项
 fn process_item(path: &Path, source_name: &str) -> Result<DesktopIcon, Box<dyn std::error::Error>> {
+    // ClickOnce 部署引用不是快捷方式，单独解析
+    if is_appref(path) {
+        return create_appref_icon(path, source_name);
+    }
+
     let file_name = path
         .file_stem()
         .and_then(|s| s.to_str())
@@ -157,8 +198,8 @@ fn process_item(path: &Path, source_name: &str) -> Result<DesktopIcon, Box<dyn s
         }
     };
 
-    // 使用 Shell API 获取本地化显示名称，如果失败则使用文件名
-    let display_name = get_localized_name(path).unwrap_or(file_name);
+    // desktop.ini 中登记的本地化显示名称，没有登记时使用文件名
+    let display_name = localized_file_name(path, &LocalPaths).unwrap_or(file_name);
 
     Ok(DesktopIcon {
         name: display_name,
@@ -197,6 +238,8 @@ fn process_item(path: &Path, source_name: &str) -> Result<DesktopIcon, Box<dyn s
         },
         hotkey: hotkey_to_string(shortcut_info.hotkey),
         show_command: Some(show_command_to_string(shortcut_info.show_command)),
+        app_user_model_id: shortcut_info.app_user_model_id,
         source_name: Some(source_name.to_string()),
+        ..Default::default()
     })
 }
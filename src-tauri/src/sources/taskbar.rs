@@ -1,10 +1,13 @@
 // 任务栏固定软件来源
 
+use crate::cancel::ScanToken;
+use crate::registry::{self, RegValue, RegistryRoot};
 use crate::shortcut::{
     get_file_metadata, get_shortcut_full_info, hotkey_to_string, resolve_shortcut,
     show_command_to_string,
 };
-use crate::sources::scanner::IconScanner;
+use crate::sources::scanner::{IconScanner, WatchTarget};
+use crate::sources::uwp::get_app_display_name;
 use crate::types::*;
 use rayon::prelude::*;
 use std::env;
@@ -21,23 +24,46 @@ impl IconScanner for TaskbarScanner {
         "任务栏固定项"
     }
     fn description(&self) -> &str {
-        "任务栏固定的应用程序"
+        "按任务栏顺序列出固定的应用程序"
     }
     fn icon(&self) -> &str {
         "📌"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_taskbar_pinned_icons(method)
+    fn default_priority(&self) -> i32 {
+        90
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        let mut targets = vec![WatchTarget::Registry(
+            RegistryRoot::CurrentUser,
+            TASKBAND_KEY.to_string(),
+        )];
+        targets.extend(get_user_pinned_path().into_iter().map(WatchTarget::Path));
+        targets
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_taskbar_pinned_icons(method, token)
     }
 }
 
 /// 获取任务栏固定的图标
 pub fn get_taskbar_pinned_icons(
     method: Option<&str>,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
+    // 优先按 Taskband 注册表中记录的真实顺序返回固定项
+    match get_taskband_pins() {
+        Ok(pins) if !pins.is_empty() => return scan_taskband_pins(&pins, token),
+        Ok(_) => println!("Taskband 未记录任何固定项，回退到目录扫描"),
+        Err(e) => println!("读取 Taskband 注册表失败，回退到目录扫描: {}", e),
+    }
+
     // 扫描 User Pinned 目录，包含 TaskBar、ImplicitAppShortcuts 等所有子目录
     if let Ok(user_pinned_path) = get_user_pinned_path() {
-        return scan_taskbar_folder(&user_pinned_path, method, "任务栏及常用项");
+        return scan_taskbar_folder(&user_pinned_path, method, "任务栏及常用项", token);
     }
 
     // 如果目录不存在，返回空列表
@@ -62,11 +88,308 @@ fn get_user_pinned_path() -> std::result::Result<PathBuf, Box<dyn std::error::Er
     }
 }
 
+// ========== Taskband 注册表解析 ==========
+
+const TASKBAND_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\Taskband";
+
+/// 任务栏上的一个固定项
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskbandPin {
+    /// 固定到 User Pinned\TaskBar 下的快捷方式（文件名）
+    Shortcut(String),
+    /// 通过 AppUserModelID 固定的应用商店应用
+    App(String),
+}
+
+/// 读取并解析 Taskband 的 Favorites/FavoritesResolve，按任务栏顺序返回固定项
+fn get_taskband_pins() -> std::result::Result<Vec<TaskbandPin>, Box<dyn std::error::Error>> {
+    let read_binary = |name: &str| match registry::system().value(
+        RegistryRoot::CurrentUser,
+        TASKBAND_KEY,
+        name,
+    ) {
+        Some(RegValue::Binary(data)) if !data.is_empty() => Some(data),
+        _ => None,
+    };
+    let favorites = read_binary("Favorites").ok_or("Taskband\\Favorites 不存在")?;
+    let resolve = read_binary("FavoritesResolve").unwrap_or_default();
+
+    Ok(parse_taskband(&favorites, &resolve))
+}
+
+/// 解析 Taskband 二进制数据
+///
+/// Favorites 以 1 字节版本号开头，之后是若干条记录：4 字节长度 + 对应长度的 IDList，
+/// 记录之间可能夹带与版本相关的附加数据。FavoritesResolve 按相同顺序保存每个固定项的
+/// ShellLink 二进制数据，仅在 IDList 中无法识别固定项时用来补充 AUMID。
+pub fn parse_taskband(favorites: &[u8], resolve: &[u8]) -> Vec<TaskbandPin> {
+    let records = split_favorites_records(favorites);
+    let resolve_records = split_shell_link_records(resolve);
+
+    let mut pins = Vec::new();
+    for (i, record) in records.iter().enumerate() {
+        let pin = find_lnk_name(record)
+            .map(TaskbandPin::Shortcut)
+            .or_else(|| find_aumid(record).map(TaskbandPin::App))
+            .or_else(|| {
+                resolve_records
+                    .get(i)
+                    .and_then(|r| find_aumid(r))
+                    .map(TaskbandPin::App)
+            });
+
+        match pin {
+            Some(pin) if !pins.contains(&pin) => pins.push(pin),
+            Some(_) => {}
+            None => println!("Taskband 第 {} 条记录无法识别，已跳过", i + 1),
+        }
+    }
+    pins
+}
+
+/// 按长度字段切分 Favorites 记录，遇到无法对齐的附加数据时向后搜索下一条合法记录
+fn split_favorites_records(data: &[u8]) -> Vec<&[u8]> {
+    let mut records = Vec::new();
+    // 跳过版本号
+    let mut pos = 1;
+    while pos + 4 <= data.len() {
+        if let Some(len) = idlist_record_len(data, pos) {
+            records.push(&data[pos + 4..pos + 4 + len]);
+            pos += 4 + len;
+        } else {
+            pos += 1;
+        }
+    }
+    records
+}
+
+/// 检查 pos 处是否为一条合法记录（长度字段 + 以 0 结尾的 Shell Item 链），返回 IDList 长度
+fn idlist_record_len(data: &[u8], pos: usize) -> Option<usize> {
+    let len = u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+    // 至少包含一个 Shell Item 和结束符
+    if len < 4 {
+        return None;
+    }
+    let idlist = data.get(pos + 4..pos + 4 + len)?;
+
+    let mut offset = 0;
+    let mut items = 0;
+    while offset + 2 <= idlist.len() {
+        let item_size = u16::from_le_bytes([idlist[offset], idlist[offset + 1]]) as usize;
+        if item_size == 0 {
+            return if items > 0 { Some(len) } else { None };
+        }
+        if item_size < 3 || offset + item_size > idlist.len() {
+            return None;
+        }
+        offset += item_size;
+        items += 1;
+    }
+    None
+}
+
+/// 按 ShellLink 头部签名切分 FavoritesResolve 中的记录
+fn split_shell_link_records(data: &[u8]) -> Vec<&[u8]> {
+    // HeaderSize (0x4C) + LinkCLSID {00021401-0000-0000-C000-000000000046}
+    const LNK_SIGNATURE: [u8; 8] = [0x4C, 0x00, 0x00, 0x00, 0x01, 0x14, 0x02, 0x00];
+
+    let starts: Vec<usize> = data
+        .windows(LNK_SIGNATURE.len())
+        .enumerate()
+        .filter(|(_, w)| *w == LNK_SIGNATURE)
+        .map(|(i, _)| i)
+        .collect();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(data.len());
+            &data[start..end]
+        })
+        .collect()
+}
+
+/// 从二进制数据中提取由可打印 ASCII 组成的 UTF-16LE 字符串（至少 3 个字符）
+fn extract_ascii_utf16_strings(data: &[u8]) -> Vec<String> {
+    let mut strings = Vec::new();
+    // UTF-16 字符串可能从奇数偏移开始，两种对齐都要尝试
+    for align in 0..2 {
+        let mut current = String::new();
+        for chunk in data[align.min(data.len())..].chunks_exact(2) {
+            let c = u16::from_le_bytes([chunk[0], chunk[1]]);
+            if (0x20..0x7F).contains(&c) {
+                current.push(c as u8 as char);
+            } else {
+                if current.len() >= 3 {
+                    strings.push(current.clone());
+                }
+                current.clear();
+            }
+        }
+        if current.len() >= 3 {
+            strings.push(current);
+        }
+    }
+    strings
+}
+
+/// 找出记录中固定快捷方式的文件名
+///
+/// 文件类 Shell Item 的长文件名保存在 0xBEEF0004 扩展块中，名称偏移由扩展块版本决定；
+/// IDList 末级项就是 TaskBar 目录下的快捷方式，因此取最后一个 .lnk 名称。
+fn find_lnk_name(record: &[u8]) -> Option<String> {
+    const BEEF0004: [u8; 4] = [0x04, 0x00, 0xEF, 0xBE];
+
+    let mut found = None;
+    let mut pos = 0;
+    while let Some(rel) = record
+        .get(pos..)
+        .and_then(|rest| rest.windows(4).position(|w| w == BEEF0004))
+    {
+        let sig_pos = pos + rel;
+        pos = sig_pos + 4;
+        // 签名前是 2 字节大小和 2 字节版本号
+        let Some(block_start) = sig_pos.checked_sub(4) else {
+            continue;
+        };
+        let block_size =
+            u16::from_le_bytes([record[block_start], record[block_start + 1]]) as usize;
+        let version = u16::from_le_bytes([record[block_start + 2], record[block_start + 3]]);
+        let name_offset = match version {
+            3..=6 => 20,
+            7 => 38,
+            8 => 42,
+            v if v >= 9 => 46,
+            _ => continue,
+        };
+        let Some(block) = record.get(block_start..block_start + block_size) else {
+            continue;
+        };
+        let Some(name_bytes) = block.get(name_offset..) else {
+            continue;
+        };
+        let name: Vec<u16> = name_bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&c| c != 0)
+            .collect();
+        let name = String::from_utf16_lossy(&name);
+        if name.to_lowercase().ends_with(".lnk") {
+            found = Some(name);
+        }
+    }
+    found
+}
+
+/// 找出记录中的应用商店 AUMID，格式为 PackageFamilyName!AppId
+fn find_aumid(record: &[u8]) -> Option<String> {
+    extract_ascii_utf16_strings(record)
+        .into_iter()
+        .find(|s| is_store_aumid(s))
+}
+
+fn is_store_aumid(s: &str) -> bool {
+    let Some((family, app_id)) = s.split_once('!') else {
+        return false;
+    };
+    // PackageFamilyName = 包名_发布者哈希
+    let Some((package, publisher_hash)) = family.rsplit_once('_') else {
+        return false;
+    };
+    !package.is_empty()
+        && publisher_hash.len() == 13
+        && publisher_hash.chars().all(|c| c.is_ascii_alphanumeric())
+        && !app_id.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '!'))
+}
+
+/// 按 Taskband 顺序生成固定项，未被固定的残留快捷方式不会出现在结果中
+fn scan_taskband_pins(
+    pins: &[TaskbandPin],
+    token: &ScanToken,
+) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
+    let source_name = "任务栏固定项";
+    let taskbar_dir = get_user_pinned_path()
+        .map(|p| p.join("TaskBar"))
+        .unwrap_or_default();
+
+    println!(
+        "🔍 [扫描阶段] {} 从 Taskband 解析到 {} 个固定项",
+        source_name,
+        pins.len()
+    );
+
+    let prepare_start = std::time::Instant::now();
+    // 保持顺序的并行处理
+    let results: Vec<DesktopIcon> = pins
+        .par_iter()
+        .filter(|_| !token.should_stop())
+        .filter_map(|pin| match pin {
+            TaskbandPin::Shortcut(file_name) => {
+                let path = taskbar_dir.join(file_name);
+                if !path.is_file() {
+                    println!("{} 固定项对应的快捷方式不存在: {:?}", source_name, path);
+                    return None;
+                }
+                match process_item(&path, source_name) {
+                    Ok(icon) => Some(icon),
+                    Err(e) => {
+                        eprintln!("{} 处理失败 {:?}: {}", source_name, path, e);
+                        None
+                    }
+                }
+            }
+            TaskbandPin::App(aumid) => Some(create_app_pin_icon(aumid, source_name)),
+        })
+        .collect();
+    let prepare_duration = prepare_start.elapsed();
+    println!(
+        "🧩 [准备阶段] {} 扫描结束, 等待后续统一提取图标, 已准备 {} 个条目, 耗时: {:.3}s",
+        source_name,
+        results.len(),
+        prepare_duration.as_secs_f64()
+    );
+
+    Ok(results)
+}
+
+/// 为通过 AUMID 固定的应用商店应用生成条目，字段与 UWP 来源保持一致
+fn create_app_pin_icon(aumid: &str, source_name: &str) -> DesktopIcon {
+    let name = get_app_display_name(aumid).unwrap_or_else(|| aumid.to_string());
+
+    DesktopIcon {
+        name,
+        icon_base64: String::new(),
+        target_path: aumid.to_string(),
+        file_path: aumid.to_string(),
+        icon_width: 32,
+        icon_height: 32,
+        icon_source_path: Some(format!("shell:AppsFolder\\{}", aumid)),
+        icon_source_index: None,
+        created_time: None,
+        modified_time: None,
+        accessed_time: None,
+        file_size: None,
+        file_type: Some("UWP App".to_string()),
+        description: None,
+        arguments: None,
+        working_directory: None,
+        hotkey: None,
+        show_command: None,
+        source_name: Some(source_name.to_string()),
+        app_user_model_id: Some(aumid.to_string()),
+        ..Default::default()
+    }
+}
+
 /// 扫描任务栏固定文件夹
 fn scan_taskbar_folder(
     folder_path: &Path,
     _method: Option<&str>,
     source_name: &str,
+    token: &ScanToken,
 ) -> std::result::Result<Vec<DesktopIcon>, Box<dyn std::error::Error>> {
     println!("扫描 {} 文件夹: {:?}", source_name, folder_path);
 
@@ -80,7 +403,7 @@ fn scan_taskbar_folder(
 
     let scan_start = std::time::Instant::now();
     // 递归收集所有 .lnk 文件（任务栏文件夹可能有子目录）
-    collect_lnk_files_recursive(folder_path, &mut all_files)?;
+    collect_lnk_files_recursive(folder_path, &mut all_files, token)?;
     let scan_duration = scan_start.elapsed();
 
     println!(
@@ -94,6 +417,7 @@ fn scan_taskbar_folder(
     let prepare_start = std::time::Instant::now();
     let results: Vec<_> = all_files
         .par_iter()
+        .filter(|_| !token.should_stop())
         .filter_map(|path| match process_item(path, source_name) {
             Ok(icon) => Some(icon),
             Err(e) => {
@@ -118,13 +442,17 @@ fn scan_taskbar_folder(
 fn collect_lnk_files_recursive(
     dir: &Path,
     files: &mut Vec<PathBuf>,
+    token: &ScanToken,
 ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    if token.should_stop() {
+        return Ok(());
+    }
     if let Ok(entries) = std::fs::read_dir(dir) {
         for entry in entries.filter_map(|e| e.ok()) {
             let path = entry.path();
             if path.is_dir() {
                 // 递归处理子目录
-                collect_lnk_files_recursive(&path, files)?;
+                collect_lnk_files_recursive(&path, files, token)?;
             } else if path.is_file() {
                 // 收集所有文件，统一由前端过滤
                 files.push(path);
@@ -174,6 +502,7 @@ fn process_item(
                 hotkey: None,
                 show_command: None,
                 source_name: Some(source_name.to_string()),
+                ..Default::default()
             });
         }
     };
@@ -209,6 +538,107 @@ fn process_item(
         },
         hotkey: hotkey_to_string(shortcut_info.hotkey),
         show_command: Some(show_command_to_string(shortcut_info.show_command)),
+        app_user_model_id: shortcut_info.app_user_model_id,
         source_name: Some(source_name.to_string()),
+        ..Default::default()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 文件类 Shell Item，长文件名放在版本 9 的 0xBEEF0004 扩展块中
+    fn file_item(name: &str) -> Vec<u8> {
+        let mut block = vec![0u8; 46];
+        block[2..4].copy_from_slice(&9u16.to_le_bytes());
+        block[4..8].copy_from_slice(&0xBEEF_0004u32.to_le_bytes());
+        block.extend(name.encode_utf16().chain([0]).flat_map(u16::to_le_bytes));
+        let block_size = block.len() as u16;
+        block[..2].copy_from_slice(&block_size.to_le_bytes());
+
+        let mut item = vec![0u8, 0, 0x32];
+        item.extend(b"SHORT~1.LNK\0");
+        item.extend(block);
+        let item_size = item.len() as u16;
+        item[..2].copy_from_slice(&item_size.to_le_bytes());
+        item
+    }
+
+    /// Favorites：版本号之后是各条记录（4 字节长度 + 以 0 结尾的 IDList）
+    fn favorites(names: &[&str]) -> Vec<u8> {
+        let mut data = vec![0x0C];
+        for name in names {
+            let mut idlist = file_item(name);
+            idlist.extend([0, 0]);
+            data.extend((idlist.len() as u32).to_le_bytes());
+            data.extend(idlist);
+            // 记录之间的附加数据
+            data.extend([0xFF; 5]);
+        }
+        data
+    }
+
+    #[test]
+    fn parses_pinned_shortcuts_in_order() {
+        let data = favorites(&["Terminal.lnk", "Browser.lnk"]);
+        assert_eq!(
+            parse_taskband(&data, &[]),
+            vec![
+                TaskbandPin::Shortcut("Terminal.lnk".into()),
+                TaskbandPin::Shortcut("Browser.lnk".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn store_app_from_resolve_records() {
+        let mut item = vec![0u8, 0, 0x1F];
+        item.extend(vec![0u8; 8]);
+        let item_size = item.len() as u16;
+        item[..2].copy_from_slice(&item_size.to_le_bytes());
+        item.extend([0, 0]);
+        let mut data = vec![0x0C];
+        data.extend((item.len() as u32).to_le_bytes());
+        data.extend(item);
+
+        let aumid = "Microsoft.WindowsCalculator_8wekyb3d8bbwe!App";
+        let mut resolve = vec![0x4C, 0x00, 0x00, 0x00, 0x01, 0x14, 0x02, 0x00];
+        resolve.extend(aumid.encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(
+            parse_taskband(&data, &resolve),
+            vec![TaskbandPin::App(aumid.into())]
+        );
+    }
+
+    #[test]
+    fn truncated_blobs_do_not_panic() {
+        let data = favorites(&["Terminal.lnk", "Browser.lnk"]);
+        for len in 0..data.len() {
+            let _ = parse_taskband(&data[..len], &data[..len]);
+        }
+        // 每个偏移处都截掉后续数据
+        for start in 0..data.len() {
+            let _ = parse_taskband(&data[start..], &[]);
+        }
+    }
+
+    #[test]
+    fn malformed_records_are_skipped() {
+        // 长度字段超出数据、Shell Item 大小越界
+        let mut data = vec![0x0C];
+        data.extend(u32::MAX.to_le_bytes());
+        data.extend([0x05, 0x00, 0x32, 0x00]);
+        data.extend(8u32.to_le_bytes());
+        data.extend([0xFF, 0x7F, 0x32, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        assert!(parse_taskband(&data, &[]).is_empty());
+
+        // 扩展块签名位于记录开头，或块大小与名称偏移越界
+        let mut record = 0xBEEF_0004u32.to_le_bytes().to_vec();
+        record.extend([0xFF, 0xFF, 0x09, 0x00]);
+        record.extend(0xBEEF_0004u32.to_le_bytes());
+        assert_eq!(find_lnk_name(&record), None);
+        assert_eq!(find_aumid(&[0x41]), None);
+        assert!(extract_ascii_utf16_strings(&[]).is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cancel::ScanToken;
 use crate::sources::scanner::IconScanner;
 use crate::types::*;
 use std::error::Error;
@@ -24,12 +25,22 @@ impl IconScanner for UWPScanner {
     fn icon(&self) -> &str {
         "🛍️"
     }
-    fn scan(&self, method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
-        get_uwp_icons(method)
+    fn default_priority(&self) -> i32 {
+        100
+    }
+    fn scan(
+        &self,
+        method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_uwp_icons(method, token)
     }
 }
 
-pub fn get_uwp_icons(_method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+pub fn get_uwp_icons(
+    _method: Option<&str>,
+    token: &ScanToken,
+) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
     let scan_start = std::time::Instant::now();
     let mut app_items = Vec::new();
 
@@ -82,9 +93,10 @@ pub fn get_uwp_icons(_method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn
             [std::ptr::null_mut()];
         let mut fetched = 0;
 
-        while enum_id_list
-            .Next(&mut item_pidl_vec, Some(&mut fetched))
-            .is_ok()
+        while !token.should_stop()
+            && enum_id_list
+                .Next(&mut item_pidl_vec, Some(&mut fetched))
+                .is_ok()
             && fetched > 0
         {
             let item_pidl = item_pidl_vec[0];
@@ -160,6 +172,10 @@ pub fn get_uwp_icons(_method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn
         .into_par_iter()
         .map(|(display_name, parsing_name)| {
             let shell_path = format!("shell:AppsFolder\\{}", parsing_name);
+            // 应用文件夹中的解析名称即 AppUserModelID；未设置 ID 的桌面程序则是路径
+            let app_user_model_id = (!parsing_name.contains('\\')
+                && !parsing_name.starts_with('{'))
+            .then(|| parsing_name.clone());
 
             DesktopIcon {
                 name: display_name,
@@ -181,6 +197,8 @@ pub fn get_uwp_icons(_method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn
                 hotkey: None,
                 show_command: None,
                 source_name: Some("应用商店应用 (UWP)".to_string()),
+                app_user_model_id,
+                ..Default::default()
             }
         })
         .collect();
@@ -193,3 +211,27 @@ pub fn get_uwp_icons(_method: Option<&str>) -> Result<Vec<DesktopIcon>, Box<dyn
 
     Ok(icons)
 }
+
+/// 通过 AppUserModelID 获取应用的显示名称
+pub fn get_app_display_name(aumid: &str) -> Option<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::{IShellItem, SHCreateItemFromParsingName, SIGDN_NORMALDISPLAY};
+
+    unsafe {
+        let _com = crate::extractors::utils::ComInit::new(COINIT_MULTITHREADED);
+
+        let parsing_name = format!("shell:AppsFolder\\{}", aumid);
+        let wide_path: Vec<u16> = parsing_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+
+        let shell_item: IShellItem =
+            SHCreateItemFromParsingName(PCWSTR(wide_path.as_ptr()), None).ok()?;
+        let display_name = shell_item.GetDisplayName(SIGDN_NORMALDISPLAY).ok()?;
+        let name = display_name.to_string().ok();
+        windows::Win32::System::Com::CoTaskMemFree(Some(display_name.as_ptr() as *const _));
+
+        name.filter(|n| !n.is_empty())
+    }
+}
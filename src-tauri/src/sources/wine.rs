@@ -0,0 +1,128 @@
+// Wine 软件来源（Linux）- 扫描各 Wine 前缀开始菜单中的快捷方式
+//
+// 前缀按离线 Windows 分区的方式读取：C:\ 映射到 drive_c，其它盘符按 dosdevices 映射，
+// 快捷方式解析与离线分区相同。条目记录所属前缀，启动时在该前缀中运行 wine start。
+
+use crate::cancel::ScanToken;
+use crate::offline::{OfflinePaths, WindowsRoot};
+use crate::sources::offline::{scan_shortcut_folder, COMMON_PROGRAMS, USER_PROGRAMS};
+use crate::sources::scanner::{IconScanner, ScanCost, SettingField, SettingKind, WatchTarget};
+use crate::types::*;
+use crate::wine::find_prefixes;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub struct WineScanner;
+
+impl IconScanner for WineScanner {
+    fn id(&self) -> &str {
+        "wine_apps"
+    }
+    fn name(&self) -> &str {
+        "Wine 应用"
+    }
+    fn description(&self) -> &str {
+        "Wine、Bottles 与 Lutris 前缀开始菜单中的 Windows 程序"
+    }
+    fn icon(&self) -> &str {
+        "🍷"
+    }
+    fn available(&self) -> bool {
+        !find_prefixes().is_empty()
+    }
+    fn cost(&self) -> ScanCost {
+        ScanCost::Moderate
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        find_prefixes()
+            .iter()
+            .flat_map(|prefix| start_menu_folders(prefix))
+            .map(WatchTarget::Path)
+            .collect()
+    }
+    fn settings(&self) -> Vec<SettingField> {
+        vec![SettingField {
+            key: "prefix",
+            label: "额外的前缀",
+            description: "自动查找之外的 Wine 前缀目录（含 drive_c）",
+            kind: SettingKind::Path { default: None },
+        }]
+    }
+    fn scan(
+        &self,
+        _method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        let prefixes = find_prefixes();
+        println!(
+            "🔍 [扫描阶段] 找到 {} 个 Wine 前缀: {:?}",
+            prefixes.len(),
+            prefixes
+        );
+
+        let mut icons = Vec::new();
+        for prefix in prefixes.iter().take_while(|_| !token.should_stop()) {
+            match scan_prefix(prefix, token) {
+                Ok(found) => icons.extend(found),
+                Err(e) => eprintln!("扫描 Wine 前缀失败 {:?}: {}", prefix, e),
+            }
+        }
+        Ok(icons)
+    }
+}
+
+/// 前缀中各用户与公共的开始菜单目录
+fn start_menu_folders(prefix: &Path) -> Vec<PathBuf> {
+    let Ok(root) = WindowsRoot::open_wine(prefix) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<PathBuf> = root
+        .users()
+        .iter()
+        .filter_map(|user| root.user_folder(user, Some("Programs"), USER_PROGRAMS))
+        .collect();
+    folders.extend(root.common_folder("Common Programs", COMMON_PROGRAMS));
+    folders
+}
+
+/// 扫描一个前缀中各用户与公共的开始菜单
+fn scan_prefix(prefix: &Path, token: &ScanToken) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+    let root = WindowsRoot::open_wine(prefix)?;
+    let prefix_name = prefix
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let source_name = format!("Wine ({})", prefix_name);
+
+    let mut icons = Vec::new();
+    for user in root.users() {
+        let paths = OfflinePaths {
+            root: &root,
+            user: Some(user),
+        };
+        icons.extend(scan_shortcut_folder(
+            root.user_folder(user, Some("Programs"), USER_PROGRAMS),
+            true,
+            &paths,
+            &source_name,
+            token,
+        )?);
+    }
+    let machine = OfflinePaths {
+        root: &root,
+        user: None,
+    };
+    icons.extend(scan_shortcut_folder(
+        root.common_folder("Common Programs", COMMON_PROGRAMS),
+        true,
+        &machine,
+        &source_name,
+        token,
+    )?);
+
+    let prefix = prefix.to_string_lossy().to_string();
+    for icon in &mut icons {
+        icon.wine_prefix = Some(prefix.clone());
+    }
+    Ok(icons)
+}
@@ -0,0 +1,236 @@
+// WSL 软件来源 - WSLg 中各 Linux 发行版的图形程序
+//
+// 发行版登记在 HKCU\Software\Microsoft\Windows\CurrentVersion\Lxss\{GUID} 的 DistributionName，
+// 其文件系统通过 \\wsl$\<发行版> 访问（首次访问会启动该发行版）。
+// 设置中指定 WSL 目录时改为读取 <目录>\<发行版>，目录本身是发行版根目录（含 usr）时直接读取。
+// 程序来自 .desktop 文件，图标按 freedesktop 图标主题查找（PNG 与 SVG），启动时交给 wsl.exe -d <发行版>。
+// 访问会启动各发行版，耗时较长，作为慢速来源只在明确选择时扫描。
+
+use crate::cancel::ScanToken;
+use crate::config::scan_settings;
+use crate::desktop_entry::{join_linux_path, DesktopEntry, IconThemes};
+use crate::pe::icon::read_icon;
+use crate::registry::{self, RegistryRoot};
+use crate::shortcut::get_file_metadata;
+use crate::sources::scanner::{IconScanner, ScanCost, WatchTarget};
+use crate::types::*;
+use rayon::prelude::*;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+const LXSS_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Lxss";
+
+// 发行版中存放 .desktop 文件的目录
+const APPLICATION_DIRS: &[&str] = &[
+    "usr/share/applications",
+    "usr/local/share/applications",
+    "var/lib/flatpak/exports/share/applications",
+];
+
+pub struct WslScanner;
+
+impl IconScanner for WslScanner {
+    fn id(&self) -> &str {
+        "wsl_apps"
+    }
+    fn name(&self) -> &str {
+        "WSL 应用"
+    }
+    fn description(&self) -> &str {
+        "WSLg 中各 Linux 发行版的图形程序（扫描时会启动发行版，不参与全量扫描）"
+    }
+    fn icon(&self) -> &str {
+        "🐧"
+    }
+    fn available(&self) -> bool {
+        !distro_roots().is_empty()
+    }
+    fn cost(&self) -> ScanCost {
+        ScanCost::Slow
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        distro_roots()
+            .iter()
+            .flat_map(|(_, root)| {
+                APPLICATION_DIRS
+                    .iter()
+                    .map(|dir| join_linux_path(root, dir))
+            })
+            .map(WatchTarget::Path)
+            .collect()
+    }
+    fn scan(
+        &self,
+        _method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        get_wsl_icons(token)
+    }
+}
+
+/// 读取各发行版的 .desktop 程序
+pub fn get_wsl_icons(token: &ScanToken) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+    let distros = distro_roots();
+    println!(
+        "🔍 [扫描阶段] 找到 {} 个 WSL 发行版: {:?}",
+        distros.len(),
+        distros.iter().map(|(name, _)| name).collect::<Vec<_>>()
+    );
+
+    let mut icons = Vec::new();
+    for (distro, root) in distros.iter().take_while(|_| !token.should_stop()) {
+        icons.extend(scan_distro(distro, root, token));
+    }
+    Ok(icons)
+}
+
+/// 各发行版的名称与根目录
+fn distro_roots() -> Vec<(String, PathBuf)> {
+    if let Some(dir) = scan_settings()
+        .wsl_root
+        .filter(|d| !d.trim().is_empty())
+        .map(|d| PathBuf::from(d.trim()))
+    {
+        let dir_name = |p: &Path| {
+            p.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        if dir.join("usr").is_dir() {
+            return vec![(dir_name(&dir), dir)];
+        }
+        return std::fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .map(|p| (dir_name(&p), p))
+            .collect();
+    }
+
+    let registry = registry::system();
+    registry
+        .subkeys(RegistryRoot::CurrentUser, LXSS_KEY)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|id| {
+            registry.string(
+                RegistryRoot::CurrentUser,
+                &format!("{}\\{}", LXSS_KEY, id),
+                "DistributionName",
+            )
+        })
+        .map(|name| {
+            let root = PathBuf::from(format!(r"\\wsl$\{}", name));
+            (name, root)
+        })
+        .collect()
+}
+
+fn scan_distro(distro: &str, root: &Path, token: &ScanToken) -> Vec<DesktopIcon> {
+    let source_name = format!("WSL ({})", distro);
+    let scan_start = std::time::Instant::now();
+    let files: Vec<PathBuf> = APPLICATION_DIRS
+        .iter()
+        .map(|dir| join_linux_path(root, dir))
+        .filter(|dir| dir.is_dir())
+        .flat_map(|dir| {
+            WalkDir::new(dir)
+                .into_iter()
+                .take_while(|_| !token.should_stop())
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .map(|e| e.into_path())
+                .filter(|p| p.extension().is_some_and(|e| e == "desktop"))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    println!(
+        "🔍 [扫描阶段] {} 扫描完成, 找到 {} 个 .desktop 文件, 耗时: {:.3}s",
+        source_name,
+        files.len(),
+        scan_start.elapsed().as_secs_f64()
+    );
+
+    let prepare_start = std::time::Instant::now();
+    let themes = IconThemes::new(
+        &[join_linux_path(root, "usr/share/icons")],
+        vec![join_linux_path(root, "usr/share/pixmaps")],
+    );
+    let results: Vec<DesktopIcon> = files
+        .par_iter()
+        .filter(|_| !token.should_stop())
+        .filter_map(|path| {
+            let text = std::fs::read_to_string(path).ok()?;
+            let entry = DesktopEntry::parse(&text)?;
+            Some(create_wsl_icon(
+                path,
+                &entry,
+                distro,
+                root,
+                &themes,
+                &source_name,
+            ))
+        })
+        .collect();
+    println!(
+        "🧩 [准备阶段] {} 已准备 {} 个条目, 耗时: {:.3}s",
+        source_name,
+        results.len(),
+        prepare_start.elapsed().as_secs_f64()
+    );
+    results
+}
+
+fn create_wsl_icon(
+    path: &Path,
+    entry: &DesktopEntry,
+    distro: &str,
+    root: &Path,
+    themes: &IconThemes,
+    source_name: &str,
+) -> DesktopIcon {
+    let file_path = path.to_string_lossy().to_string();
+    let file_meta = get_file_metadata(path);
+    let mut icon = DesktopIcon {
+        name: entry.name.clone(),
+        icon_base64: String::new(),
+        target_path: file_path.clone(),
+        file_path,
+        icon_width: 32,
+        icon_height: 32,
+        created_time: file_meta.created_time,
+        modified_time: file_meta.modified_time,
+        accessed_time: file_meta.accessed_time,
+        file_size: file_meta.file_size,
+        file_type: file_meta.file_type,
+        description: entry.comment.clone(),
+        arguments: Some(entry.exec.clone()),
+        working_directory: entry.path.clone(),
+        source_name: Some(source_name.to_string()),
+        run_in_terminal: entry.terminal,
+        aliases: (!entry.keywords.is_empty()).then(|| entry.keywords.clone()),
+        wsl_distro: Some(distro.to_string()),
+        ..Default::default()
+    };
+
+    let image = entry
+        .icon
+        .as_deref()
+        .and_then(|name| themes.find(root, name));
+    if let Some(image) = image {
+        match read_icon(&image, 0) {
+            Some(data) => {
+                icon.icon_base64 = data.base64;
+                icon.icon_width = data.width;
+                icon.icon_height = data.height;
+            }
+            None => eprintln!("读取 WSL 图标失败 {:?}", image),
+        }
+        icon.icon_source_path = Some(image.to_string_lossy().to_string());
+        icon.icon_source_index = Some(0);
+    }
+    icon
+}
@@ -0,0 +1,219 @@
+// XDG 应用来源（Linux）- 本机 applications 目录中的 .desktop 程序
+//
+// 目录按 XDG 基础目录规范取得：$XDG_DATA_HOME（默认 ~/.local/share）在前，
+// 其后是 $XDG_DATA_DIRS（默认 /usr/local/share:/usr/share）。同一桌面文件 ID
+// （相对 applications 的路径）只取最先找到的，用户目录中的文件覆盖系统的同名文件。
+// 图标在扫描时按图标主题查找，图像在补全阶段读取；启动时交给 gio launch。
+
+use crate::cancel::ScanToken;
+use crate::desktop_entry::{DesktopEntry, IconThemes};
+use crate::shortcut::get_file_metadata;
+use crate::sources::scanner::{IconScanner, WatchTarget};
+use crate::types::*;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+const SOURCE_NAME: &str = "应用程序 (XDG)";
+
+pub struct XdgScanner;
+
+impl IconScanner for XdgScanner {
+    fn id(&self) -> &str {
+        "xdg_apps"
+    }
+    fn name(&self) -> &str {
+        "应用程序 (XDG)"
+    }
+    fn description(&self) -> &str {
+        "~/.local/share/applications 与 /usr/share/applications 等目录中的 .desktop 程序"
+    }
+    fn icon(&self) -> &str {
+        "🐧"
+    }
+    fn default_priority(&self) -> i32 {
+        60
+    }
+    fn watch_targets(&self) -> Vec<WatchTarget> {
+        application_dirs()
+            .into_iter()
+            .map(WatchTarget::Path)
+            .collect()
+    }
+    fn scan(
+        &self,
+        _method: Option<&str>,
+        token: &ScanToken,
+    ) -> Result<Vec<DesktopIcon>, Box<dyn Error>> {
+        Ok(get_xdg_icons(token))
+    }
+}
+
+/// XDG 数据目录，用户目录在前
+fn data_dirs() -> Vec<PathBuf> {
+    let env_dirs = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| {
+                v.split(':')
+                    .filter(|d| !d.is_empty())
+                    .map(PathBuf::from)
+                    .collect::<Vec<_>>()
+            })
+    };
+    let mut dirs: Vec<PathBuf> = env_dirs("XDG_DATA_HOME")
+        .or_else(|| dirs::home_dir().map(|home| vec![home.join(".local/share")]))
+        .unwrap_or_default();
+    dirs.extend(env_dirs("XDG_DATA_DIRS").unwrap_or_else(|| {
+        vec![
+            PathBuf::from("/usr/local/share"),
+            PathBuf::from("/usr/share"),
+        ]
+    }));
+    let mut seen = HashSet::new();
+    dirs.retain(|dir| seen.insert(dir.clone()));
+    dirs
+}
+
+/// 各数据目录下存在的 applications 目录
+fn application_dirs() -> Vec<PathBuf> {
+    data_dirs()
+        .into_iter()
+        .map(|dir| dir.join("applications"))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// 读取各 applications 目录中的 .desktop 程序
+pub fn get_xdg_icons(token: &ScanToken) -> Vec<DesktopIcon> {
+    let scan_start = std::time::Instant::now();
+    let mut ids = HashSet::new();
+    let mut files: Vec<PathBuf> = Vec::new();
+    for dir in application_dirs() {
+        let found = WalkDir::new(&dir)
+            .into_iter()
+            .take_while(|_| !token.should_stop())
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().is_some_and(|e| e == "desktop"));
+        for path in found {
+            // 桌面文件 ID 相同时前面的目录优先
+            if let Ok(id) = path.strip_prefix(&dir) {
+                if ids.insert(id.to_path_buf()) {
+                    files.push(path);
+                }
+            }
+        }
+    }
+    println!(
+        "🔍 [扫描阶段] {} 扫描完成, 找到 {} 个 .desktop 文件, 耗时: {:.3}s",
+        SOURCE_NAME,
+        files.len(),
+        scan_start.elapsed().as_secs_f64()
+    );
+
+    let prepare_start = std::time::Instant::now();
+    let data_dirs = data_dirs();
+    let mut icon_dirs: Vec<PathBuf> = dirs::home_dir()
+        .map(|home| home.join(".icons"))
+        .into_iter()
+        .collect();
+    icon_dirs.extend(data_dirs.iter().map(|dir| dir.join("icons")));
+    let themes = IconThemes::new(
+        &icon_dirs,
+        data_dirs.iter().map(|dir| dir.join("pixmaps")).collect(),
+    );
+    let results: Vec<DesktopIcon> = files
+        .par_iter()
+        .filter(|_| !token.should_stop())
+        .filter_map(|path| {
+            let text = std::fs::read_to_string(path).ok()?;
+            let entry = DesktopEntry::parse(&text)?;
+            Some(create_xdg_icon(path, entry, &themes))
+        })
+        .collect();
+    println!(
+        "🧩 [准备阶段] {} 已准备 {} 个条目, 耗时: {:.3}s",
+        SOURCE_NAME,
+        results.len(),
+        prepare_start.elapsed().as_secs_f64()
+    );
+    results
+}
+
+fn create_xdg_icon(path: &Path, entry: DesktopEntry, themes: &IconThemes) -> DesktopIcon {
+    let file_path = path.to_string_lossy().to_string();
+    let file_meta = get_file_metadata(path);
+    let icon_source_path = entry
+        .icon
+        .as_deref()
+        .and_then(|name| themes.find(Path::new("/"), name))
+        .map(|p| p.to_string_lossy().to_string());
+    DesktopIcon {
+        name: entry.name,
+        icon_base64: String::new(),
+        target_path: file_path.clone(),
+        file_path,
+        icon_width: 32,
+        icon_height: 32,
+        icon_source_index: icon_source_path.as_ref().map(|_| 0),
+        icon_source_path,
+        created_time: file_meta.created_time,
+        modified_time: file_meta.modified_time,
+        accessed_time: file_meta.accessed_time,
+        file_size: file_meta.file_size,
+        file_type: file_meta.file_type,
+        description: entry.comment,
+        arguments: Some(entry.exec),
+        working_directory: entry.path,
+        source_name: Some(SOURCE_NAME.to_string()),
+        run_in_terminal: entry.terminal,
+        aliases: (!entry.keywords.is_empty()).then_some(entry.keywords),
+        ..Default::default()
+    }
+}
+
+/// 启动条目：.desktop 文件交给 gio launch（处理 Exec、Path 与 Terminal），
+/// 系统没有 gio 时在 Path 目录中通过 sh 运行 Exec；其余文件交给 xdg-open
+pub fn launch_entry(icon: &DesktopIcon) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(&icon.file_path);
+    let is_desktop_file = path.extension().is_some_and(|e| e == "desktop");
+    if !is_desktop_file {
+        Command::new("xdg-open")
+            .arg(path)
+            .spawn()
+            .map_err(|e| format!("无法运行 xdg-open: {}", e))?;
+        return Ok(());
+    }
+
+    match Command::new("gio").arg("launch").arg(path).spawn() {
+        Ok(_) => return Ok(()),
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            return Err(format!("无法运行 gio: {}", e).into());
+        }
+        Err(_) => {}
+    }
+    let exec = icon
+        .arguments
+        .as_deref()
+        .filter(|e| !e.trim().is_empty())
+        .ok_or("条目没有 Exec 命令")?;
+    let mut command = Command::new("sh");
+    command.args(["-c", exec]);
+    if let Some(dir) = icon
+        .working_directory
+        .as_deref()
+        .filter(|d| Path::new(d).is_dir())
+    {
+        command.current_dir(dir);
+    }
+    command
+        .spawn()
+        .map_err(|e| format!("无法运行 {}: {}", exec, e))?;
+    Ok(())
+}
@@ -0,0 +1,247 @@
+// 流式扫描 - 通过 Tauri 通道逐步发送扫描结果与各阶段进度
+//
+// 各来源扫描完成即发送其条目；合并去重并补全可执行信息后发送完整列表（尚无图标）；
+// 图标分批提取（每个图标源受图标提取超时限制），每批完成后发送图标更新。进度阶段与日志阶段对应：
+// scanning（🔍 扫描）、deduping（✅ 汇总）、extracting（🖼️ 提取）。
+// 扫描开始时发送可用于取消的扫描 ID，结束时发送扫描报告（失败、超时的来源等）。
+
+use crate::cancel::ScanToken;
+use crate::catalog::{combine_sources, record_catalog};
+use crate::sources::scanner::{IconScanner, ScanStatus};
+use crate::sources::{fill_executable_info, fill_icons, run_scanners_with};
+use crate::types::DesktopIcon;
+use serde::Serialize;
+use tauri::ipc::Channel;
+
+// 每批提取的条目数
+const EXTRACT_BATCH: usize = 64;
+
+/// 扫描阶段
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanPhase {
+    /// 各来源扫描，进度按来源计
+    Scanning,
+    /// 合并去重，进度按条目计
+    Deduping,
+    /// 读取可执行文件的头部、版本资源、清单与签名，进度按目标计
+    Inspecting,
+    /// 提取图标，进度按条目计
+    Extracting,
+}
+
+/// 图标提取结果，按位置对应到 Merged 事件发送的列表（file_path 可能重复）
+#[derive(Debug, Clone, Serialize)]
+pub struct IconUpdate {
+    pub index: usize,
+    pub icon_base64: String,
+    pub icon_width: u32,
+    pub icon_height: u32,
+}
+
+/// 未正常完成的来源
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceFailure {
+    pub source: String,
+    pub status: ScanStatus,
+}
+
+/// 扫描报告，随 finished 事件发送并作为命令的返回值
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanReport {
+    pub scan_id: u64,
+    /// 最终条目数
+    pub total: usize,
+    pub elapsed_ms: u64,
+    /// 扫描被取消，结果不完整且未记录为当前目录
+    pub cancelled: bool,
+    /// 失败或超时的来源
+    pub failures: Vec<SourceFailure>,
+    /// 图标提取超时的条目数
+    pub extract_timed_out: usize,
+    /// 读取可执行信息超时的目标数
+    pub info_timed_out: usize,
+}
+
+/// 通过通道发送的扫描事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum ScanEvent {
+    /// 扫描开始，scan_id 可用于取消
+    Started {
+        scan_id: u64,
+    },
+    Progress {
+        phase: ScanPhase,
+        done: usize,
+        total: usize,
+    },
+    /// 某个来源扫描结束（未去重，尚无图标），失败或超时时条目为空
+    Source {
+        source: String,
+        icons: Vec<DesktopIcon>,
+        status: ScanStatus,
+    },
+    /// 合并去重后的完整列表，替换此前各来源发送的条目
+    Merged {
+        icons: Vec<DesktopIcon>,
+    },
+    /// 一批图标提取完成
+    Icons {
+        icons: Vec<IconUpdate>,
+    },
+    Finished(ScanReport),
+}
+
+/// 执行扫描并逐步发送结果，正常结束时结果记录为当前目录；返回扫描报告
+pub fn stream_scan(
+    scanners: Vec<Box<dyn IconScanner>>,
+    merge: bool,
+    method: Option<&str>,
+    token: &ScanToken,
+    channel: &Channel<ScanEvent>,
+) -> ScanReport {
+    let start = std::time::Instant::now();
+    let send = |event: ScanEvent| {
+        if let Err(e) = channel.send(event) {
+            eprintln!("!!! 发送扫描事件失败: {}", e);
+        }
+    };
+    let mut report = ScanReport {
+        scan_id: token.id(),
+        ..Default::default()
+    };
+    let finish = |mut report: ScanReport| {
+        report.cancelled = token.is_cancelled();
+        report.elapsed_ms = start.elapsed().as_millis() as u64;
+        if report.cancelled {
+            println!("⛔ [取消] 扫描 {} 已取消", report.scan_id);
+        }
+        send(ScanEvent::Finished(report.clone()));
+        report
+    };
+    send(ScanEvent::Started {
+        scan_id: token.id(),
+    });
+
+    // 扫描阶段：各来源结束即发送
+    let source_count = scanners.len();
+    let mut scanned = 0;
+    send(ScanEvent::Progress {
+        phase: ScanPhase::Scanning,
+        done: 0,
+        total: source_count,
+    });
+    let results = run_scanners_with(scanners, method, token, |source, icons, status| {
+        send(ScanEvent::Source {
+            source: source.to_string(),
+            icons: icons.to_vec(),
+            status: status.clone(),
+        });
+        scanned += 1;
+        send(ScanEvent::Progress {
+            phase: ScanPhase::Scanning,
+            done: scanned,
+            total: source_count,
+        });
+    });
+    let mut sources = Vec::with_capacity(results.len());
+    for (source, icons, status) in results {
+        if matches!(status, ScanStatus::Failed(_) | ScanStatus::TimedOut) {
+            report.failures.push(SourceFailure {
+                source: source.clone(),
+                status,
+            });
+        }
+        sources.push((source, icons));
+    }
+    if token.is_cancelled() {
+        return finish(report);
+    }
+
+    // 汇总阶段：合并去重，再补全可执行信息（按目标报告进度）
+    let found: usize = sources.iter().map(|(_, icons)| icons.len()).sum();
+    send(ScanEvent::Progress {
+        phase: ScanPhase::Deduping,
+        done: 0,
+        total: found,
+    });
+    let raw = combine_sources(&sources, merge);
+    let mut icons = raw.clone();
+    println!(
+        "✅ [汇总阶段] 共找到 {} 个条目, 去重后 {} 个, 耗时: {:.3}s",
+        found,
+        icons.len(),
+        start.elapsed().as_secs_f64()
+    );
+    send(ScanEvent::Progress {
+        phase: ScanPhase::Deduping,
+        done: found,
+        total: found,
+    });
+    report.info_timed_out = fill_executable_info(&mut icons, token, |done, total| {
+        send(ScanEvent::Progress {
+            phase: ScanPhase::Inspecting,
+            done,
+            total,
+        });
+    });
+    if token.is_cancelled() {
+        return finish(report);
+    }
+    send(ScanEvent::Merged {
+        icons: icons.clone(),
+    });
+
+    // 提取阶段：图标源相同的条目排在一起分批提取，同一图标源只提取一次
+    let mut order: Vec<usize> = (0..icons.len()).collect();
+    order.sort_by_cached_key(|&i| {
+        let icon = &icons[i];
+        (
+            icon.icon_source_path
+                .clone()
+                .unwrap_or_else(|| icon.file_path.clone()),
+            icon.icon_source_index,
+        )
+    });
+    let mut extracted = 0;
+    send(ScanEvent::Progress {
+        phase: ScanPhase::Extracting,
+        done: 0,
+        total: icons.len(),
+    });
+    for batch in order.chunks(EXTRACT_BATCH) {
+        let mut filled: Vec<DesktopIcon> = batch.iter().map(|&i| icons[i].clone()).collect();
+        // 超时的图标源对应的条目保留没有图标的状态
+        report.extract_timed_out += fill_icons(&mut filled, method, token);
+        if token.is_cancelled() {
+            return finish(report);
+        }
+        let updates = batch
+            .iter()
+            .zip(filled)
+            .filter(|(_, icon)| !icon.icon_base64.is_empty())
+            .map(|(&i, icon)| {
+                let update = IconUpdate {
+                    index: i,
+                    icon_base64: icon.icon_base64.clone(),
+                    icon_width: icon.icon_width,
+                    icon_height: icon.icon_height,
+                };
+                icons[i] = icon;
+                update
+            })
+            .collect();
+        extracted += batch.len();
+        send(ScanEvent::Icons { icons: updates });
+        send(ScanEvent::Progress {
+            phase: ScanPhase::Extracting,
+            done: extracted,
+            total: icons.len(),
+        });
+    }
+
+    report.total = icons.len();
+    record_catalog(sources, merge, method, raw, &icons);
+    finish(report)
+}
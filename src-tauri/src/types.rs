@@ -1,6 +1,7 @@
 // 类型定义和结构体
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 #[cfg(target_os = "windows")]
 use windows::core::BOOL;
 #[cfg(target_os = "windows")]
@@ -19,7 +20,7 @@ pub struct IconData {
     pub height: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct DesktopIcon {
     pub name: String,
     pub icon_base64: String,
@@ -46,6 +47,72 @@ pub struct DesktopIcon {
     pub hotkey: Option<String>,            // 快捷键
     pub show_command: Option<String>,      // 运行方式（正常、最小化、最大化）
     pub source_name: Option<String>,       // 软件来源名称（如：用户桌面、开始菜单等）
+    #[serde(default)]
+    pub source_names: Vec<String>, // 合并后找到该条目的全部来源名称
+
+    // 可执行文件版本资源信息（VS_VERSIONINFO）
+    #[serde(default)]
+    pub file_description: Option<String>, // 文件描述
+    #[serde(default)]
+    pub product_name: Option<String>, // 产品名称
+    #[serde(default)]
+    pub company_name: Option<String>, // 公司名称
+    #[serde(default)]
+    pub file_version: Option<String>, // 文件版本
+    #[serde(default)]
+    pub product_version: Option<String>, // 产品版本
+    #[serde(default)]
+    pub version_strings: Option<BTreeMap<String, BTreeMap<String, String>>>, // 各语言的 StringFileInfo 表，键如 "080404b0"
+    #[serde(default)]
+    pub publisher: Option<String>, // 登记的发布者（卸载项、ClickOnce）
+    #[serde(default)]
+    pub signer: Option<String>, // 签名者：签名证书主题中的 CN（没有时取 O），signature_status 不是 verified 时未经校验
+    #[serde(default)]
+    pub signature_status: Option<String>, // 签名状态: verified/untrusted/digest_match/hash_mismatch/unsigned/invalid/unsupported
+    #[serde(default)]
+    pub signer_subject: Option<String>, // 签名证书主题
+    #[serde(default)]
+    pub signer_issuer: Option<String>, // 签名证书颁发者
+    #[serde(default)]
+    pub architecture: Option<String>, // 目标架构: x86/x64/ARM64/ARM64EC/ARM64X
+    #[serde(default)]
+    pub subsystem: Option<String>, // 子系统: gui/console/native/efi/dos/other
+    #[serde(default)]
+    pub run_in_terminal: bool, // 控制台程序，需要在终端中启动
+    #[serde(default)]
+    pub execution_level: Option<String>, // 清单中的 requestedExecutionLevel
+    #[serde(default)]
+    pub requires_elevation: bool, // 启动时需要管理员权限（UAC）
+    #[serde(default)]
+    pub dpi_awareness: Option<String>, // 清单中的 dpiAwareness/dpiAware
+    #[serde(default)]
+    pub supported_os: Option<Vec<String>>, // 清单中声明支持的系统版本
+    #[serde(default)]
+    pub deployment_url: Option<String>, // ClickOnce 部署清单地址
+    #[serde(default)]
+    pub display_version: Option<String>, // 卸载项中的 DisplayVersion
+    #[serde(default)]
+    pub install_date: Option<String>, // 安装日期 YYYY-MM-DD
+    #[serde(default)]
+    pub estimated_size: Option<u64>, // 卸载项估计占用空间（KB）
+    #[serde(default)]
+    pub url_info_about: Option<String>, // 产品主页
+    #[serde(default)]
+    pub uninstall_string: Option<String>, // 卸载命令
+    #[serde(default)]
+    pub quiet_uninstall_string: Option<String>, // 静默卸载命令
+    #[serde(default)]
+    pub aliases: Option<Vec<String>>, // 搜索别名，如 App Paths 中登记的 chrome.exe
+    #[serde(default)]
+    pub app_path_dirs: Option<String>, // App Paths 中的 Path 值（启动时加在 PATH 前面的目录）
+    #[serde(default)]
+    pub wine_prefix: Option<String>, // 所属的 Wine 前缀，通过 wine start 启动
+    #[serde(default)]
+    pub wsl_distro: Option<String>, // 所属的 WSL 发行版，通过 wsl.exe 运行 arguments 中的命令
+    #[serde(default)]
+    pub bundle_id: Option<String>, // macOS 应用的 CFBundleIdentifier
+    #[serde(default)]
+    pub app_user_model_id: Option<String>, // AppUserModelID：快捷方式属性中设置的，或应用商店应用的 ID
 }
 
 /// 检查路径是否为 URL
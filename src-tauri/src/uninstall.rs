@@ -0,0 +1,470 @@
+// 卸载模块 - 确定条目对应的已安装产品，并调用系统的卸载方式
+//
+// Windows 按注册表 Uninstall 项的卸载命令或 msiexec 卸载；
+// macOS 把应用程序包移到废纸篓；Linux 交给拥有该文件的包管理器。
+// 查找到的卸载操作按 ID 登记，用户确认后按 ID 执行同一个操作，不再重新查找。
+
+use crate::types::DesktopIcon;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+#[cfg(not(target_os = "windows"))]
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// 条目对应的已安装产品与将要执行的卸载操作，供用户确认
+#[derive(Debug, Clone, Serialize)]
+pub struct UninstallTarget {
+    /// 登记的 ID，确认后以此执行卸载
+    pub id: u64,
+    /// 产品名称（注册表 DisplayName、应用程序包名称或软件包名）
+    pub product: String,
+    pub version: Option<String>,
+    pub publisher: Option<String>,
+    /// 卸载方式：msi、quiet_uninstall_string、uninstall_string、trash、dpkg、rpm、flatpak、snap
+    pub method: String,
+    /// 将要执行的命令，仅用于展示
+    pub command: String,
+    #[serde(skip)]
+    program: String,
+    #[serde(skip)]
+    arguments: Vec<String>,
+}
+
+/// 卸载结果
+#[derive(Debug, Clone, Serialize)]
+pub struct UninstallOutcome {
+    pub product: String,
+    pub method: String,
+    /// 卸载程序的退出码，被信号终止时为空
+    pub exit_code: Option<i32>,
+    pub success: bool,
+}
+
+static NEXT_TARGET_ID: AtomicU64 = AtomicU64::new(1);
+static TARGETS: OnceLock<Mutex<HashMap<u64, UninstallTarget>>> = OnceLock::new();
+
+fn targets() -> &'static Mutex<HashMap<u64, UninstallTarget>> {
+    TARGETS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 查找条目对应的卸载操作并登记，返回的 ID 用于确认后执行
+pub fn prepare_uninstall(icon: &DesktopIcon) -> Result<UninstallTarget, Box<dyn Error>> {
+    let mut target = find_uninstall_target(icon)?;
+    target.id = NEXT_TARGET_ID.fetch_add(1, Ordering::Relaxed);
+    targets()
+        .lock()
+        .map_err(|_| "卸载登记表不可用")?
+        .insert(target.id, target.clone());
+    Ok(target)
+}
+
+/// 执行已确认的卸载操作（每个 ID 只执行一次），等待卸载程序结束并返回退出状态；
+/// command 须与确认时展示的命令一致
+pub fn uninstall_target(id: u64, command: &str) -> Result<UninstallOutcome, Box<dyn Error>> {
+    let target = targets()
+        .lock()
+        .map_err(|_| "卸载登记表不可用")?
+        .remove(&id)
+        .ok_or("卸载操作不存在或已执行，请重新确认")?;
+    if target.command != command {
+        return Err("卸载命令与确认的不一致，请重新确认".into());
+    }
+    println!(
+        "[卸载] {} ({}): {}",
+        target.product, target.method, target.command
+    );
+    let exit_code = run_uninstaller(&target)?;
+    println!("[卸载] {} 退出码: {:?}", target.product, exit_code);
+    Ok(UninstallOutcome {
+        success: is_success(&target.method, exit_code),
+        product: target.product,
+        method: target.method,
+        exit_code,
+    })
+}
+
+/// msiexec 以 3010/1641 表示卸载成功但需要重启
+fn is_success(method: &str, exit_code: Option<i32>) -> bool {
+    match exit_code {
+        Some(0) => true,
+        Some(3010) | Some(1641) => method == "msi",
+        _ => false,
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub use windows_impl::find_uninstall_target;
+
+#[cfg(target_os = "windows")]
+fn run_uninstaller(target: &UninstallTarget) -> Result<Option<i32>, Box<dyn Error>> {
+    let parameters = target.arguments.join(" ");
+    let parameters = (!parameters.is_empty()).then_some(parameters.as_str());
+    let exit_code = crate::launch::shell_execute_wait(&target.program, parameters)?;
+    Ok(Some(exit_code as i32))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn run_uninstaller(target: &UninstallTarget) -> Result<Option<i32>, Box<dyn Error>> {
+    let status = std::process::Command::new(&target.program)
+        .args(&target.arguments)
+        .status()
+        .map_err(|e| format!("无法执行 {}: {}", target.program, e))?;
+    Ok(status.code())
+}
+
+#[cfg(target_os = "windows")]
+mod windows_impl {
+    use super::UninstallTarget;
+    use crate::registry;
+    use crate::registry::uninstall::{read_uninstall_entries, visible_entries, UninstallEntry};
+    use crate::types::DesktopIcon;
+    use std::error::Error;
+
+    /// 在"程序和功能"的卸载项中查找条目对应的产品
+    pub fn find_uninstall_target(icon: &DesktopIcon) -> Result<UninstallTarget, Box<dyn Error>> {
+        if icon.file_type.as_deref() == Some("UWP App") {
+            return Err("应用商店应用请在系统设置中卸载".into());
+        }
+        let registry = registry::system();
+        let entries = visible_entries(registry, read_uninstall_entries(registry));
+        let entry = match_entry(icon, &entries).ok_or("未找到对应的已安装程序")?;
+        create_target(entry)
+    }
+
+    /// 依次按卸载命令、DisplayIcon、安装目录与卸载程序所在目录、名称匹配
+    fn match_entry<'a>(
+        icon: &DesktopIcon,
+        entries: &'a [UninstallEntry],
+    ) -> Option<&'a UninstallEntry> {
+        // 来自已安装程序来源的条目自带卸载命令
+        if let Some(uninstall) = &icon.uninstall_string {
+            if let Some(entry) = entries
+                .iter()
+                .find(|e| e.uninstall_string.as_ref() == Some(uninstall))
+            {
+                return Some(entry);
+            }
+        }
+
+        let target = clean_path(&icon.target_path);
+        if !target.is_empty() {
+            if let Some(entry) = entries.iter().find(|e| {
+                e.display_icon
+                    .as_deref()
+                    .is_some_and(|p| clean_path(strip_icon_index(p)) == target)
+            }) {
+                return Some(entry);
+            }
+
+            // 目标位于多个目录之下时取最深的一个
+            let containing = entries
+                .iter()
+                .filter_map(|e| {
+                    let uninstaller = e
+                        .uninstall_string
+                        .as_deref()
+                        .map(|c| split_command_line(c).0)
+                        .filter(|p| !is_system_uninstaller(p))
+                        .and_then(|p| parent_dir(&clean_path(&p)));
+                    [e.install_location.as_deref().map(clean_path), uninstaller]
+                        .into_iter()
+                        .flatten()
+                        .filter(|dir| {
+                            is_specific_dir(dir) && target.starts_with(&format!("{}\\", dir))
+                        })
+                        .map(|dir| dir.len())
+                        .max()
+                        .map(|depth| (depth, e))
+                })
+                .max_by_key(|(depth, _)| *depth)
+                .map(|(_, e)| e);
+            if containing.is_some() {
+                return containing;
+            }
+        }
+
+        let name = icon.name.trim();
+        entries
+            .iter()
+            .find(|e| e.display_name.trim().eq_ignore_ascii_case(name))
+    }
+
+    fn create_target(entry: &UninstallEntry) -> Result<UninstallTarget, Box<dyn Error>> {
+        let key_name = entry.registry_key.rsplit('\\').next().unwrap_or_default();
+        // MSI 产品的卸载项名称即产品代码，UninstallString 常为修复用的 /I
+        let (method, program, parameters) = if entry.windows_installer && is_guid(key_name) {
+            ("msi", "msiexec.exe".to_string(), format!("/x {}", key_name))
+        } else if let Some(command) = &entry.quiet_uninstall_string {
+            let (program, parameters) = split_command_line(command);
+            ("quiet_uninstall_string", program, parameters)
+        } else if let Some(command) = &entry.uninstall_string {
+            let (program, parameters) = split_command_line(command);
+            ("uninstall_string", program, parameters)
+        } else {
+            return Err(format!("{} 没有登记卸载命令", entry.display_name).into());
+        };
+
+        let command = format!("\"{}\" {}", program, parameters).trim().to_string();
+        Ok(UninstallTarget {
+            id: 0,
+            product: entry.display_name.clone(),
+            version: entry.display_version.clone(),
+            publisher: entry.publisher.clone(),
+            method: method.to_string(),
+            command,
+            program,
+            arguments: if parameters.is_empty() {
+                Vec::new()
+            } else {
+                vec![parameters]
+            },
+        })
+    }
+
+    /// 拆分命令行为程序与参数；未加引号的路径可能含空格，按 .exe 结尾切分
+    fn split_command_line(command: &str) -> (String, String) {
+        let command = command.trim();
+        if let Some(rest) = command.strip_prefix('"') {
+            if let Some(end) = rest.find('"') {
+                return (rest[..end].to_string(), rest[end + 1..].trim().to_string());
+            }
+        }
+        if let Some(pos) = command.to_ascii_lowercase().find(".exe") {
+            let end = pos + 4;
+            return (
+                command[..end].to_string(),
+                command[end..].trim().to_string(),
+            );
+        }
+        match command.split_once(' ') {
+            Some((program, parameters)) => (program.to_string(), parameters.trim().to_string()),
+            None => (command.to_string(), String::new()),
+        }
+    }
+
+    /// 卸载命令由系统程序（msiexec、rundll32 等）执行时，其目录不能说明安装位置
+    fn is_system_uninstaller(program: &str) -> bool {
+        let program = clean_path(program);
+        !program.contains('\\') || program.contains("\\windows\\")
+    }
+
+    /// 排除驱动器根目录与 Program Files 这类过浅的目录，避免误匹配
+    fn is_specific_dir(dir: &str) -> bool {
+        dir.matches('\\').count() >= 2
+    }
+
+    fn parent_dir(path: &str) -> Option<String> {
+        path.rsplit_once('\\').map(|(dir, _)| dir.to_string())
+    }
+
+    /// DisplayIcon 形如 "C:\app\app.exe",0
+    fn strip_icon_index(path: &str) -> &str {
+        let path = path.trim();
+        match path.rsplit_once(',') {
+            Some((file, index)) if index.trim().parse::<i32>().is_ok() => file,
+            _ => path,
+        }
+    }
+
+    fn clean_path(path: &str) -> String {
+        crate::path::expand_env_vars(path.trim().trim_matches('"'))
+            .replace('/', "\\")
+            .trim_end_matches('\\')
+            .to_lowercase()
+    }
+
+    fn is_guid(name: &str) -> bool {
+        name.len() == 38 && name.starts_with('{') && name.ends_with('}')
+    }
+}
+
+/// 把应用程序包移到废纸篓（通过 Finder，以便可以从废纸篓放回）
+#[cfg(target_os = "macos")]
+pub fn find_uninstall_target(icon: &DesktopIcon) -> Result<UninstallTarget, Box<dyn Error>> {
+    let bundle = [&icon.target_path, &icon.file_path]
+        .into_iter()
+        .find_map(|p| app_bundle(Path::new(p)))
+        .ok_or("未找到对应的应用程序包")?;
+    if bundle.starts_with("/System") {
+        return Err("系统应用不能卸载".into());
+    }
+
+    let info = plist::Value::from_file(bundle.join("Contents/Info.plist")).ok();
+    let read = |key: &str| {
+        info.as_ref()
+            .and_then(|v| v.as_dictionary())
+            .and_then(|d| d.get(key))
+            .and_then(|v| v.as_string())
+            .filter(|s| !s.trim().is_empty())
+            .map(str::to_string)
+    };
+    let product = read("CFBundleDisplayName")
+        .or_else(|| read("CFBundleName"))
+        .or_else(|| bundle.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .unwrap_or_else(|| icon.name.clone());
+
+    let path = bundle.to_string_lossy().to_string();
+    let script = format!(
+        "tell application \"Finder\" to delete (POSIX file \"{}\")",
+        path.replace('\\', "\\\\").replace('"', "\\\"")
+    );
+    Ok(UninstallTarget {
+        id: 0,
+        product,
+        version: read("CFBundleShortVersionString"),
+        publisher: None,
+        method: "trash".to_string(),
+        command: format!("移到废纸篓: {}", path),
+        program: "osascript".to_string(),
+        arguments: vec!["-e".to_string(), script],
+    })
+}
+
+/// 路径所在的最外层 .app 包（应用内嵌的辅助应用属于外层应用）
+#[cfg(target_os = "macos")]
+fn app_bundle(path: &Path) -> Option<std::path::PathBuf> {
+    path.ancestors()
+        .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("app")))
+        .last()
+        .map(Path::to_path_buf)
+}
+
+/// 查找拥有条目文件的软件包：flatpak、snap、dpkg、rpm
+#[cfg(target_os = "linux")]
+pub fn find_uninstall_target(icon: &DesktopIcon) -> Result<UninstallTarget, Box<dyn Error>> {
+    let paths: Vec<&str> = [icon.file_path.as_str(), icon.target_path.as_str()]
+        .into_iter()
+        .filter(|p| Path::new(p).is_absolute())
+        .collect();
+    paths
+        .iter()
+        .find_map(|p| flatpak_target(p).or_else(|| snap_target(p)))
+        .or_else(|| {
+            paths
+                .iter()
+                .find_map(|p| dpkg_target(p).or_else(|| rpm_target(p)))
+        })
+        .ok_or_else(|| "未找到拥有该程序的软件包".into())
+}
+
+/// .../flatpak/exports/share/applications/<应用ID>.desktop 或 .../flatpak/app/<应用ID>/...
+#[cfg(target_os = "linux")]
+fn flatpak_target(path: &str) -> Option<UninstallTarget> {
+    let (root, rest) = path.split_once("/flatpak/")?;
+    let app_id = if let Some(file) = rest.strip_prefix("exports/share/applications/") {
+        file.strip_suffix(".desktop")?.to_string()
+    } else {
+        rest.strip_prefix("app/")?.split('/').next()?.to_string()
+    };
+    // 安装在用户目录下的应用属于 --user 安装
+    let home = std::env::var("HOME").unwrap_or_default();
+    let scope = if !home.is_empty() && root.starts_with(&home) {
+        "--user"
+    } else {
+        "--system"
+    };
+    let arguments = vec![
+        "uninstall".to_string(),
+        "-y".to_string(),
+        scope.to_string(),
+        app_id.clone(),
+    ];
+    Some(package_target(
+        app_id, None, "flatpak", "flatpak", arguments,
+    ))
+}
+
+/// /snap/<名称>/... 或 /var/lib/snapd/desktop/applications/<名称>_<应用>.desktop
+#[cfg(target_os = "linux")]
+fn snap_target(path: &str) -> Option<UninstallTarget> {
+    let name = if let Some(rest) = path.strip_prefix("/snap/") {
+        rest.split('/').next()?
+    } else {
+        let file = path.strip_prefix("/var/lib/snapd/desktop/applications/")?;
+        file.split('_').next()?
+    };
+    if name.is_empty() || name == "bin" {
+        return None;
+    }
+    let arguments = vec!["snap".to_string(), "remove".to_string(), name.to_string()];
+    Some(package_target(
+        name.to_string(),
+        None,
+        "snap",
+        "pkexec",
+        arguments,
+    ))
+}
+
+/// dpkg-query -S 输出 "软件包[:架构][, 软件包]: 路径"
+///
+/// /usr 合并后 /bin 链接到 /usr/bin，但软件包仍按安装时的路径登记，两种写法都要查询。
+#[cfg(target_os = "linux")]
+fn dpkg_target(path: &str) -> Option<UninstallTarget> {
+    let merged = path.strip_prefix("/usr").filter(|rest| {
+        ["/bin/", "/sbin/", "/lib/"]
+            .iter()
+            .any(|d| rest.starts_with(d))
+    });
+    let owner = query("dpkg-query", &["-S", path])
+        .or_else(|| merged.and_then(|rest| query("dpkg-query", &["-S", rest])))?;
+    let package = owner
+        .lines()
+        .next()?
+        .split_once(": ")?
+        .0
+        .split(", ")
+        .next()?
+        .to_string();
+    let version = query("dpkg-query", &["-W", "-f=${Version}", &package]);
+    let arguments = vec!["dpkg".to_string(), "--remove".to_string(), package.clone()];
+    Some(package_target(
+        package, version, "dpkg", "pkexec", arguments,
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn rpm_target(path: &str) -> Option<UninstallTarget> {
+    let output = query(
+        "rpm",
+        &["-qf", "--queryformat", "%{NAME}\\n%{VERSION}", path],
+    )?;
+    let mut lines = output.lines();
+    let package = lines.next()?.trim().to_string();
+    let version = lines.next().map(str::to_string);
+    let arguments = vec!["rpm".to_string(), "-e".to_string(), package.clone()];
+    Some(package_target(package, version, "rpm", "pkexec", arguments))
+}
+
+#[cfg(target_os = "linux")]
+fn package_target(
+    product: String,
+    version: Option<String>,
+    method: &str,
+    program: &str,
+    arguments: Vec<String>,
+) -> UninstallTarget {
+    UninstallTarget {
+        id: 0,
+        product,
+        version,
+        publisher: None,
+        method: method.to_string(),
+        command: format!("{} {}", program, arguments.join(" ")),
+        program: program.to_string(),
+        arguments,
+    }
+}
+
+/// 运行查询命令，成功且有输出时返回标准输出
+#[cfg(target_os = "linux")]
+fn query(program: &str, arguments: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(arguments)
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !text.is_empty()).then_some(text)
+}
@@ -0,0 +1,202 @@
+// 来源变化监视 - 监视当前目录各来源的文件夹与注册表键，变化时增量更新
+//
+// 文件夹通过 notify 监视，注册表键（Windows）各用一个线程等待变化通知。
+// 变化先汇总到工作线程，静默一段时间后只重新扫描受影响的来源，
+// 变化通过 catalog-changed 事件发送给前端。
+
+use crate::catalog::{catalog_sources, rescan_sources};
+use crate::sources::get_all_scanners;
+use crate::sources::scanner::WatchTarget;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 目录变化事件名
+pub const CATALOG_CHANGED_EVENT: &str = "catalog-changed";
+
+// 最后一次变化后静默多久再重新扫描，安装程序通常会连续写入大量文件
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+enum Change {
+    /// 文件夹中的路径发生变化
+    Path(PathBuf),
+    /// 来源的注册表键发生变化
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Source(String),
+}
+
+struct WatchHandle {
+    _watcher: RecommendedWatcher,
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+static WATCH: Mutex<Option<WatchHandle>> = Mutex::new(None);
+
+/// 开始监视当前目录的来源，已有的监视会先停止；返回监视目标的数量
+pub fn start_watch(app: AppHandle) -> Result<usize, Box<dyn std::error::Error>> {
+    stop_watch();
+
+    let source_ids = catalog_sources();
+    let (tx, rx) = channel::<Change>();
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let path_tx = tx.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            for path in event.paths {
+                let _ = path_tx.send(Change::Path(path));
+            }
+        }
+    })?;
+
+    // 文件夹根目录与所属来源
+    let mut roots: Vec<(PathBuf, String)> = Vec::new();
+    let mut count = 0;
+    for scanner in get_all_scanners() {
+        let id = scanner.id().to_string();
+        if !source_ids.contains(&id) {
+            continue;
+        }
+        for target in scanner.watch_targets() {
+            match target {
+                WatchTarget::Path(path) => {
+                    if !path.exists() {
+                        continue;
+                    }
+                    if let Err(e) = watcher.watch(&path, RecursiveMode::Recursive) {
+                        eprintln!("!!! 监视 {:?} 失败: {}", path, e);
+                        continue;
+                    }
+                    roots.push((path, id.clone()));
+                    count += 1;
+                }
+                WatchTarget::Registry(root, key) => {
+                    if watch_registry(root, key, id.clone(), tx.clone(), stop.clone()) {
+                        count += 1;
+                    }
+                }
+            }
+        }
+    }
+    drop(tx);
+
+    let worker_stop = stop.clone();
+    std::thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            if worker_stop.load(Ordering::Relaxed) {
+                break;
+            }
+            let mut changed = HashSet::new();
+            collect_source(first, &roots, &mut changed);
+            loop {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(change) => collect_source(change, &roots, &mut changed),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            if changed.is_empty() || worker_stop.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            let changed: Vec<String> = changed.into_iter().collect();
+            println!("👀 [监视阶段] 来源发生变化: {:?}", changed);
+            let Some(change) = rescan_sources(&changed) else {
+                continue;
+            };
+            println!(
+                "👀 [监视阶段] 新增 {} 个, 更新 {} 个, 删除 {} 个条目",
+                change.added.len(),
+                change.updated.len(),
+                change.removed.len()
+            );
+            if !change.is_empty() {
+                if let Err(e) = app.emit(CATALOG_CHANGED_EVENT, &change) {
+                    eprintln!("!!! 发送目录变化事件失败: {}", e);
+                }
+            }
+        }
+    });
+
+    println!(
+        "👀 [监视阶段] 开始监视 {} 个来源的 {} 个目标",
+        source_ids.len(),
+        count
+    );
+    if let Ok(mut watch) = WATCH.lock() {
+        *watch = Some(WatchHandle {
+            _watcher: watcher,
+            stop,
+        });
+    }
+    Ok(count)
+}
+
+/// 停止监视
+pub fn stop_watch() {
+    if let Ok(mut watch) = WATCH.lock() {
+        if watch.take().is_some() {
+            println!("👀 [监视阶段] 已停止监视");
+        }
+    }
+}
+
+/// 变化所属的来源，路径按最长的根目录匹配（如用户文件夹下的自定义目录）
+fn collect_source(change: Change, roots: &[(PathBuf, String)], changed: &mut HashSet<String>) {
+    match change {
+        Change::Source(id) => {
+            changed.insert(id);
+        }
+        Change::Path(path) => {
+            if let Some((_, id)) = roots
+                .iter()
+                .filter(|(root, _)| path.starts_with(root))
+                .max_by_key(|(root, _)| root.components().count())
+            {
+                changed.insert(id.clone());
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn watch_registry(
+    root: crate::registry::RegistryRoot,
+    key: String,
+    source_id: String,
+    tx: Sender<Change>,
+    stop: Arc<AtomicBool>,
+) -> bool {
+    std::thread::spawn(move || {
+        let found = crate::registry::live::watch_key(root, &key, &stop, || {
+            let _ = tx.send(Change::Source(source_id.clone()));
+        });
+        if !found {
+            eprintln!("!!! 监视注册表 {}\\{} 失败", root.name(), key);
+        }
+    });
+    true
+}
+
+/// 离线分区与其他平台没有本机注册表
+#[cfg(not(target_os = "windows"))]
+fn watch_registry(
+    _root: crate::registry::RegistryRoot,
+    _key: String,
+    _source_id: String,
+    _tx: Sender<Change>,
+    _stop: Arc<AtomicBool>,
+) -> bool {
+    false
+}
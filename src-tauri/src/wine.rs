@@ -0,0 +1,99 @@
+// Wine 前缀 - 查找本机的 Wine 前缀，并通过 wine start 启动其中的程序（Linux）
+//
+// 前缀来源：
+//   WINEPREFIX 环境变量、Wine 来源设置中的前缀与默认的 ~/.wine
+//   Bottles：~/.local/share/bottles/bottles/<名称>（含 Flatpak 版本的数据目录）
+//   Lutris：游戏配置 ~/.config/lutris/games/*.yml 中的 prefix
+
+use crate::config::scanner_setting;
+use crate::types::DesktopIcon;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 本机的全部 Wine 前缀（含 drive_c 的目录），按真实路径去重
+pub fn find_prefixes() -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Some(prefix) = std::env::var_os("WINEPREFIX").filter(|p| !p.is_empty()) {
+        candidates.push(PathBuf::from(prefix));
+    }
+    if let Some(prefix) =
+        scanner_setting::<String>("wine_apps", "prefix").filter(|p| !p.trim().is_empty())
+    {
+        candidates.push(PathBuf::from(prefix.trim()));
+    }
+    candidates.push(home.join(".wine"));
+
+    let bottles_dirs = [
+        dirs::data_dir().map(|d| d.join("bottles").join("bottles")),
+        Some(home.join(".var/app/com.usebottles.bottles/data/bottles/bottles")),
+    ];
+    for dir in bottles_dirs.into_iter().flatten() {
+        candidates.extend(subdirectories(&dir));
+    }
+
+    let lutris_dirs = [
+        dirs::config_dir().map(|d| d.join("lutris").join("games")),
+        dirs::data_dir().map(|d| d.join("lutris").join("games")),
+    ];
+    for dir in lutris_dirs.into_iter().flatten() {
+        candidates.extend(lutris_prefixes(&dir, &home));
+    }
+
+    let mut prefixes: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        if !candidate.join("drive_c").is_dir() {
+            continue;
+        }
+        let prefix = std::fs::canonicalize(&candidate).unwrap_or(candidate);
+        if !prefixes.contains(&prefix) {
+            prefixes.push(prefix);
+        }
+    }
+    prefixes
+}
+
+/// 在条目所属的前缀中运行，快捷方式交给 wine start 解析目标与参数
+pub fn launch_entry(icon: &DesktopIcon) -> Result<(), Box<dyn Error>> {
+    let prefix = icon.wine_prefix.as_deref().ok_or("条目不属于 Wine 前缀")?;
+    Command::new("wine")
+        .env("WINEPREFIX", prefix)
+        .args(["start", "/unix", &icon.file_path])
+        .spawn()
+        .map_err(|e| format!("无法运行 wine: {}", e))?;
+    Ok(())
+}
+
+fn subdirectories(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+/// 读取 Lutris 游戏配置中的 prefix 项（game 段下的 "prefix: 路径"）
+fn lutris_prefixes(dir: &Path, home: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "yml"))
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .flat_map(|text| {
+            text.lines()
+                .filter_map(|line| line.trim().strip_prefix("prefix:"))
+                .map(|value| value.trim().trim_matches(['"', '\'']).to_string())
+                .filter(|value| !value.is_empty())
+                .map(|value| match value.strip_prefix("~/") {
+                    Some(rest) => home.join(rest),
+                    None => PathBuf::from(value),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
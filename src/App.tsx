@@ -12,8 +12,13 @@ import { IconViewModal } from './components/IconViewModal';
 import { useAppIcons } from './hooks/useAppIcons';
 import { useWindowSize } from './hooks/useWindowSize';
 import { useConfigSync } from './sync/configSync.ts';
-import type { IconType } from './types/icon';
-import { openApplication, revealFile } from './utils/appUtils';
+import type { IconType, UninstallTarget } from './types/icon';
+import {
+  findUninstallTarget,
+  openApplication,
+  revealFile,
+  uninstallEntry,
+} from './utils/appUtils';
 
 const { Content } = Layout;
 
@@ -23,6 +28,7 @@ const { Content } = Layout;
  */
 const App: React.FC = () => {
   const [messageApi, contextHolder] = message.useMessage();
+  const [modalApi, modalContextHolder] = Modal.useModal();
 
   // 状态管理
   const [searchValue, setSearchValue] = useState('');
@@ -45,11 +51,47 @@ const App: React.FC = () => {
     hideList,
   } = config;
 
-  const { filteredIcons, loading, loadingTip, availableSources, desktopIcons } =
-    useAppIcons(searchValue, hideList);
+  const {
+    filteredIcons,
+    loading,
+    loadingTip,
+    availableSources,
+    desktopIcons,
+    scanReport,
+    reload,
+  } = useAppIcons(searchValue, hideList);
 
   const containerSize = useWindowSize(contentAreaRef, [tileSide]);
 
+  // 提示扫描失败或超时的来源，避免结果缺失却无从得知
+  useEffect(() => {
+    if (!scanReport) return;
+    const sourceName = (id: string) =>
+      availableSources.find((source) => source.id === id)?.name ?? id;
+    const timedOut = scanReport.failures
+      .filter((failure) => failure.status.kind === 'timed_out')
+      .map((failure) => sourceName(failure.source));
+    const failed = scanReport.failures
+      .filter((failure) => failure.status.kind === 'failed')
+      .map((failure) => sourceName(failure.source));
+    if (timedOut.length > 0) {
+      messageApi.warning(`扫描超时: ${timedOut.join('、')}`);
+    }
+    if (failed.length > 0) {
+      messageApi.warning(`扫描失败: ${failed.join('、')}`);
+    }
+    if (scanReport.extract_timed_out > 0) {
+      messageApi.warning(
+        `${scanReport.extract_timed_out} 个图标提取超时，已显示默认图标`,
+      );
+    }
+    if (scanReport.info_timed_out > 0) {
+      messageApi.warning(
+        `${scanReport.info_timed_out} 个程序读取版本与签名信息超时`,
+      );
+    }
+  }, [scanReport]);
+
   // 快捷键处理：Esc 退出应用
   useEffect(() => {
     function handleKeyDown(e: KeyboardEvent) {
@@ -89,6 +131,53 @@ const App: React.FC = () => {
     }
   }
 
+  /**
+   * 卸载条目对应的产品：先确认产品与卸载命令，完成后报告退出码并刷新列表
+   * @param icon 图标对象
+   */
+  async function handleUninstall(icon: IconType) {
+    let target: UninstallTarget;
+    try {
+      target = await findUninstallTarget(icon);
+    } catch (error) {
+      messageApi.error(`无法卸载 ${icon.name}: ${error}`);
+      return;
+    }
+
+    const confirmed = await modalApi.confirm({
+      title: `卸载 ${target.product}`,
+      content: (
+        <div>
+          {target.version && <div>版本: {target.version}</div>}
+          {target.publisher && <div>发布者: {target.publisher}</div>}
+          <div style={{ wordBreak: 'break-all' }}>命令: {target.command}</div>
+        </div>
+      ),
+      okText: '卸载',
+      okButtonProps: { danger: true },
+      cancelText: '取消',
+    });
+    if (!confirmed) return;
+
+    const hide = messageApi.loading(`正在卸载 ${target.product}...`, 0);
+    try {
+      const outcome = await uninstallEntry(target);
+      if (outcome.success) {
+        messageApi.success(`已卸载: ${outcome.product}`);
+      } else {
+        messageApi.warning(
+          `${outcome.product} 卸载未完成，退出码: ${outcome.exit_code ?? '未知'}`,
+        );
+      }
+      reload();
+    } catch (error) {
+      console.error('卸载失败:', error);
+      messageApi.error(`${error}`);
+    } finally {
+      hide();
+    }
+  }
+
   /**
    * 获取图标右键菜单配置
    * @param icon 图标对象
@@ -138,6 +227,12 @@ const App: React.FC = () => {
             }
           },
         },
+        {
+          key: 'uninstall',
+          label: '卸载',
+          disabled: isOpening,
+          onClick: () => handleUninstall(icon),
+        },
         {
           key: 'info',
           label: '详细信息',
@@ -184,6 +279,7 @@ const App: React.FC = () => {
   return (
     <Layout style={{ height: '100vh' }}>
       {contextHolder}
+      {modalContextHolder}
 
       <HeaderBar
         searchValue={searchValue}
@@ -1,3 +1,4 @@
+import { SafetyCertificateFilled } from '@ant-design/icons';
 import { Button, Dropdown, Flex, Image, Spin, Tooltip, Typography } from 'antd';
 import React from 'react';
 import type { IconType } from '../types/icon';
@@ -50,10 +51,23 @@ export const IconTile: React.FC<IconTileProps> = ({
         <Tooltip title="">
           <Button
             type="text"
-            style={{ width: tileSide, height: tileSide }}
+            style={{ width: tileSide, height: tileSide, position: 'relative' }}
             onClick={() => onClick(icon)}
             disabled={isOpening}
           >
+            {/* 需要管理员权限的程序显示盾牌标记 */}
+            {icon.requires_elevation && (
+              <SafetyCertificateFilled
+                title="启动时需要管理员权限"
+                style={{
+                  position: 'absolute',
+                  top: 4,
+                  right: 4,
+                  color: '#faad14',
+                  fontSize: Math.max(12, iconImageSide / 6),
+                }}
+              />
+            )}
             <Flex gap="small" vertical align="center" style={{ width: '100%' }}>
               <Image
                 preview={false}
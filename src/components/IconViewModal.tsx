@@ -58,11 +58,14 @@ export const IconViewModal = ({ open, icon, onClose }: IconViewModalProps) => {
             <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
               位置: {icon.file_path}
             </Typography.Text>
-            {icon.source_name && (
+            {(icon.source_names?.length || icon.source_name) && (
               <>
                 <br />
                 <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
-                  软件来源: {String(icon.source_name)}
+                  软件来源:{' '}
+                  {icon.source_names?.length
+                    ? icon.source_names.join('、')
+                    : String(icon.source_name)}
                 </Typography.Text>
               </>
             )}
@@ -74,6 +77,81 @@ export const IconViewModal = ({ open, icon, onClose }: IconViewModalProps) => {
                 </Typography.Text>
               </>
             )}
+            {icon.publisher && (
+              <>
+                <br />
+                <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
+                  发布者: {icon.publisher}
+                </Typography.Text>
+              </>
+            )}
+            {icon.signer && (
+              <>
+                <br />
+                <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
+                  签名者: {icon.signer}
+                  {icon.signature_status !== 'verified' && '（未校验）'}
+                </Typography.Text>
+              </>
+            )}
+            {icon.display_version && (
+              <>
+                <br />
+                <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
+                  版本: {icon.display_version}
+                </Typography.Text>
+              </>
+            )}
+            {icon.install_date && (
+              <>
+                <br />
+                <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
+                  安装日期: {icon.install_date}
+                </Typography.Text>
+              </>
+            )}
+            {icon.estimated_size && (
+              <>
+                <br />
+                <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
+                  占用空间: {(icon.estimated_size / 1024).toFixed(2)} MB
+                </Typography.Text>
+              </>
+            )}
+            {icon.url_info_about && (
+              <>
+                <br />
+                <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
+                  主页: {icon.url_info_about}
+                </Typography.Text>
+              </>
+            )}
+            {icon.deployment_url && (
+              <>
+                <br />
+                <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
+                  部署地址: {icon.deployment_url}
+                </Typography.Text>
+              </>
+            )}
+            {icon.architecture && (
+              <>
+                <br />
+                <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
+                  架构: {icon.architecture}
+                  {icon.subsystem === 'console' && ' (控制台程序，在终端中启动)'}
+                </Typography.Text>
+              </>
+            )}
+            {icon.execution_level && (
+              <>
+                <br />
+                <Typography.Text type="secondary" style={{ fontSize: '12px' }}>
+                  权限级别: {icon.execution_level}
+                  {icon.requires_elevation && ' (启动时需要管理员权限)'}
+                </Typography.Text>
+              </>
+            )}
             {icon.file_size && (
               <>
                 <br />
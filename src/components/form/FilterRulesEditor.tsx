@@ -0,0 +1,99 @@
+import { invoke } from '@tauri-apps/api/core';
+import { Button, Input, message, Space, Tag, Tooltip, Typography } from 'antd';
+import { useEffect, useState } from 'react';
+import { useConfigSync } from '../../sync/configSync.ts';
+import type { IconType } from '../../types/icon.ts';
+import type { FilterRule, RuleHitSummary } from '../../types/rule.ts';
+
+export interface FilterRulesEditorProps {
+  // 图标列表变化时（重新扫描后）刷新命中统计
+  desktopIcons: IconType[];
+}
+
+/**
+ * 过滤规则编辑器：以 JSON 编辑自定义规则，并显示各规则的命中数
+ */
+export const FilterRulesEditor = ({ desktopIcons }: FilterRulesEditorProps) => {
+  const { data: config, sync: syncConfig } = useConfigSync();
+  const [messageApi, contextHolder] = message.useMessage();
+  const [text, setText] = useState('');
+  const [defaultRules, setDefaultRules] = useState<FilterRule[]>([]);
+  const [hits, setHits] = useState<RuleHitSummary[]>([]);
+
+  // 配置变化时（如跨窗口同步）更新编辑框
+  useEffect(() => {
+    setText(JSON.stringify(config.filterRules ?? [], null, 2));
+  }, [JSON.stringify(config.filterRules)]);
+
+  useEffect(() => {
+    invoke<FilterRule[]>('get_default_filter_rules')
+      .then(setDefaultRules)
+      .catch((e) => console.warn('获取内置规则失败:', e));
+  }, []);
+
+  useEffect(() => {
+    invoke<RuleHitSummary[]>('get_rule_hits')
+      .then(setHits)
+      .catch((e) => console.warn('获取规则命中统计失败:', e));
+  }, [desktopIcons]);
+
+  async function saveRules() {
+    try {
+      const rules = JSON.parse(text || '[]');
+      if (!Array.isArray(rules)) {
+        messageApi.error('规则必须是数组');
+        return;
+      }
+      await syncConfig('filterRules', rules);
+      messageApi.success('规则已保存，将在下次扫描时生效');
+    } catch (error) {
+      console.error('保存规则失败:', error);
+      messageApi.error('规则不是有效的 JSON');
+    }
+  }
+
+  return (
+    <Space direction="vertical" style={{ width: '100%' }}>
+      {contextHolder}
+      <Input.TextArea
+        value={text}
+        onChange={(e) => setText(e.target.value)}
+        autoSize={{ minRows: 3, maxRows: 12 }}
+        placeholder='[{ "id": "my.rule", "name": "示例", "file_name": "*-cli.exe" }]'
+        style={{ fontFamily: 'monospace' }}
+      />
+      <Space wrap>
+        <Button size="small" onClick={saveRules}>
+          保存规则
+        </Button>
+        <Tooltip
+          title={defaultRules.map((rule) => (
+            <div key={rule.id}>{rule.name}</div>
+          ))}
+        >
+          <Typography.Text type="secondary">
+            内置规则 {defaultRules.length} 条
+          </Typography.Text>
+        </Tooltip>
+      </Space>
+      {hits.length === 0 ? (
+        <Typography.Text type="secondary">暂无被过滤的条目</Typography.Text>
+      ) : (
+        <Space wrap>
+          {hits.map((hit) => (
+            <Tooltip
+              key={hit.rule_id}
+              title={hit.samples.map((path) => (
+                <div key={path}>{path}</div>
+              ))}
+            >
+              <Tag>
+                {hit.rule_name || hit.rule_id}: {hit.count}
+              </Tag>
+            </Tooltip>
+          ))}
+        </Space>
+      )}
+    </Space>
+  );
+};
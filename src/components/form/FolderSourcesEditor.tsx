@@ -0,0 +1,173 @@
+import {
+  Button,
+  Card,
+  Checkbox,
+  Input,
+  InputNumber,
+  message,
+  Select,
+  Space,
+} from 'antd';
+import { type } from '@tauri-apps/plugin-os';
+import { useEffect, useState } from 'react';
+import { useConfigSync } from '../../sync/configSync.ts';
+import type { FolderSource } from '../../types/source.ts';
+
+const FILE_TYPE_OPTIONS = [
+  { value: 'exe', label: '程序 (.exe)' },
+  { value: 'lnk', label: '快捷方式 (.lnk)' },
+  { value: 'url', label: '网址 (.url)' },
+  { value: 'desktop', label: '.desktop' },
+  { value: 'app', label: '应用包 (.app)' },
+  { value: 'script', label: '脚本' },
+];
+
+/** 当前平台可收集的文件类型，.desktop 只能在 Linux 上启动 */
+function fileTypeOptions() {
+  return FILE_TYPE_OPTIONS.filter(
+    (option) => option.value !== 'desktop' || type() === 'linux',
+  );
+}
+
+function newSourceId(): string {
+  return `${Date.now().toString(36)}${Math.random().toString(36).slice(2, 6)}`;
+}
+
+/**
+ * 目录来源编辑器：添加用户自己的目录作为软件来源，保存后参与扫描与去重
+ */
+export const FolderSourcesEditor = () => {
+  const { data: config, sync: syncConfig } = useConfigSync();
+  const [messageApi, contextHolder] = message.useMessage();
+  const [drafts, setDrafts] = useState<FolderSource[]>([]);
+
+  // 配置变化时（如跨窗口同步）更新编辑中的来源；没有标识的旧来源补上标识并保存
+  useEffect(() => {
+    const sources = config.folderSources ?? [];
+    if (sources.some((s) => !s.id)) {
+      void syncConfig(
+        'folderSources',
+        sources.map((s) => (s.id ? s : { ...s, id: newSourceId() })),
+      );
+      return;
+    }
+    setDrafts(sources);
+  }, [JSON.stringify(config.folderSources)]);
+
+  function update(index: number, patch: Partial<FolderSource>) {
+    setDrafts(drafts.map((d, i) => (i === index ? { ...d, ...patch } : d)));
+  }
+
+  function addSource() {
+    setDrafts([
+      ...drafts,
+      {
+        id: newSourceId(),
+        name: '',
+        root: '',
+        max_depth: 2,
+        include: [],
+        exclude: [],
+        file_types: fileTypeOptions().map((option) => option.value),
+      },
+    ]);
+  }
+
+  async function saveSources() {
+    const sources = drafts.filter((d) => d.root.trim() !== '');
+    try {
+      // 新添加的来源默认选中
+      const previous = (config.folderSources ?? []).map((s) => s.id);
+      const added = sources
+        .filter((s) => !previous.includes(s.id))
+        .map((s) => `folder:${s.id}`);
+      await syncConfig('folderSources', sources);
+      if (added.length > 0) {
+        await syncConfig('iconSources', [...config.iconSources, ...added]);
+      }
+      messageApi.success('目录来源已保存');
+    } catch (error) {
+      console.error('保存目录来源失败:', error);
+      messageApi.error('保存失败');
+    }
+  }
+
+  return (
+    <Space direction="vertical" style={{ width: '100%' }}>
+      {contextHolder}
+      {drafts.map((source, index) => (
+        <Card
+          key={source.id}
+          size="small"
+          extra={
+            <Button
+              type="link"
+              size="small"
+              danger
+              onClick={() => setDrafts(drafts.filter((_, i) => i !== index))}
+            >
+              删除
+            </Button>
+          }
+          title={
+            <Input
+              size="small"
+              variant="borderless"
+              placeholder="显示名称"
+              value={source.name}
+              onChange={(e) => update(index, { name: e.target.value })}
+            />
+          }
+        >
+          <Space direction="vertical" style={{ width: '100%' }}>
+            <Space wrap>
+              <Input
+                size="small"
+                style={{ width: 280 }}
+                addonBefore="根目录"
+                placeholder="D:\Tools 或 ~/opt"
+                value={source.root}
+                onChange={(e) => update(index, { root: e.target.value })}
+              />
+              <InputNumber
+                size="small"
+                addonBefore="深度"
+                min={1}
+                max={8}
+                value={source.max_depth}
+                onChange={(value) => update(index, { max_depth: value ?? 2 })}
+              />
+            </Space>
+            <Checkbox.Group
+              options={fileTypeOptions()}
+              value={source.file_types}
+              onChange={(value) => update(index, { file_types: value })}
+            />
+            <Select
+              mode="tags"
+              size="small"
+              placeholder="包含的文件名，如 *.exe（留空不限制）"
+              value={source.include}
+              onChange={(value: string[]) => update(index, { include: value })}
+            />
+            <Select
+              mode="tags"
+              size="small"
+              placeholder="排除的文件或目录名，如 node_modules"
+              value={source.exclude}
+              onChange={(value: string[]) => update(index, { exclude: value })}
+            />
+          </Space>
+        </Card>
+      ))}
+      <Space wrap>
+        <Button size="small" onClick={addSource}>
+          添加目录
+        </Button>
+        <Button size="small" type="primary" onClick={saveSources}>
+          保存目录来源
+        </Button>
+      </Space>
+    </Space>
+  );
+};
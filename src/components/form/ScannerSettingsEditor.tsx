@@ -0,0 +1,102 @@
+import { Checkbox, Input, InputNumber, Select, Space, Typography } from 'antd';
+import { useConfigSync } from '../../sync/configSync.ts';
+import type { IconSourceInfo, SettingField } from '../../types/source.ts';
+
+export interface ScannerSettingsEditorProps {
+  // 只显示这些来源的设置项
+  sources: IconSourceInfo[];
+}
+
+/**
+ * 扫描器设置编辑器：按各来源声明的设置项渲染输入控件，留空使用默认值
+ */
+export const ScannerSettingsEditor = ({
+  sources,
+}: ScannerSettingsEditorProps) => {
+  const { data: config, sync: syncConfig } = useConfigSync();
+  const withSettings = sources.filter((source) => source.settings.length > 0);
+
+  function setValue(sourceId: string, key: string, value: unknown) {
+    const all = { ...(config.scannerSettings ?? {}) };
+    const values = { ...(all[sourceId] ?? {}) };
+    if (value === null || value === undefined || value === '') {
+      delete values[key];
+    } else {
+      values[key] = value;
+    }
+    all[sourceId] = values;
+    void syncConfig('scannerSettings', all);
+  }
+
+  function renderField(sourceId: string, field: SettingField) {
+    const value = config.scannerSettings?.[sourceId]?.[field.key];
+    switch (field.kind) {
+      case 'depth':
+        return (
+          <InputNumber
+            size="small"
+            min={field.min}
+            max={field.max}
+            placeholder={String(field.default)}
+            value={typeof value === 'number' ? value : null}
+            onChange={(v) => setValue(sourceId, field.key, v)}
+          />
+        );
+      case 'bool':
+        return (
+          <Checkbox
+            checked={typeof value === 'boolean' ? value : field.default}
+            onChange={(e) => setValue(sourceId, field.key, e.target.checked)}
+          />
+        );
+      case 'path':
+        // 回车或点击按钮后才同步，避免输入过程中反复扫描
+        return (
+          <Input.Search
+            key={typeof value === 'string' ? value : ''}
+            size="small"
+            defaultValue={typeof value === 'string' ? value : ''}
+            placeholder={field.default ?? ''}
+            enterButton="应用"
+            allowClear
+            onSearch={(v) => setValue(sourceId, field.key, v.trim())}
+          />
+        );
+      case 'filters':
+        return (
+          <Select
+            mode="tags"
+            size="small"
+            style={{ minWidth: 200 }}
+            placeholder="*.tmp"
+            value={Array.isArray(value) ? (value as string[]) : field.default}
+            onChange={(v: string[]) => setValue(sourceId, field.key, v)}
+          />
+        );
+    }
+  }
+
+  if (withSettings.length === 0) {
+    return (
+      <Typography.Text type="secondary">所选来源没有设置项</Typography.Text>
+    );
+  }
+
+  return (
+    <Space direction="vertical">
+      {withSettings.map((source) => (
+        <Space key={source.id} wrap>
+          <Typography.Text strong>
+            {source.icon} {source.name}
+          </Typography.Text>
+          {source.settings.map((field) => (
+            <Space key={field.key} size="small" title={field.description}>
+              <Typography.Text>{field.label}</Typography.Text>
+              {renderField(source.id, field)}
+            </Space>
+          ))}
+        </Space>
+      ))}
+    </Space>
+  );
+};
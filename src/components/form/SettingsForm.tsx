@@ -3,6 +3,8 @@ import {
   Checkbox,
   ColorPicker,
   Form,
+  Input,
+  InputNumber,
   message,
   Segmented,
   Select,
@@ -14,15 +16,12 @@ import { useEffect } from 'react';
 import { ThemeModeEnum } from '../../enums/ThemeModeEnum.ts';
 import { useConfigSync } from '../../sync/configSync.ts';
 import type { IconType } from '../../types/icon.ts';
+import type { IconSourceInfo } from '../../types/source.ts';
 import { syncValuesConfig } from '../../utils/formUtils.ts';
 import { LabeledSliderInput } from '../LabeledSliderInput.tsx';
-
-export interface IconSourceInfo {
-  id: string;
-  name: string;
-  description: string;
-  icon: string;
-}
+import { FilterRulesEditor } from './FilterRulesEditor.tsx';
+import { FolderSourcesEditor } from './FolderSourcesEditor.tsx';
+import { ScannerSettingsEditor } from './ScannerSettingsEditor.tsx';
 
 export interface Props {
   availableSources: IconSourceInfo[];
@@ -43,6 +42,7 @@ export const SettingsForm = ({ availableSources, desktopIcons }: Props) => {
   const isMac = availableSources.some(
     (s) => s.id === 'applications' || s.id === 'spotlight',
   );
+  const hasWsl = availableSources.some((s) => s.id === 'wsl_apps');
 
   const windowsOptions = [
     { value: 'smart', label: '智能方式 (ImageList -> PrivateExtractIcons)' },
@@ -79,6 +79,108 @@ export const SettingsForm = ({ availableSources, desktopIcons }: Props) => {
           ))}
         </Checkbox.Group>
       </Form.Item>
+      <Form.Item label="自定义目录">
+        <FolderSourcesEditor />
+      </Form.Item>
+      <Form.Item label="来源优先级">
+        {/* 同一应用出现在多个来源时保留优先级最高的，留空使用来源的默认优先级 */}
+        <Space wrap>
+          {availableSources
+            .filter((source) => config.iconSources.includes(source.id))
+            .map((source) => (
+              <InputNumber
+                key={source.id}
+                size="small"
+                addonBefore={`${source.icon} ${source.name}`}
+                placeholder={String(source.default_priority)}
+                value={config.sourcePriorities?.[source.id]}
+                onChange={(value) => {
+                  const priorities = { ...(config.sourcePriorities ?? {}) };
+                  if (value === null) {
+                    delete priorities[source.id];
+                  } else {
+                    priorities[source.id] = value;
+                  }
+                  void syncConfig('sourcePriorities', priorities);
+                }}
+              />
+            ))}
+        </Space>
+      </Form.Item>
+      <Form.Item label="来源设置">
+        <ScannerSettingsEditor
+          sources={availableSources.filter((source) =>
+            config.iconSources.includes(source.id),
+          )}
+        />
+      </Form.Item>
+      <Form.Item label="扫描超时">
+        {/* 超时的来源或图标不再等待，结果中提示；0 表示不限制 */}
+        <Space wrap>
+          <Form.Item name="scannerTimeoutSecs" noStyle>
+            <InputNumber
+              min={0}
+              addonBefore="单个来源"
+              addonAfter="秒"
+              title="单个来源超过此时间仍未扫描完成时跳过该来源"
+            />
+          </Form.Item>
+          <Form.Item name="extractTimeoutSecs" noStyle>
+            <InputNumber
+              min={0}
+              addonBefore="图标提取"
+              addonAfter="秒"
+              title="单个图标源超过此时间仍未提取完成时显示默认图标"
+            />
+          </Form.Item>
+        </Space>
+      </Form.Item>
+      {!isMac && (
+        <Form.Item name="guiOnly" label="目录扫描" valuePropName="checked">
+          <Checkbox
+            title="Program Files 与 AppData 中只保留图形界面程序，跳过控制台工具和 16 位程序"
+          >
+            仅显示 GUI 程序
+          </Checkbox>
+        </Form.Item>
+      )}
+      <Form.Item label="离线 Windows 根目录">
+        {/* 回车或点击按钮后才同步，避免输入过程中反复扫描 */}
+        <Input.Search
+          key={config.windowsRoot}
+          defaultValue={config.windowsRoot}
+          placeholder="/mnt/c"
+          title="扫描挂载的 Windows 分区而不是本机，留空则扫描本机"
+          enterButton="扫描"
+          allowClear
+          onSearch={(value) => syncConfig('windowsRoot', value.trim())}
+        />
+      </Form.Item>
+      {hasWsl && (
+        <Form.Item label="WSL 目录">
+          <Input.Search
+            key={config.wslRoot}
+            defaultValue={config.wslRoot}
+            placeholder="\\wsl$"
+            title="读取此目录下的发行版（如发行版文件系统的本地副本），留空则通过 \\wsl$ 访问"
+            enterButton="应用"
+            allowClear
+            onSearch={(value) => syncConfig('wslRoot', value.trim())}
+          />
+        </Form.Item>
+      )}
+      <Form.Item
+        name="useDefaultRules"
+        label="噪声过滤"
+        valuePropName="checked"
+      >
+        <Checkbox title="隐藏卸载程序、更新程序、崩溃报告程序、运行库安装包等">
+          启用内置过滤规则
+        </Checkbox>
+      </Form.Item>
+      <Form.Item label="自定义规则">
+        <FilterRulesEditor desktopIcons={desktopIcons} />
+      </Form.Item>
       <Form.Item name="themeMode" label="主题模式">
         <Segmented options={Object.values(ThemeModeEnum)} />
       </Form.Item>
@@ -29,6 +29,26 @@ export const DEFAULT_VALUES = {
   iconImageSide: 170,
   // 已隐藏的图标
   hideList: [],
+  // 目录扫描仅保留 GUI 程序
+  guiOnly: false,
+  // 是否启用内置过滤规则
+  useDefaultRules: true,
+  // 自定义过滤规则
+  filterRules: [],
+  // 离线扫描的 Windows 根目录，为空时扫描本机
+  windowsRoot: '',
+  // WSL 发行版所在目录，为空时使用 \\wsl$
+  wslRoot: '',
+  // 去重时的来源优先级覆盖，键为来源 ID
+  sourcePriorities: {},
+  // 各扫描器的设置值，键为来源 ID 与设置项 key
+  scannerSettings: {},
+  // 用户添加的目录来源
+  folderSources: [],
+  // 单个来源的扫描超时（秒），0 表示不限制
+  scannerTimeoutSecs: 60,
+  // 每批图标提取的超时（秒），0 表示不限制
+  extractTimeoutSecs: 30,
 };
 
 export const DEFAULT_VALUES_MAC = {
@@ -54,4 +74,24 @@ export const DEFAULT_VALUES_MAC = {
   iconImageSide: 100,
   // 已隐藏的图标
   hideList: [],
+  // 目录扫描仅保留 GUI 程序
+  guiOnly: false,
+  // 是否启用内置过滤规则
+  useDefaultRules: true,
+  // 自定义过滤规则
+  filterRules: [],
+  // 离线扫描的 Windows 根目录，为空时扫描本机
+  windowsRoot: '',
+  // WSL 发行版所在目录，为空时使用 \\wsl$
+  wslRoot: '',
+  // 去重时的来源优先级覆盖，键为来源 ID
+  sourcePriorities: {},
+  // 各扫描器的设置值，键为来源 ID 与设置项 key
+  scannerSettings: {},
+  // 用户添加的目录来源
+  folderSources: [],
+  // 单个来源的扫描超时（秒），0 表示不限制
+  scannerTimeoutSecs: 60,
+  // 每批图标提取的超时（秒），0 表示不限制
+  extractTimeoutSecs: 30,
 };
@@ -1,11 +1,27 @@
-import { invoke } from '@tauri-apps/api/core';
+import { Channel, invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { type } from '@tauri-apps/plugin-os';
 import { useEffect, useState } from 'react';
-import type { IconSourceInfo } from '../components/form/SettingsForm';
+import type { IconSourceInfo } from '../types/source.ts';
 import { useConfigSync } from '../sync/configSync';
-import type { IconType } from '../types/icon';
+import type {
+  CatalogChange,
+  IconType,
+  IconUpdate,
+  ScanEvent,
+  ScanPhase,
+  ScanReport,
+} from '../types/icon';
 import { sortIcons } from '../utils/appUtils';
 
+// 进度提示中的阶段名称
+const PHASE_LABELS: Record<ScanPhase, string> = {
+  scanning: '正在扫描来源',
+  deduping: '正在去重',
+  inspecting: '正在读取程序信息',
+  extracting: '正在提取图标',
+};
+
 interface UseAppIconsResult {
   desktopIcons: IconType[];
   filteredIcons: IconType[];
@@ -13,6 +29,8 @@ interface UseAppIconsResult {
   loadingTip: string;
   isWindows: boolean;
   availableSources: IconSourceInfo[];
+  scanReport: ScanReport | null; // 最近一次完成的扫描
+  reload: () => void;
 }
 
 /**
@@ -31,11 +49,28 @@ export function useAppIcons(
   const [availableSources, setAvailableSources] = useState<IconSourceInfo[]>(
     [],
   );
+  const [scanReport, setScanReport] = useState<ScanReport | null>(null);
+  // 递增以重新扫描（如卸载程序之后）
+  const [reloadCount, setReloadCount] = useState(0);
 
   const { data: config } = useConfigSync();
-  const { orderMode, iconMethod, iconSources } = config;
+  const {
+    orderMode,
+    iconMethod,
+    iconSources,
+    guiOnly,
+    useDefaultRules,
+    filterRules,
+    windowsRoot,
+    wslRoot,
+    sourcePriorities,
+    scannerSettings,
+    folderSources,
+    scannerTimeoutSecs,
+    extractTimeoutSecs,
+  } = config;
 
-  // 检测平台并获取可用来源
+  // 检测平台（可用来源在加载图标前获取）
   useEffect(() => {
     async function initSources() {
       setLoadingTip('正在获取可用来源...');
@@ -45,10 +80,8 @@ export function useAppIcons(
         const isWin = osType === 'windows'; // 在 Tauri 中，osType 是准确的，不需要 !isMac 判断
         setIsWindows(isWin);
 
-        const sources = await invoke<IconSourceInfo[]>('get_available_sources');
-        setAvailableSources(sources);
-
-        if (isMac) {
+        // 扫描离线 Windows 分区时保留 Windows 来源
+        if (isMac && !useConfigSync.getState().data.windowsRoot) {
           const currentSources = useConfigSync.getState().data.iconSources;
           const hasWindowsSources = currentSources.some((s) =>
             [
@@ -58,6 +91,7 @@ export function useAppIcons(
               'start_menu',
               'common_start_menu',
               'installed_programs',
+              'app_paths',
               'program_files',
               'program_files_x86',
               'quick_launch',
@@ -84,34 +118,148 @@ export function useAppIcons(
 
   // 加载图标
   useEffect(() => {
+    // 设置变化后取消上一次扫描，并忽略它仍在发送的结果
+    let stale = false;
+    let scanId: number | null = null;
+
+    // 扫描结果逐步到达：先是各来源的条目，再是去重后的列表，最后分批补上图标
+    function handleScanEvent(message: ScanEvent) {
+      if (message.event === 'started') {
+        scanId = message.data.scan_id;
+        if (stale) void invoke('cancel_scan', { scanId });
+        return;
+      }
+      if (stale) return;
+      switch (message.event) {
+        case 'progress': {
+          const { phase, done, total } = message.data;
+          setLoadingTip(`${PHASE_LABELS[phase]} ${done}/${total}`);
+          break;
+        }
+        case 'source':
+          setDesktopIcons((icons) => icons.concat(message.data.icons));
+          setLoading(false);
+          break;
+        case 'merged':
+          setDesktopIcons(message.data.icons);
+          break;
+        case 'icons': {
+          // 按位置对应到去重后的列表，同一路径的多个条目各自更新
+          const updates = new Map<number, IconUpdate>(
+            message.data.icons.map((update) => [update.index, update]),
+          );
+          setDesktopIcons((icons) =>
+            icons.map((icon, index) => {
+              const update = updates.get(index);
+              if (!update) return icon;
+              const { icon_base64, icon_width, icon_height } = update;
+              return { ...icon, icon_base64, icon_width, icon_height };
+            }),
+          );
+          break;
+        }
+      }
+    }
+
     async function loadIcons() {
       setLoading(true);
       setLoadingTip('正在加载图标...');
       try {
-        let icons: IconType[] = [];
+        // 扫描设置需要在扫描前同步到后端
+        await invoke('set_scan_settings', {
+          settings: {
+            gui_only: !!guiOnly,
+            use_default_rules: useDefaultRules ?? true,
+            filter_rules: filterRules ?? [],
+            windows_root: windowsRoot || null,
+            wsl_root: wslRoot || null,
+            source_priorities: sourcePriorities ?? {},
+            scanner_settings: scannerSettings ?? {},
+            folder_sources: folderSources ?? [],
+            scanner_timeout_secs: scannerTimeoutSecs ?? 60,
+            extract_timeout_secs: extractTimeoutSecs ?? 30,
+          },
+        });
 
-        if (iconSources.length === 1) {
-          icons = await invoke<IconType[]>('get_icons_from_source', {
-            source: iconSources[0],
-            method: iconMethod === 'default' ? null : iconMethod,
-          });
-        } else if (iconSources.length > 1) {
-          icons = await invoke<IconType[]>('get_icons_from_multiple_sources', {
-            sources: iconSources,
+        // 可用来源取决于是否扫描离线分区，需在同步设置后获取
+        const available = await invoke<IconSourceInfo[]>(
+          'get_available_sources',
+        );
+        setAvailableSources(available);
+
+        // 离线分区只有 Windows 来源，Linux 只有 Wine 来源，已选来源都不可用时扫描全部来源
+        let sources = iconSources;
+        if (windowsRoot || type() === 'linux') {
+          const ids = available.map((source) => source.id);
+          const matched = iconSources.filter((id) => ids.includes(id));
+          sources = matched.length > 0 ? matched : ids;
+        }
+
+        setDesktopIcons([]);
+        if (sources.length > 0) {
+          const onEvent = new Channel<ScanEvent>();
+          onEvent.onmessage = handleScanEvent;
+          const report = await invoke<ScanReport>('stream_source_icons', {
+            sources,
             method: iconMethod === 'default' ? null : iconMethod,
+            onEvent,
           });
+          if (stale || report.cancelled) return;
+          setScanReport(report);
         }
+        if (stale) return;
 
-        setDesktopIcons(icons);
+        // 监视本次扫描的来源，变化时增量更新
+        invoke<number>('start_catalog_watch').catch((e) =>
+          console.warn('监视来源变化失败:', e),
+        );
       } catch (error) {
         console.error('获取图标失败:', error);
       } finally {
-        setLoading(false);
+        if (!stale) setLoading(false);
       }
     }
 
     void loadIcons();
-  }, [iconMethod, JSON.stringify(iconSources)]);
+    return () => {
+      stale = true;
+      if (scanId !== null) void invoke('cancel_scan', { scanId });
+    };
+  }, [
+    iconMethod,
+    guiOnly,
+    useDefaultRules,
+    JSON.stringify(filterRules),
+    JSON.stringify(iconSources),
+    windowsRoot,
+    wslRoot,
+    JSON.stringify(sourcePriorities),
+    JSON.stringify(scannerSettings),
+    JSON.stringify(folderSources),
+    scannerTimeoutSecs,
+    extractTimeoutSecs,
+    reloadCount,
+  ]);
+
+  // 来源变化时按（更新前的）file_path 合并增量结果
+  useEffect(() => {
+    const unlisten = listen<CatalogChange>('catalog-changed', (event) => {
+      const { added, updated, removed } = event.payload;
+      setDesktopIcons((icons) => {
+        const replaced = new Map<string, IconType>(
+          updated.map((update) => [update.previous_file_path, update.icon]),
+        );
+        return icons
+          .filter((icon) => !removed.includes(icon.file_path))
+          .map((icon) => replaced.get(icon.file_path) ?? icon)
+          .concat(added);
+      });
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+      void invoke('stop_catalog_watch');
+    };
+  }, []);
 
   // 搜索过滤和排序逻辑
   function getFilteredIcons(): IconType[] {
@@ -125,15 +273,17 @@ export function useAppIcons(
       filtered = desktopIcons.filter((icon) => {
         const name = icon.name || '';
         const target = icon.target_path || '';
+        const aliases = icon.aliases || [];
         return (
           name.toLowerCase().includes(lowerSearch) ||
-          target.toLowerCase().includes(lowerSearch)
+          target.toLowerCase().includes(lowerSearch) ||
+          aliases.some((alias) => alias.toLowerCase().includes(lowerSearch))
         );
       });
     }
 
-    // 2. 平台特定的额外过滤
-    if (isWindows && filtered.length > 0) {
+    // 2. 平台特定的额外过滤（离线分区中同样是 Windows 程序）
+    if ((isWindows || !!windowsRoot) && filtered.length > 0) {
       filtered = filtered.filter((icon) => {
         const target = (icon.target_path || '').toLowerCase();
         const file = (icon.file_path || '').toLowerCase();
@@ -144,13 +294,16 @@ export function useAppIcons(
           '.msi',
           '.com',
           '.lnk',
+          '.appref-ms',
         ];
 
         const isExecutable = executableExtensions.some(
           (ext) => target.endsWith(ext) || file.endsWith(ext),
         );
 
-        return isExecutable || icon.file_type === 'UWP App';
+        return (
+          isExecutable || icon.file_type === 'UWP App' || !!icon.wsl_distro
+        );
       });
     }
 
@@ -191,5 +344,7 @@ export function useAppIcons(
     loadingTip,
     isWindows,
     availableSources,
+    scanReport,
+    reload: () => setReloadCount((count) => count + 1),
   };
 }
@@ -1,3 +1,6 @@
+import type { FilterRule } from '../../types/rule.ts';
+import type { FolderSource } from '../../types/source.ts';
+
 export interface ConfigItem {
   // 图标源
   iconSources: string[];
@@ -21,4 +24,24 @@ export interface ConfigItem {
   iconImageSide: number;
   // 隐藏图标列表
   hideList: string[];
+  // 目录扫描仅保留 GUI 程序
+  guiOnly: boolean;
+  // 是否启用内置过滤规则
+  useDefaultRules: boolean;
+  // 自定义过滤规则
+  filterRules: FilterRule[];
+  // 离线扫描的 Windows 根目录，为空时扫描本机
+  windowsRoot: string;
+  // WSL 发行版所在目录，为空时使用 \\wsl$
+  wslRoot: string;
+  // 去重时的来源优先级覆盖，键为来源 ID
+  sourcePriorities: Record<string, number>;
+  // 各扫描器的设置值，键为来源 ID 与设置项 key
+  scannerSettings: Record<string, Record<string, unknown>>;
+  // 用户添加的目录来源
+  folderSources: FolderSource[];
+  // 单个来源的扫描超时（秒），0 表示不限制
+  scannerTimeoutSecs: number;
+  // 每批图标提取的超时（秒），0 表示不限制
+  extractTimeoutSecs: number;
 }
@@ -26,8 +26,123 @@ export interface IconType {
   hotkey?: string; // 快捷键
   show_command?: string; // 运行方式（正常、最小化、最大化）
   source_name?: string; // 软件来源名称（如：用户桌面、开始菜单等）
+  source_names?: string[]; // 合并后找到该条目的全部来源名称
+
+  // 可执行文件版本资源信息
+  file_description?: string; // 文件描述
+  product_name?: string; // 产品名称
+  company_name?: string; // 公司名称
+  file_version?: string; // 文件版本
+  product_version?: string; // 产品版本
+  version_strings?: Record<string, Record<string, string>>; // 各语言的 StringFileInfo 表
+  publisher?: string; // 登记的发布者（卸载项、ClickOnce）
+  signer?: string; // 签名者：签名证书主题中的 CN，状态不是 verified 时未经校验
+  // 签名状态：verified 为签名与证书链校验通过，digest_match 为摘要一致但签名未校验（非 Windows）
+  signature_status?:
+    | 'verified'
+    | 'untrusted'
+    | 'digest_match'
+    | 'hash_mismatch'
+    | 'unsigned'
+    | 'invalid'
+    | 'unsupported';
+  signer_subject?: string; // 签名证书主题
+  signer_issuer?: string; // 签名证书颁发者
+  architecture?: string; // 目标架构: x86/x64/ARM64/ARM64EC/ARM64X
+  subsystem?: string; // 子系统: gui/console/native/efi/dos/other
+  run_in_terminal?: boolean; // 控制台程序，在终端中启动
+  execution_level?: string; // 清单中的 requestedExecutionLevel
+  requires_elevation?: boolean; // 启动时需要管理员权限（UAC）
+  dpi_awareness?: string; // 清单中的 dpiAwareness/dpiAware
+  supported_os?: string[]; // 清单中声明支持的系统版本
+  deployment_url?: string; // ClickOnce 部署清单地址
+  display_version?: string; // 卸载项中的 DisplayVersion
+  install_date?: string; // 安装日期 YYYY-MM-DD
+  estimated_size?: number; // 卸载项估计占用空间（KB）
+  url_info_about?: string; // 产品主页
+  uninstall_string?: string; // 卸载命令
+  quiet_uninstall_string?: string; // 静默卸载命令
+  aliases?: string[]; // 搜索别名，如 App Paths 中登记的 chrome.exe
+  app_path_dirs?: string; // App Paths 中的 Path 值
+  wine_prefix?: string; // 所属的 Wine 前缀，通过 wine start 启动
+  wsl_distro?: string; // 所属的 WSL 发行版，通过 wsl.exe 运行 arguments 中的命令
+  bundle_id?: string; // macOS 应用的 CFBundleIdentifier
+  app_user_model_id?: string; // AppUserModelID：快捷方式属性中设置的，或应用商店应用的 ID
+}
+
+/** 条目对应的已安装产品与卸载方式 */
+export interface UninstallTarget {
+  id: number; // 登记的 ID，确认后以此执行卸载
+  product: string;
+  version?: string;
+  publisher?: string;
+  method: string; // msi/quiet_uninstall_string/uninstall_string/trash/dpkg/rpm/flatpak/snap
+  command: string; // 将要执行的命令
+}
+
+/** 卸载结果 */
+export interface UninstallOutcome {
+  product: string;
+  method: string;
+  exit_code?: number; // 卸载程序退出码
+  success: boolean;
+}
+
+/** 来源变化后目录的增量更新（catalog-changed 事件） */
+export interface CatalogChange {
+  added: IconType[];
+  updated: CatalogUpdate[];
+  removed: string[]; // 已删除条目的 file_path
+}
+
+/** 更新的条目，file_path 可能随之变化 */
+export interface CatalogUpdate {
+  previous_file_path: string; // 更新前的 file_path
+  icon: IconType;
+}
+
+/** 扫描阶段：扫描来源（按来源计数）、去重、读取程序信息（按目标计数）、提取图标（按条目计数） */
+export type ScanPhase = 'scanning' | 'deduping' | 'inspecting' | 'extracting';
+
+/** 一个条目的图标提取结果，index 为条目在去重后列表中的位置 */
+export interface IconUpdate
+  extends Pick<IconType, 'icon_base64' | 'icon_width' | 'icon_height'> {
+  index: number;
 }
 
+/** 来源的扫描结果状态 */
+export type ScanStatus =
+  | { kind: 'ok' }
+  | { kind: 'failed'; message: string }
+  | { kind: 'timed_out' }
+  | { kind: 'cancelled' };
+
+/** 扫描报告（流式扫描结束时返回） */
+export interface ScanReport {
+  scan_id: number;
+  total: number;
+  elapsed_ms: number;
+  cancelled: boolean; // 被取消时结果不完整
+  failures: { source: string; status: ScanStatus }[]; // 失败或超时的来源
+  extract_timed_out: number; // 图标提取超时的条目数
+  info_timed_out: number; // 读取可执行信息超时的目标数
+}
+
+/** 扫描图标时通过通道逐步发送的事件（与后端 ScanEvent 对应） */
+export type ScanEvent =
+  | { event: 'started'; data: { scan_id: number } } // 可通过 cancel_scan 取消
+  | {
+      event: 'progress';
+      data: { phase: ScanPhase; done: number; total: number };
+    }
+  | {
+      event: 'source';
+      data: { source: string; icons: IconType[]; status: ScanStatus }; // 未去重，尚无图标
+    }
+  | { event: 'merged'; data: { icons: IconType[] } } // 替换此前各来源的条目
+  | { event: 'icons'; data: { icons: IconUpdate[] } }
+  | { event: 'finished'; data: ScanReport };
+
 export interface IconMethod {
   id: string;
   name: string;
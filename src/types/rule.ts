@@ -0,0 +1,28 @@
+/**
+ * 噪声过滤规则（与后端 FilterRule 对应）
+ * 文本条件使用不区分大小写的通配符（* 与 ?），多个模式用 | 分隔
+ */
+export interface FilterRule {
+  id: string;
+  name: string;
+  enabled?: boolean;
+  action?: 'hide' | 'keep'; // 命中后隐藏或保留
+  file_name?: string; // 文件名
+  path_segment?: string; // 任一上级目录名
+  description?: string; // FileDescription 或 ProductName
+  company?: string; // CompanyName
+  subsystem?: string; // PE 子系统
+  min_size?: number; // 最小文件大小（字节）
+  max_size?: number; // 最大文件大小（字节）
+  sibling?: string; // 同目录下存在的文件或文件夹
+}
+
+/**
+ * 单条规则的命中统计
+ */
+export interface RuleHitSummary {
+  rule_id: string;
+  rule_name: string;
+  count: number;
+  samples: string[];
+}
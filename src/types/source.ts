@@ -0,0 +1,40 @@
+/**
+ * 扫描器设置项（与后端 SettingField 对应），值保存在 scannerSettings[来源 ID][key]
+ */
+export type SettingField = {
+  key: string;
+  label: string;
+  description: string;
+} & (
+  | { kind: 'path'; default: string | null }
+  | { kind: 'depth'; default: number; min: number; max: number }
+  | { kind: 'bool'; default: boolean }
+  | { kind: 'filters'; default: string[] } // 通配符（* 与 ?）
+);
+
+/**
+ * 软件来源（已注册的扫描器）
+ */
+export interface IconSourceInfo {
+  id: string;
+  name: string;
+  description: string;
+  icon: string;
+  cost: 'fast' | 'moderate' | 'slow'; // 预估耗时，全量扫描跳过 slow
+  default_priority: number; // 去重时的默认优先级
+  supports_watching: boolean; // 能否监视变化增量更新
+  settings: SettingField[];
+}
+
+/**
+ * 用户添加的目录来源（与后端 FolderSource 对应），扫描器 ID 为 folder:<id>
+ */
+export interface FolderSource {
+  id: string;
+  name: string; // 为空时使用根目录
+  root: string; // 可含 %VAR% 与 ~
+  max_depth: number; // 1 表示只扫描根目录
+  include: string[]; // 文件名通配符，为空时不限制
+  exclude: string[]; // 跳过名称匹配的文件与目录
+  file_types: string[]; // exe、lnk、url、desktop（Linux）、app、script，为空时收集全部类型
+}
@@ -1,6 +1,11 @@
 import { invoke } from '@tauri-apps/api/core';
 import { openPath } from '@tauri-apps/plugin-opener';
-import type { IconType } from '../types/icon';
+import { type } from '@tauri-apps/plugin-os';
+import type {
+  IconType,
+  UninstallOutcome,
+  UninstallTarget,
+} from '../types/icon';
 
 /**
  * 打开应用程序
@@ -8,6 +13,17 @@ import type { IconType } from '../types/icon';
  * @param icon 图标对象
  */
 export async function openApplication(icon: IconType): Promise<void> {
+  // Windows 由后端启动，控制台程序会在终端中运行；
+  // Linux 由后端启动，Wine 条目在所属前缀中运行，.desktop 条目按其 Exec 启动
+  if (type() === 'windows' || type() === 'linux') {
+    try {
+      return await invoke<void>('launch_entry', { icon });
+    } catch (error) {
+      console.error('打开应用程序失败:', error);
+      throw error;
+    }
+  }
+
   let filePath = icon.file_path;
 
   // 如果是 UWP 应用，使用 shell:AppsFolder 协议启动
@@ -46,3 +62,26 @@ export function sortIcons(icons: IconType[], mode: string): IconType[] {
 export async function revealFile(path: string): Promise<void> {
   await invoke<void>('reveal_file', { path });
 }
+
+/**
+ * 查找条目对应的已安装产品，用于卸载前确认
+ * @param icon 图标对象
+ */
+export async function findUninstallTarget(
+  icon: IconType,
+): Promise<UninstallTarget> {
+  return await invoke<UninstallTarget>('find_uninstall_target', { icon });
+}
+
+/**
+ * 执行已确认的卸载操作，等待卸载程序结束
+ * @param target findUninstallTarget 返回并经用户确认的卸载操作
+ */
+export async function uninstallEntry(
+  target: UninstallTarget,
+): Promise<UninstallOutcome> {
+  return await invoke<UninstallOutcome>('uninstall_entry', {
+    id: target.id,
+    command: target.command,
+  });
+}